@@ -0,0 +1,377 @@
+use anyhow::{anyhow, bail, Result};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::fmt;
+
+use super::settings::{KeybindingSettings, KeymapPreset};
+
+/// A single key press with its modifiers, normalised so that config strings
+/// and terminal events compare equal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyChord {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        // Shift is already reflected in the character (`G`, `<`) and in
+        // BackTab, and terminals disagree on whether they also report it
+        let modifiers = match code {
+            KeyCode::Char(_) | KeyCode::BackTab => modifiers - KeyModifiers::SHIFT,
+            _ => modifiers,
+        };
+        Self { code, modifiers }
+    }
+
+    /// Parse a chord such as `q`, `G`, `ctrl+c`, `shift+tab`, `alt+<` or `f1`
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut parts: Vec<&str> = text.split('+').collect();
+        // A trailing empty part means the key itself is `+`, as in `ctrl++`
+        let key = match parts.pop() {
+            Some("") if text.ends_with('+') => {
+                parts.pop();
+                "+"
+            }
+            Some(key) => key,
+            None => bail!("empty key"),
+        };
+
+        for modifier in parts {
+            modifiers |= match modifier.to_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" | "meta" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                other => bail!("unknown modifier \"{}\" in \"{}\"", other, text),
+            };
+        }
+
+        let mut chars = key.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => {
+                if modifiers.contains(KeyModifiers::SHIFT) {
+                    KeyCode::Char(c.to_ascii_uppercase())
+                } else {
+                    KeyCode::Char(c)
+                }
+            }
+            _ => match key.to_lowercase().as_str() {
+                "esc" | "escape" => KeyCode::Esc,
+                "enter" | "return" => KeyCode::Enter,
+                "tab" if modifiers.contains(KeyModifiers::SHIFT) => KeyCode::BackTab,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "space" => KeyCode::Char(' '),
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" | "ins" => KeyCode::Insert,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" | "pgup" => KeyCode::PageUp,
+                "pagedown" | "pgdn" => KeyCode::PageDown,
+                function if function.starts_with('f') => function[1..]
+                    .parse::<u8>()
+                    .ok()
+                    .filter(|n| (1..=12).contains(n))
+                    .map(KeyCode::F)
+                    .ok_or_else(|| anyhow!("unknown key \"{}\" in \"{}\"", key, text))?,
+                _ => bail!("unknown key \"{}\" in \"{}\"", key, text),
+            },
+        };
+
+        Ok(Self::new(code, modifiers))
+    }
+}
+
+impl From<KeyEvent> for KeyChord {
+    fn from(event: KeyEvent) -> Self {
+        Self::new(event.code, event.modifiers)
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "Shift+")?;
+        }
+
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) if self.modifiers.contains(KeyModifiers::CONTROL) => {
+                write!(f, "{}", c.to_ascii_uppercase())
+            }
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::BackTab => write!(f, "Shift+Tab"),
+            KeyCode::Up => write!(f, "↑"),
+            KeyCode::Down => write!(f, "↓"),
+            KeyCode::Left => write!(f, "←"),
+            KeyCode::Right => write!(f, "→"),
+            KeyCode::PageUp => write!(f, "PgUp"),
+            KeyCode::PageDown => write!(f, "PgDn"),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            other => write!(f, "{:?}", other),
+        }
+    }
+}
+
+/// One or more chords pressed in order, e.g. `g g` or `ctrl+x ctrl+c`
+pub type KeySequence = Vec<KeyChord>;
+
+pub fn parse_sequence(text: &str) -> Result<KeySequence> {
+    let sequence = text
+        .split_whitespace()
+        .map(KeyChord::parse)
+        .collect::<Result<KeySequence>>()?;
+    if sequence.is_empty() {
+        bail!("empty key binding");
+    }
+    Ok(sequence)
+}
+
+pub fn format_sequence(sequence: &[KeyChord]) -> String {
+    sequence.iter().map(|chord| chord.to_string()).collect::<Vec<_>>().join(" ")
+}
+
+/// The keys each preset binds, by the action names `[keybindings.bindings]`
+/// uses. Every preset binds every action.
+fn preset_bindings(preset: KeymapPreset) -> Vec<(&'static str, &'static [&'static str])> {
+    let common: Vec<(&'static str, &'static [&'static str])> = vec![
+        ("tab_1", &["1"]),
+        ("tab_2", &["2"]),
+        ("tab_3", &["3"]),
+        ("tab_4", &["4"]),
+        ("tab_5", &["5"]),
+        ("tab_6", &["6"]),
+        ("tab_7", &["7"]),
+        ("focus_panel", &["enter"]),
+        ("sort_cpu", &["c"]),
+        ("sort_memory", &["m"]),
+        ("sort_pid", &["p"]),
+        ("sort_name", &["n"]),
+        ("sort_io", &["i"]),
+        ("kill_process", &["K"]),
+        ("toggle_pause", &["space"]),
+        ("faster_refresh", &["+", "="]),
+        ("slower_refresh", &["-"]),
+        ("toggle_load_per_core", &["L"]),
+        ("toggle_container_view", &["C"]),
+        ("save_settings", &["S"]),
+    ];
+
+    let specific: Vec<(&'static str, &'static [&'static str])> = match preset {
+        KeymapPreset::Default => vec![
+            ("quit", &["q", "ctrl+c"]),
+            ("back", &["esc"]),
+            ("next_tab", &["tab"]),
+            ("prev_tab", &["shift+tab"]),
+            ("scroll_up", &["up"]),
+            ("scroll_down", &["down"]),
+            ("scroll_top", &["home"]),
+            ("scroll_bottom", &["end"]),
+            ("next_panel", &["right"]),
+            ("prev_panel", &["left"]),
+            ("command_palette", &[":", "ctrl+p"]),
+            ("refresh", &["r"]),
+            ("help", &["h"]),
+        ],
+        KeymapPreset::Vim => vec![
+            ("quit", &["q", "ctrl+c"]),
+            ("back", &["esc"]),
+            ("next_tab", &["tab", "g t"]),
+            ("prev_tab", &["shift+tab", "g T"]),
+            ("scroll_up", &["k", "up"]),
+            ("scroll_down", &["j", "down"]),
+            ("scroll_top", &["g g", "home"]),
+            ("scroll_bottom", &["G", "end"]),
+            ("next_panel", &["l", "right"]),
+            ("prev_panel", &["h", "left"]),
+            ("command_palette", &[":", "ctrl+p"]),
+            ("refresh", &["r", "ctrl+l"]),
+            ("help", &["?", "f1"]),
+        ],
+        KeymapPreset::Emacs => vec![
+            ("quit", &["ctrl+x ctrl+c", "q"]),
+            ("back", &["ctrl+g", "esc"]),
+            ("next_tab", &["tab", "ctrl+x o"]),
+            ("prev_tab", &["shift+tab"]),
+            ("scroll_up", &["ctrl+p", "up"]),
+            ("scroll_down", &["ctrl+n", "down"]),
+            ("scroll_top", &["alt+<", "home"]),
+            ("scroll_bottom", &["alt+>", "end"]),
+            ("next_panel", &["ctrl+f", "right"]),
+            ("prev_panel", &["ctrl+b", "left"]),
+            ("command_palette", &["alt+x", ":"]),
+            ("refresh", &["ctrl+l", "r"]),
+            ("help", &["ctrl+h", "f1"]),
+        ],
+    };
+
+    common.into_iter().chain(specific).collect()
+}
+
+/// Every action name that can be bound, in the default preset's order
+pub fn action_names() -> Vec<&'static str> {
+    preset_bindings(KeymapPreset::Default).into_iter().map(|(name, _)| name).collect()
+}
+
+/// The key sequences of a preset plus per-action overrides, each with the
+/// name of its action, rejecting unknown actions, unparsable keys and
+/// conflicting bindings
+pub fn resolve(settings: &KeybindingSettings) -> Result<Vec<(KeySequence, String)>> {
+    let mut by_action: Vec<(String, Vec<String>)> = preset_bindings(settings.preset)
+        .into_iter()
+        .map(|(action, keys)| (action.to_string(), keys.iter().map(|key| key.to_string()).collect()))
+        .collect();
+
+    for (name, keys) in &settings.bindings {
+        match by_action.iter_mut().find(|(existing, _)| existing == name) {
+            Some((_, existing_keys)) => *existing_keys = keys.clone(),
+            None => bail!("keybindings.bindings.{}: unknown action \"{}\"", name, name),
+        }
+    }
+
+    let mut bindings: Vec<(KeySequence, String)> = Vec::new();
+    for (action, keys) in by_action {
+        for key in keys {
+            let sequence = parse_sequence(&key).map_err(|err| anyhow!("keybindings.bindings.{}: {}", action, err))?;
+            bindings.push((sequence, action.clone()));
+        }
+    }
+
+    check_conflicts(&bindings)?;
+    Ok(bindings)
+}
+
+/// A sequence may not be bound twice, nor be the start of a longer
+/// sequence, since the longer one could then never be typed
+fn check_conflicts(bindings: &[(KeySequence, String)]) -> Result<()> {
+    let mut problems = Vec::new();
+
+    for (i, (sequence, action)) in bindings.iter().enumerate() {
+        for (other_sequence, other_action) in &bindings[i + 1..] {
+            let shorter = sequence.len().min(other_sequence.len());
+            if sequence[..shorter] != other_sequence[..shorter] {
+                continue;
+            }
+
+            if sequence.len() == other_sequence.len() {
+                if action != other_action {
+                    problems.push(format!(
+                        "\"{}\" is bound to both {} and {}",
+                        format_sequence(sequence),
+                        action,
+                        other_action
+                    ));
+                }
+            } else {
+                let (prefix, prefix_action, longer, longer_action) = if sequence.len() < other_sequence.len() {
+                    (sequence, action, other_sequence, other_action)
+                } else {
+                    (other_sequence, other_action, sequence, action)
+                };
+                problems.push(format!(
+                    "\"{}\" ({}) is a prefix of \"{}\" ({})",
+                    format_sequence(prefix),
+                    prefix_action,
+                    format_sequence(longer),
+                    longer_action
+                ));
+            }
+        }
+    }
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        bail!("conflicting key bindings:\n  {}", problems.join("\n  "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    fn resolve_with(bindings: &[(&str, &[&str])]) -> Result<Vec<(KeySequence, String)>> {
+        resolve(&KeybindingSettings {
+            preset: KeymapPreset::Default,
+            bindings: bindings
+                .iter()
+                .map(|(action, keys)| (action.to_string(), keys.iter().map(|key| key.to_string()).collect()))
+                .collect(),
+        })
+    }
+
+    #[test]
+    fn parses_chords() {
+        assert_eq!(KeyChord::parse("q").unwrap(), KeyChord::new(KeyCode::Char('q'), KeyModifiers::NONE));
+        assert_eq!(KeyChord::parse("ctrl+c").unwrap(), KeyChord::new(KeyCode::Char('c'), KeyModifiers::CONTROL));
+        assert_eq!(KeyChord::parse("ctrl++").unwrap(), KeyChord::new(KeyCode::Char('+'), KeyModifiers::CONTROL));
+        assert_eq!(KeyChord::parse("+").unwrap(), KeyChord::new(KeyCode::Char('+'), KeyModifiers::NONE));
+        assert_eq!(KeyChord::parse("shift+tab").unwrap(), KeyChord::new(KeyCode::BackTab, KeyModifiers::NONE));
+        assert_eq!(KeyChord::parse("shift+g").unwrap(), KeyChord::parse("G").unwrap());
+        assert_eq!(KeyChord::parse("alt+<").unwrap(), KeyChord::new(KeyCode::Char('<'), KeyModifiers::ALT));
+        assert_eq!(KeyChord::parse("F5").unwrap(), KeyChord::new(KeyCode::F(5), KeyModifiers::NONE));
+    }
+
+    #[test]
+    fn rejects_unknown_keys_and_modifiers() {
+        assert!(KeyChord::parse("hyper+x").is_err());
+        assert!(KeyChord::parse("f13").is_err());
+        assert!(KeyChord::parse("banana").is_err());
+        assert!(parse_sequence("   ").is_err());
+    }
+
+    #[test]
+    fn terminal_shift_reports_match_config_keys() {
+        let backtab = KeyChord::from(key(KeyCode::BackTab, KeyModifiers::SHIFT));
+        assert_eq!(backtab, KeyChord::parse("shift+tab").unwrap());
+        let capital = KeyChord::from(key(KeyCode::Char('G'), KeyModifiers::SHIFT));
+        assert_eq!(capital, KeyChord::parse("G").unwrap());
+    }
+
+    #[test]
+    fn conflicting_bindings_are_rejected() {
+        let err = resolve_with(&[("refresh", &["q"])]).unwrap_err();
+        assert!(err.to_string().contains("\"q\" is bound to both"), "{}", err);
+
+        let err = resolve_with(&[("help", &["r x"])]).unwrap_err();
+        assert!(err.to_string().contains("\"r\" (refresh) is a prefix of \"r x\" (help)"), "{}", err);
+
+        // The same key twice for one action is harmless
+        assert!(resolve_with(&[("refresh", &["r", "r"])]).is_ok());
+    }
+
+    #[test]
+    fn overrides_must_name_an_action() {
+        let err = resolve_with(&[("launch", &["x"])]).unwrap_err();
+        assert_eq!(err.to_string(), "keybindings.bindings.launch: unknown action \"launch\"");
+
+        let err = resolve_with(&[("refresh", &["hyper+r"])]).unwrap_err();
+        assert!(err.to_string().starts_with("keybindings.bindings.refresh: unknown modifier"), "{}", err);
+    }
+
+    #[test]
+    fn every_preset_binds_every_action() {
+        for preset in KeymapPreset::ALL {
+            let bindings = resolve(&KeybindingSettings { preset, ..KeybindingSettings::default() })
+                .unwrap_or_else(|err| panic!("{:?}: {:#}", preset, err));
+            for name in action_names() {
+                assert!(bindings.iter().any(|(_, action)| action == name), "{:?} leaves {} unbound", preset, name);
+            }
+        }
+    }
+}
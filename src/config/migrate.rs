@@ -0,0 +1,188 @@
+use toml::{Table, Value};
+
+use super::validate::Problem;
+
+/// Layout written by this build. Files without a `version` key predate it
+/// and are read as version 1.
+pub const CURRENT_VERSION: u32 = 2;
+
+/// Bring a parsed file up to the current layout, returning a note for each
+/// key that was moved or dropped
+pub fn migrate(table: &mut Table) -> Result<Vec<Problem>, Problem> {
+    let version = match table.get("version") {
+        None => 1,
+        Some(Value::Integer(version)) if (1..=CURRENT_VERSION as i64).contains(version) => *version as u32,
+        Some(Value::Integer(version)) if *version > CURRENT_VERSION as i64 => {
+            return Err(Problem::new(
+                "version",
+                format!(
+                    "{} is newer than this build understands (up to {}); upgrade system-monitor",
+                    version, CURRENT_VERSION
+                ),
+            ))
+        }
+        Some(value) => {
+            return Err(Problem::new(
+                "version",
+                format!("must be a layout number from 1 to {}, not {}", CURRENT_VERSION, value),
+            ))
+        }
+    };
+
+    let mut notes = Vec::new();
+    if version < 2 {
+        v1_to_v2(table, &mut notes);
+    }
+    table.insert("version".to_string(), Value::Integer(CURRENT_VERSION as i64));
+    Ok(notes)
+}
+
+/// Version 1 had a single `dashboard.refresh_rate_ms` that nothing read;
+/// collectors now each have an interval under `[system.intervals]`
+fn v1_to_v2(table: &mut Table, notes: &mut Vec<Problem>) {
+    let Some(Value::Table(dashboard)) = table.get_mut("dashboard") else {
+        return;
+    };
+    let Some(refresh_rate) = dashboard.remove("refresh_rate_ms") else {
+        return;
+    };
+
+    let system = table
+        .entry("system")
+        .or_insert_with(|| Value::Table(Table::new()));
+    let intervals = system
+        .as_table_mut()
+        .map(|system| system.contains_key("intervals"));
+    match (intervals, &refresh_rate) {
+        (Some(false), Value::Integer(_)) => {
+            // The rate applied to the once-a-tick samples; slower collectors keep their defaults
+            let mut intervals = Table::new();
+            for key in ["cpu_ms", "memory_ms", "network_ms"] {
+                intervals.insert(key.to_string(), refresh_rate.clone());
+            }
+            system
+                .as_table_mut()
+                .expect("checked above")
+                .insert("intervals".to_string(), Value::Table(intervals));
+            notes.push(Problem::new(
+                "dashboard.refresh_rate_ms",
+                format!(
+                    "moved to [system.intervals] as cpu_ms, memory_ms and network_ms = {}; \
+                     write it there and set version = {}",
+                    refresh_rate, CURRENT_VERSION
+                ),
+            ));
+        }
+        _ => notes.push(Problem::new(
+            "dashboard.refresh_rate_ms",
+            format!(
+                "no longer used, [system.intervals] sets each collector's rate; \
+                 remove it and set version = {}",
+                CURRENT_VERSION
+            ),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::validate::parse;
+
+    fn table(source: &str) -> Table {
+        source.parse().unwrap()
+    }
+
+    #[test]
+    fn v1_refresh_rate_moves_to_intervals() {
+        let mut v1 = table(
+            "\
+[dashboard]
+title = \"Old box\"
+refresh_rate_ms = 500
+
+[display]
+show_cpu_graph = false
+",
+        );
+        let notes = migrate(&mut v1).unwrap();
+
+        let expected = table(
+            "\
+version = 2
+
+[dashboard]
+title = \"Old box\"
+
+[system.intervals]
+cpu_ms = 500
+memory_ms = 500
+network_ms = 500
+
+[display]
+show_cpu_graph = false
+",
+        );
+        assert_eq!(v1, expected);
+        assert_eq!(notes.len(), 1);
+        assert_eq!(notes[0].key, "dashboard.refresh_rate_ms");
+        assert!(notes[0].message.starts_with("moved to [system.intervals]"), "{}", notes[0].message);
+    }
+
+    #[test]
+    fn v1_refresh_rate_is_dropped_when_intervals_are_set() {
+        let mut v1 = table("[dashboard]\nrefresh_rate_ms = 500\n[system.intervals]\ncpu_ms = 2000\n");
+        let notes = migrate(&mut v1).unwrap();
+
+        assert_eq!(v1, table("version = 2\n[dashboard]\n[system.intervals]\ncpu_ms = 2000\n"));
+        assert!(notes[0].message.starts_with("no longer used"), "{}", notes[0].message);
+    }
+
+    #[test]
+    fn current_files_are_left_alone() {
+        let source = "version = 2\n[dashboard]\nrefresh_rate_ms = 500\n";
+        let mut current = table(source);
+        assert!(migrate(&mut current).unwrap().is_empty());
+        assert_eq!(current, table(source));
+    }
+
+    #[test]
+    fn unsupported_versions_are_refused() {
+        assert!(migrate(&mut table("version = 3\n")).unwrap_err().message.contains("newer"));
+        assert!(migrate(&mut table("version = 0\n")).is_err());
+        assert!(migrate(&mut table("version = \"2\"\n")).is_err());
+    }
+
+    #[test]
+    fn migrated_file_loads_with_a_warning() {
+        let parsed = parse("[dashboard]\nrefresh_rate_ms = 750\n").unwrap();
+        assert_eq!(parsed.settings.version, CURRENT_VERSION);
+        assert_eq!(parsed.settings.system.intervals.cpu_ms, 750);
+        assert_eq!(parsed.warnings.len(), 1);
+        assert_eq!(parsed.warnings[0].line, Some(2));
+    }
+
+    #[test]
+    fn partial_file_keeps_defaults_and_warns_about_misspelled_keys() {
+        let source = "\
+version = 2
+
+[dashboard]
+titel = \"db1\"
+
+[system]
+cpu_history_length = 60
+";
+        let parsed = parse(source).unwrap();
+        let defaults = crate::config::Settings::default();
+        assert_eq!(parsed.settings.system.cpu_history_length, 60);
+        assert_eq!(parsed.settings.dashboard.title, defaults.dashboard.title);
+        assert_eq!(parsed.settings.display, defaults.display);
+        assert_eq!(parsed.settings.system.intervals, defaults.system.intervals);
+
+        assert_eq!(parsed.warnings.len(), 1, "{:?}", parsed.warnings);
+        assert_eq!(parsed.warnings[0].key, "dashboard.titel");
+        assert_eq!(parsed.warnings[0].line, Some(4));
+        assert_eq!(parsed.warnings[0].message, "unknown key, ignored");
+    }
+}
@@ -1,3 +1,11 @@
+pub mod keys;
+pub mod migrate;
+pub mod overrides;
 pub mod settings;
+pub mod validate;
+pub mod watcher;
 
-pub use settings::Settings;
\ No newline at end of file
+pub use overrides::Override;
+pub use settings::{ConfigFileError, Settings};
+pub use validate::{InvalidConfig, Parsed, Problem};
+pub use watcher::ConfigWatcher;
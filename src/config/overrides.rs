@@ -0,0 +1,262 @@
+use anyhow::{bail, Result};
+use std::fmt;
+use toml::{Table, Value};
+
+use super::settings::CollectorIntervals;
+use super::validate::Problem;
+
+/// Environment variables starting with this set a key each, e.g.
+/// `SYSMON_SYSTEM__INTERVALS__CPU_MS=500` for `system.intervals.cpu_ms`.
+/// Names without a `__` are left alone: every setting is inside a section,
+/// and variables like `$SYSMON_TOKEN` are for the user's own scripts.
+pub const ENV_PREFIX: &str = "SYSMON_";
+
+/// One setting given outside the config file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Override {
+    /// Dotted key, e.g. `alerts.disk_warning_percent`
+    pub key: String,
+    pub value: Value,
+    pub origin: Origin,
+}
+
+/// Where an override came from, for error messages
+#[derive(Debug, Clone, PartialEq)]
+pub enum Origin {
+    Env(String),
+    Set,
+    Refresh,
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Origin::Env(name) => write!(f, "${}", name),
+            Origin::Set => write!(f, "--set"),
+            Origin::Refresh => write!(f, "--refresh"),
+        }
+    }
+}
+
+impl Override {
+    /// A `section.key=value` argument to `--set`
+    pub fn parse_assignment(assignment: &str) -> Result<Override> {
+        let Some((key, value)) = assignment.split_once('=') else {
+            bail!("expected section.key=value, got \"{}\"", assignment);
+        };
+        let key = key.trim();
+        if key.is_empty() || key.split('.').any(str::is_empty) {
+            bail!("\"{}\" is not a dotted key like dashboard.title", key);
+        }
+        Ok(Override { key: key.to_string(), value: parse_value(value.trim()), origin: Origin::Set })
+    }
+
+    /// `--refresh`: every `[system.intervals]` key set to `seconds`, so the
+    /// range checks apply to it like to any other value
+    pub fn refresh(seconds: u64) -> Result<Vec<Override>> {
+        let Some(interval_ms) = seconds.checked_mul(1000).filter(|ms| i64::try_from(*ms).is_ok()) else {
+            bail!("--refresh {} is too long; the longest interval is 60 seconds", seconds);
+        };
+        let intervals = Table::try_from(CollectorIntervals::uniform(interval_ms))?;
+        Ok(intervals
+            .into_iter()
+            .map(|(name, value)| Override {
+                key: format!("system.intervals.{}", name),
+                value,
+                origin: Origin::Refresh,
+            })
+            .collect())
+    }
+
+    /// Every `SYSMON_SECTION__KEY` variable in `vars`, usually
+    /// `std::env::vars()`, sorted so the result does not depend on the
+    /// environment's order
+    pub fn from_env(vars: impl IntoIterator<Item = (String, String)>) -> Vec<Override> {
+        let mut overrides: Vec<Override> = vars
+            .into_iter()
+            .filter_map(|(name, value)| Self::from_env_var(&name, &value))
+            .collect();
+        overrides.sort_by(|a, b| a.key.cmp(&b.key));
+        overrides
+    }
+
+    fn from_env_var(name: &str, value: &str) -> Option<Override> {
+        let path = name.strip_prefix(ENV_PREFIX)?;
+        let segments: Vec<String> = path.split("__").map(str::to_lowercase).collect();
+        if segments.len() < 2 || segments.iter().any(String::is_empty) {
+            return None;
+        }
+        Some(Override {
+            key: segments.join("."),
+            value: parse_value(value),
+            origin: Origin::Env(name.to_string()),
+        })
+    }
+
+    /// Whether a problem reported at `key` is about this override's value
+    pub fn covers(&self, key: &str) -> bool {
+        key.strip_prefix(self.key.as_str())
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('.') || rest.starts_with('['))
+    }
+}
+
+/// TOML syntax when the text is a TOML value (numbers, booleans, quoted
+/// strings, arrays), otherwise the text itself as a string
+fn parse_value(raw: &str) -> Value {
+    format!("value = {}", raw)
+        .parse::<Table>()
+        .ok()
+        .and_then(|mut table| table.remove("value"))
+        .unwrap_or_else(|| Value::String(raw.to_string()))
+}
+
+/// Write each override into the parsed file, later ones winning, creating
+/// tables along the way
+pub fn apply(table: &mut Table, overrides: &[Override]) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    for entry in overrides {
+        let segments: Vec<&str> = entry.key.split('.').collect();
+        if let Err(depth) = insert(table, &segments, entry.value.clone()) {
+            problems.push(Problem::new(
+                entry.key.clone(),
+                format!("{} is not a table, so {} cannot set a key in it", segments[..=depth].join("."), entry.origin),
+            ));
+        }
+    }
+    problems
+}
+
+/// Set `path` to `value`; on failure, the index of the segment that holds
+/// something other than a table
+pub(super) fn insert(table: &mut Table, path: &[&str], value: Value) -> Result<(), usize> {
+    match path {
+        [] => Ok(()),
+        [field] => {
+            table.insert(field.to_string(), value);
+            Ok(())
+        }
+        [segment, rest @ ..] => match table
+            .entry(segment.to_string())
+            .or_insert_with(|| Value::Table(Table::new()))
+        {
+            Value::Table(next) => insert(next, rest, value).map_err(|depth| depth + 1),
+            _ => Err(0),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::validate::parse_with_overrides;
+
+    #[test]
+    fn refresh_sets_every_interval() {
+        let overrides = Override::refresh(2).unwrap();
+        assert_eq!(overrides.len(), Table::try_from(CollectorIntervals::default()).unwrap().len());
+        for entry in &overrides {
+            assert!(entry.key.starts_with("system.intervals."), "{}", entry.key);
+            assert_eq!(entry.value, Value::Integer(2000));
+            assert_eq!(entry.origin, Origin::Refresh);
+        }
+    }
+
+    #[test]
+    fn refresh_that_overflows_is_an_error() {
+        assert!(Override::refresh(u64::MAX / 1000 + 1).is_err());
+        assert!(Override::refresh(u64::MAX / 1000).is_err());
+    }
+
+    #[test]
+    fn refresh_out_of_range_is_reported_by_validation() {
+        let problems = parse_with_overrides("", &Override::refresh(0).unwrap()).unwrap_err();
+        assert!(!problems.is_empty());
+        assert!(problems.iter().all(|problem| problem.key.starts_with("system.intervals.")));
+        assert!(problems[0].message.ends_with("(set by --refresh)"), "{}", problems[0].message);
+    }
+
+    fn env(vars: &[(&str, &str)]) -> Vec<Override> {
+        Override::from_env(vars.iter().map(|(name, value)| (name.to_string(), value.to_string())))
+    }
+
+    #[test]
+    fn env_names_map_to_dotted_keys() {
+        let overrides = env(&[
+            ("SYSMON_SYSTEM__INTERVALS__CPU_MS", "500"),
+            ("SYSMON_DASHBOARD__TITLE", "db1"),
+            ("PATH", "/usr/bin"),
+            ("SYSMON_", "ignored"),
+            ("SYSMON_TOKEN", "ignored"),
+            ("SYSMON_DISPLAY____SHOW_CPU_GRAPH", "ignored"),
+        ]);
+        let keys: Vec<&str> = overrides.iter().map(|entry| entry.key.as_str()).collect();
+        assert_eq!(keys, ["dashboard.title", "system.intervals.cpu_ms"]);
+        assert_eq!(overrides[1].origin, Origin::Env("SYSMON_SYSTEM__INTERVALS__CPU_MS".to_string()));
+        assert_eq!(overrides[1].origin.to_string(), "$SYSMON_SYSTEM__INTERVALS__CPU_MS");
+    }
+
+    #[test]
+    fn values_are_typed_like_toml() {
+        let value = |raw: &str| env(&[("SYSMON_A__B", raw)]).remove(0).value;
+        assert_eq!(value("true"), Value::Boolean(true));
+        assert_eq!(value("250"), Value::Integer(250));
+        assert_eq!(value("-3"), Value::Integer(-3));
+        assert_eq!(value("80.5"), Value::Float(80.5));
+        assert_eq!(value("\"42\""), Value::String("42".to_string()));
+        assert_eq!(value("db1"), Value::String("db1".to_string()));
+        assert_eq!(value("unix:/run/agent.sock"), Value::String("unix:/run/agent.sock".to_string()));
+        assert_eq!(value("[\"j\", \"down\"]"), Value::Array(vec![Value::from("j"), Value::from("down")]));
+    }
+
+    #[test]
+    fn set_parses_dotted_assignments() {
+        let entry = Override::parse_assignment("alerts.disk_warning_percent = 80").unwrap();
+        assert_eq!(entry.key, "alerts.disk_warning_percent");
+        assert_eq!(entry.value, Value::Integer(80));
+        assert_eq!(entry.origin, Origin::Set);
+
+        // Only the first = splits
+        let entry = Override::parse_assignment("dashboard.title=a=b").unwrap();
+        assert_eq!(entry.value, Value::String("a=b".to_string()));
+    }
+
+    #[test]
+    fn set_without_equals_or_key_is_an_error() {
+        assert!(Override::parse_assignment("dashboard.title").is_err());
+        assert!(Override::parse_assignment("=5").is_err());
+        assert!(Override::parse_assignment("dashboard..title=x").is_err());
+        assert!(Override::parse_assignment("dashboard.=x").is_err());
+    }
+
+    #[test]
+    fn set_of_unknown_key_is_a_warning_naming_the_flag() {
+        let overrides = [Override::parse_assignment("dashboard.titel=db1").unwrap()];
+        let parsed = parse_with_overrides("", &overrides).unwrap();
+        assert_eq!(parsed.warnings.len(), 1, "{:?}", parsed.warnings);
+        assert_eq!(parsed.warnings[0].key, "dashboard.titel");
+        assert_eq!(parsed.warnings[0].message, "unknown key, ignored (set by --set)");
+    }
+
+    #[test]
+    fn later_overrides_win_and_bad_values_name_their_origin() {
+        let overrides = [
+            env(&[("SYSMON_DASHBOARD__TITLE", "from env")]).remove(0),
+            Override::parse_assignment("dashboard.title=from set").unwrap(),
+        ];
+        let parsed = parse_with_overrides("[dashboard]\ntitle = \"from file\"\n", &overrides).unwrap();
+        assert_eq!(parsed.settings.dashboard.title, "from set");
+
+        let overrides = env(&[("SYSMON_SYSTEM__MAX_PROCESSES_DISPLAYED", "lots")]);
+        let problems = parse_with_overrides("", &overrides).unwrap_err();
+        assert_eq!(problems[0].key, "system.max_processes_displayed");
+        assert_eq!(problems[0].line, None);
+        assert!(problems[0].message.ends_with("(set by $SYSMON_SYSTEM__MAX_PROCESSES_DISPLAYED)"), "{}", problems[0].message);
+    }
+
+    #[test]
+    fn override_inside_a_value_is_blocked() {
+        let overrides = [Override::parse_assignment("dashboard.title.text=x").unwrap()];
+        let problems = parse_with_overrides("[dashboard]\ntitle = \"db1\"\n", &overrides).unwrap_err();
+        assert_eq!(problems[0].message, "dashboard.title is not a table, so --set cannot set a key in it");
+    }
+}
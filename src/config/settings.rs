@@ -1,85 +1,726 @@
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use serde::{Deserialize, Serialize};
-use std::fs;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use std::{env, fmt, fs, io};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+use crate::system::{CollectorKind, HistoryLengths};
+use super::migrate::{self, CURRENT_VERSION};
+use super::overrides::{self, Override};
+use super::validate::{self, InvalidConfig, Parsed, Problem};
+
+/// Every section and field is optional; whatever a file leaves out keeps
+/// its default
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Settings {
+    /// Layout the file is written in; older layouts are migrated on load
+    pub version: u32,
     pub dashboard: DashboardSettings,
     pub system: SystemSettings,
     pub display: DisplaySettings,
+    pub keybindings: KeybindingSettings,
+    pub alerts: AlertSettings,
+    pub agent: AgentSettings,
+    pub fleet: FleetSettings,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct DashboardSettings {
     pub title: String,
-    pub refresh_rate_ms: u64,
     pub max_history_entries: usize,
+    /// Capture the mouse for clicking and scrolling; disable to keep the
+    /// terminal's own text selection
+    pub mouse_capture: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl Default for DashboardSettings {
+    fn default() -> Self {
+        Self {
+            title: "System Monitor Dashboard".to_string(),
+            max_history_entries: 100,
+            mouse_capture: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct SystemSettings {
     pub enable_process_monitoring: bool,
     pub max_processes_displayed: usize,
     pub cpu_history_length: usize,
     pub memory_history_length: usize,
+    /// How often each collector samples, in milliseconds
+    pub intervals: CollectorIntervals,
+}
+
+impl Default for SystemSettings {
+    fn default() -> Self {
+        Self {
+            enable_process_monitoring: true,
+            max_processes_displayed: 20,
+            cpu_history_length: 60,
+            memory_history_length: 60,
+            intervals: CollectorIntervals::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CollectorIntervals {
+    pub cpu_ms: u64,
+    pub memory_ms: u64,
+    pub processes_ms: u64,
+    pub disks_ms: u64,
+    pub network_ms: u64,
+    pub sensors_ms: u64,
+    pub pressure_ms: u64,
+    pub cgroups_ms: u64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl Default for CollectorIntervals {
+    fn default() -> Self {
+        Self {
+            cpu_ms: 1000,
+            memory_ms: 1000,
+            processes_ms: 2000,
+            disks_ms: 5000,
+            network_ms: 1000,
+            sensors_ms: 2000,
+            pressure_ms: 2000,
+            cgroups_ms: 2000,
+        }
+    }
+}
+
+impl CollectorIntervals {
+    /// Every collector on the same interval
+    pub fn uniform(interval_ms: u64) -> Self {
+        Self {
+            cpu_ms: interval_ms,
+            memory_ms: interval_ms,
+            processes_ms: interval_ms,
+            disks_ms: interval_ms,
+            network_ms: interval_ms,
+            sensors_ms: interval_ms,
+            pressure_ms: interval_ms,
+            cgroups_ms: interval_ms,
+        }
+    }
+
+    /// Every interval multiplied by `factor`, kept between 100 ms and one minute
+    pub fn scaled(&self, factor: f64) -> Self {
+        let scale = |ms: u64| ((ms as f64 * factor).round() as u64).clamp(100, 60_000);
+        Self {
+            cpu_ms: scale(self.cpu_ms),
+            memory_ms: scale(self.memory_ms),
+            processes_ms: scale(self.processes_ms),
+            disks_ms: scale(self.disks_ms),
+            network_ms: scale(self.network_ms),
+            sensors_ms: scale(self.sensors_ms),
+            pressure_ms: scale(self.pressure_ms),
+            cgroups_ms: scale(self.cgroups_ms),
+        }
+    }
+
+    pub fn interval_ms(&self, kind: CollectorKind) -> u64 {
+        match kind {
+            CollectorKind::Cpu => self.cpu_ms,
+            CollectorKind::Memory => self.memory_ms,
+            CollectorKind::Processes => self.processes_ms,
+            CollectorKind::Disks => self.disks_ms,
+            CollectorKind::Network => self.network_ms,
+            CollectorKind::Sensors => self.sensors_ms,
+            CollectorKind::Pressure => self.pressure_ms,
+            CollectorKind::Cgroups => self.cgroups_ms,
+        }
+    }
+
+    /// The collector schedule, skipping process collection when it is disabled
+    pub fn schedule(&self, enable_process_monitoring: bool) -> Vec<(CollectorKind, Duration)> {
+        CollectorKind::ALL
+            .into_iter()
+            .filter(|kind| enable_process_monitoring || *kind != CollectorKind::Processes)
+            .map(|kind| (kind, Duration::from_millis(self.interval_ms(kind))))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct DisplaySettings {
     pub show_cpu_graph: bool,
     pub show_memory_graph: bool,
     pub show_process_list: bool,
     pub show_network_info: bool,
     pub show_disk_info: bool,
+    /// Show load average divided by the number of CPU cores
+    pub normalize_load: bool,
+    /// Whether gauges show host totals or our cgroup's usage against its limits
+    pub usage_view: UsageView,
+}
+
+impl Default for DisplaySettings {
+    fn default() -> Self {
+        Self {
+            show_cpu_graph: true,
+            show_memory_graph: true,
+            show_process_list: true,
+            show_network_info: true,
+            show_disk_info: true,
+            normalize_load: false,
+            usage_view: UsageView::Auto,
+        }
+    }
+}
+
+/// What the CPU and memory gauges are measured against
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UsageView {
+    /// Container view when our cgroup has a CPU or memory limit, else host
+    #[default]
+    Auto,
+    Host,
+    Container,
+}
+
+impl UsageView {
+    pub const ALL: [UsageView; 3] = [UsageView::Auto, UsageView::Host, UsageView::Container];
+}
+
+/// Thresholds that raise alerts in the status bar
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AlertSettings {
+    /// PSI `some` avg10 (percent of time at least one task stalled) that warns
+    pub psi_some_avg10: f32,
+    /// PSI `full` avg10 (percent of time all tasks stalled) that is critical
+    pub psi_full_avg10: f32,
+    /// Filesystem usage percent that warns
+    pub disk_warning_percent: f32,
+    /// Filesystem usage percent that is critical
+    pub disk_critical_percent: f32,
+}
+
+impl Default for AlertSettings {
+    fn default() -> Self {
+        Self {
+            psi_some_avg10: 20.0,
+            psi_full_avg10: 5.0,
+            disk_warning_percent: 90.0,
+            disk_critical_percent: 97.0,
+        }
+    }
+}
+
+/// Options for `system-monitor agent`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AgentSettings {
+    /// `host:port` to listen on, or `unix:/path/to/socket`
+    pub listen: String,
+    /// Serve clients that match no `[[agent.clients]]` entry, read-only
+    pub allow_anonymous: bool,
+    /// Encrypt the link, and with `client_ca` require client certificates
+    pub tls: Option<AgentTlsSettings>,
+    pub clients: Vec<AgentClientSettings>,
+}
+
+impl Default for AgentSettings {
+    fn default() -> Self {
+        Self {
+            listen: "127.0.0.1:7878".to_string(),
+            allow_anonymous: false,
+            tls: None,
+            clients: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AgentTlsSettings {
+    /// PEM certificate chain the agent presents
+    pub cert: PathBuf,
+    /// PEM private key for `cert`
+    pub key: PathBuf,
+    /// PEM CA that client certificates must be signed by; enables mutual TLS
+    pub client_ca: Option<PathBuf>,
+}
+
+/// A dashboard allowed to connect, identified by a token or a certificate
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AgentClientSettings {
+    pub name: String,
+    /// Pre-shared secret sent in the client's hello
+    pub token: Option<String>,
+    /// PEM client certificate, matched exactly against the one presented
+    pub certificate: Option<PathBuf>,
+    #[serde(default)]
+    pub permission: Permission,
+}
+
+/// What a client may do besides watching
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Permission {
+    #[default]
+    ReadOnly,
+    /// May also signal processes on the agent's host
+    Control,
+}
+
+impl Permission {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Permission::ReadOnly => "read-only",
+            Permission::Control => "control",
+        }
+    }
+}
+
+/// Remote agents listed on the Fleet tab alongside this machine
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FleetSettings {
+    pub hosts: Vec<FleetHostSettings>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FleetHostSettings {
+    /// Shown in the Fleet table and the title bar
+    pub name: String,
+    /// The agent's `host:port` or `unix:/path/to/socket`
+    pub address: String,
+    /// Pre-shared token matching one of the agent's `[[agent.clients]]`
+    pub token: Option<String>,
+    pub tls: Option<RemoteTlsSettings>,
+}
+
+/// How a dashboard verifies an agent, and proves itself for mutual TLS
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RemoteTlsSettings {
+    /// PEM CA the agent's certificate must be signed by
+    pub ca: PathBuf,
+    /// PEM client certificate and key, for agents that require one
+    pub cert: Option<PathBuf>,
+    pub key: Option<PathBuf>,
+    /// Name to check the agent's certificate against; defaults to the address's host
+    pub server_name: Option<String>,
+}
+
+/// Built-in key layouts that `[keybindings.bindings]` is applied on top of
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeymapPreset {
+    #[default]
+    Default,
+    Vim,
+    Emacs,
+}
+
+impl KeymapPreset {
+    pub const ALL: [KeymapPreset; 3] = [KeymapPreset::Default, KeymapPreset::Vim, KeymapPreset::Emacs];
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeybindingSettings {
+    pub preset: KeymapPreset,
+    /// Per-action overrides: action name to key chords, e.g.
+    /// `scroll_down = ["j", "ctrl+n"]`. Replaces the preset's keys for that action.
+    pub bindings: BTreeMap<String, Vec<String>>,
 }
 
 impl Default for Settings {
     fn default() -> Self {
         Self {
-            dashboard: DashboardSettings {
-                title: "System Monitor Dashboard".to_string(),
-                refresh_rate_ms: 1000,
-                max_history_entries: 100,
-            },
-            system: SystemSettings {
-                enable_process_monitoring: true,
-                max_processes_displayed: 20,
-                cpu_history_length: 60,
-                memory_history_length: 60,
-            },
-            display: DisplaySettings {
-                show_cpu_graph: true,
-                show_memory_graph: true,
-                show_process_list: true,
-                show_network_info: true,
-                show_disk_info: true,
-            },
+            version: CURRENT_VERSION,
+            dashboard: DashboardSettings::default(),
+            system: SystemSettings::default(),
+            display: DisplaySettings::default(),
+            keybindings: KeybindingSettings::default(),
+            alerts: AlertSettings::default(),
+            agent: AgentSettings::default(),
+            fleet: FleetSettings::default(),
         }
     }
 }
 
-impl Settings {
-    pub fn load(config_path: &str) -> Result<Self> {
-        match fs::read_to_string(config_path) {
-            Ok(content) => {
-                let settings: Settings = toml::from_str(&content)
-                    .with_context(|| format!("Failed to parse config file: {}", config_path))?;
-                Ok(settings)
+/// Why a config file could not be read, keeping a missing file apart from
+/// one we are not allowed to read
+#[derive(Debug)]
+pub enum ConfigFileError {
+    NotFound(PathBuf),
+    Unreadable { path: PathBuf, source: io::Error },
+}
+
+impl fmt::Display for ConfigFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigFileError::NotFound(path) => write!(
+                f,
+                "{} does not exist; create it with `system-monitor --config {} config init`",
+                path.display(),
+                path.display()
+            ),
+            ConfigFileError::Unreadable { path, source } => {
+                write!(f, "{} exists but could not be read: {}", path.display(), source)
             }
-            Err(_) => {
-                // Create default config file if it doesn't exist
-                let default_settings = Settings::default();
-                default_settings.save(config_path)?;
-                Ok(default_settings)
+        }
+    }
+}
+
+impl std::error::Error for ConfigFileError {}
+
+impl Settings {
+    /// Samples each history keeps; pressure has no length of its own and
+    /// uses the overall cap
+    pub fn history_lengths(&self) -> HistoryLengths {
+        HistoryLengths {
+            cpu: self.system.cpu_history_length,
+            memory: self.system.memory_history_length,
+            pressure: self.dashboard.max_history_entries,
+        }
+    }
+
+    /// A copy safe to print, with agent and fleet tokens masked
+    pub fn redacted(&self) -> Settings {
+        let mask = |token: &mut Option<String>| {
+            if let Some(token) = token {
+                *token = "<redacted>".to_string();
             }
+        };
+        let mut settings = self.clone();
+        settings.agent.clients.iter_mut().for_each(|client| mask(&mut client.token));
+        settings.fleet.hosts.iter_mut().for_each(|host| mask(&mut host.token));
+        settings
+    }
+
+    /// The per-user config file: `$XDG_CONFIG_HOME/system-monitor/config.toml`,
+    /// falling back to `~/.config` as the XDG spec does
+    pub fn user_config_path() -> Option<PathBuf> {
+        let non_empty = |name| env::var_os(name).filter(|value| !value.is_empty()).map(PathBuf::from);
+        non_empty("XDG_CONFIG_HOME")
+            .or_else(|| non_empty("HOME").map(|home| home.join(".config")))
+            .map(|dir| dir.join("system-monitor").join("config.toml"))
+    }
+
+    /// Config files tried in order when none is given with `--config`
+    pub fn search_path() -> Vec<PathBuf> {
+        Self::user_config_path()
+            .into_iter()
+            .chain([PathBuf::from("/etc/system-monitor/config.toml")])
+            .collect()
+    }
+
+    /// The file to load: `explicit` when given, else the first file on the
+    /// search path that is there. A file we cannot even stat counts as there,
+    /// so loading it reports why rather than skipping to the next.
+    pub fn find(explicit: Option<&Path>) -> Option<PathBuf> {
+        if let Some(path) = explicit {
+            return Some(path.to_path_buf());
         }
+        Self::search_path().into_iter().find(|path| match fs::metadata(path) {
+            Ok(_) => true,
+            Err(err) => err.kind() != io::ErrorKind::NotFound,
+        })
     }
 
-    pub fn save(&self, config_path: &str) -> Result<()> {
+    /// Load, migrate and validate a config file, along with warnings about
+    /// keys that were ignored or moved. Never writes to the file.
+    pub fn load(config_path: &Path) -> Result<Parsed> {
+        Self::load_with_overrides(Some(config_path), &[])
+    }
+
+    /// `load`, then `overrides` on top; without a file they apply to the defaults
+    pub fn load_with_overrides(config_path: Option<&Path>, overrides: &[Override]) -> Result<Parsed> {
+        let content = match config_path {
+            Some(path) => fs::read_to_string(path).map_err(|source| match source.kind() {
+                io::ErrorKind::NotFound => ConfigFileError::NotFound(path.to_path_buf()),
+                _ => ConfigFileError::Unreadable { path: path.to_path_buf(), source },
+            })?,
+            None => String::new(),
+        };
+        validate::parse_with_overrides(&content, overrides).map_err(|problems| {
+            let path = match config_path {
+                Some(path) => path.display().to_string(),
+                None => "The default settings with overrides".to_string(),
+            };
+            InvalidConfig { path, problems }.into()
+        })
+    }
+
+    /// Write the defaults to `config_path` for `config init`, creating its
+    /// directory; an existing file is only replaced with `force`
+    pub fn init(config_path: &Path, force: bool) -> Result<()> {
+        if !force && fs::symlink_metadata(config_path).is_ok() {
+            bail!("{} already exists; pass --force to overwrite it", config_path.display());
+        }
+        if let Some(dir) = config_path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        Settings::default().save(config_path)
+    }
+
+    pub fn save(&self, config_path: &Path) -> Result<()> {
         let content = toml::to_string_pretty(self)
             .context("Failed to serialize settings to TOML")?;
         fs::write(config_path, content)
-            .with_context(|| format!("Failed to write config file: {}", config_path))?;
+            .with_context(|| format!("Failed to write config file: {}", config_path.display()))?;
         Ok(())
     }
-}
\ No newline at end of file
+
+    /// The settings as a TOML table. Every float in them is an f32, so each
+    /// keeps the short form it reads back from (0.1, not 0.10000000149011612).
+    pub fn to_table(&self) -> Result<toml::Table> {
+        let mut table = toml::Table::try_from(self).context("Failed to serialize settings to TOML")?;
+        table.iter_mut().for_each(|(_, value)| shorten_floats(value));
+        Ok(table)
+    }
+
+    /// A copy with the dotted `key` set to `value`, checked the way a file is
+    pub fn with_value(&self, key: &str, value: toml::Value) -> Result<Settings, Vec<Problem>> {
+        let mut table = self.to_table().map_err(|err| vec![Problem::new(key, format!("{:#}", err))])?;
+        let segments: Vec<&str> = key.split('.').collect();
+        if overrides::insert(&mut table, &segments, value).is_err() {
+            return Err(vec![Problem::new(key, "is not a setting")]);
+        }
+        let source = toml::to_string(&table).map_err(|err| vec![Problem::new(key, err.to_string())])?;
+        validate::parse(&source).map(|parsed| parsed.settings)
+    }
+
+    /// Write the current value of each dotted key in `keys` into
+    /// `config_path`, leaving everything else in the file, comments
+    /// included, as it was. A missing file is created, and one in an older
+    /// layout is migrated first so the next load keeps the saved values.
+    pub fn save_keys(&self, config_path: &Path, keys: &[String]) -> Result<()> {
+        let mut document = match fs::read_to_string(config_path) {
+            Ok(content) => content
+                .parse::<toml_edit::DocumentMut>()
+                .with_context(|| format!("Failed to parse {}", config_path.display()))?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                if let Some(dir) = config_path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+                    fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+                }
+                let mut document = toml_edit::DocumentMut::new();
+                document["version"] = toml_edit::value(CURRENT_VERSION as i64);
+                document
+            }
+            Err(err) => return Err(err).with_context(|| format!("Failed to read {}", config_path.display())),
+        };
+        migrate_document(&mut document).with_context(|| format!("Cannot save to {}", config_path.display()))?;
+
+        let current = self.to_table()?;
+        for key in keys {
+            let segments: Vec<&str> = key.split('.').collect();
+            let value = lookup(&current, &segments)
+                .with_context(|| format!("{} is not a setting", key))?
+                .to_string()
+                .parse::<toml_edit::Value>()
+                .with_context(|| format!("Failed to write {}", key))?;
+            set_item(document.as_table_mut(), &segments, value)
+                .with_context(|| format!("Cannot write {} to {}", key, config_path.display()))?;
+        }
+
+        fs::write(config_path, document.to_string())
+            .with_context(|| format!("Failed to write config file: {}", config_path.display()))?;
+        Ok(())
+    }
+}
+
+/// Rewrite an edited file in an older layout as `migrate` reads it: moved
+/// keys are written where they now live, dropped keys are removed and
+/// `version` is bumped. Everything else keeps its formatting.
+fn migrate_document(document: &mut toml_edit::DocumentMut) -> Result<()> {
+    let mut migrated: toml::Table = toml::from_str(&document.to_string())?;
+    if migrated.get("version").and_then(toml::Value::as_integer) == Some(CURRENT_VERSION as i64) {
+        return Ok(());
+    }
+    migrate::migrate(&mut migrated).map_err(|problem| anyhow::anyhow!("{}", problem))?;
+
+    let mut moved = Vec::new();
+    sync_layout(document.as_table_mut(), &migrated, &mut Vec::new(), &mut moved);
+    for (path, value) in moved {
+        let segments: Vec<&str> = path.iter().map(String::as_str).collect();
+        set_item(document.as_table_mut(), &segments, value.to_string().parse()?)?;
+    }
+    Ok(())
+}
+
+/// Remove what `migrated` no longer has from `table`, and collect the values
+/// it has that `table` lacks. Values present in both only differ in
+/// `version`, which is brought up to date.
+fn sync_layout(
+    table: &mut dyn toml_edit::TableLike,
+    migrated: &toml::Table,
+    path: &mut Vec<String>,
+    moved: &mut Vec<(Vec<String>, toml::Value)>,
+) {
+    let dropped: Vec<String> = table
+        .iter()
+        .map(|(key, _)| key.to_string())
+        .filter(|key| !migrated.contains_key(key))
+        .collect();
+    for key in dropped {
+        table.remove(&key);
+    }
+
+    for (key, value) in migrated {
+        path.push(key.clone());
+        match (table.get_mut(key), value) {
+            (None, toml::Value::Table(nested)) => {
+                let mut leaves = Vec::new();
+                collect_leaves(nested, path, &mut leaves);
+                moved.extend(leaves);
+            }
+            (None, value) => moved.push((path.clone(), value.clone())),
+            (Some(item), toml::Value::Table(nested)) => {
+                if let Some(next) = item.as_table_like_mut() {
+                    sync_layout(next, nested, path, moved);
+                }
+            }
+            (Some(item), version) if path.len() == 1 && key == "version" => {
+                *item = toml_edit::value(version.as_integer().unwrap_or(CURRENT_VERSION as i64));
+            }
+            (Some(_), _) => {}
+        }
+        path.pop();
+    }
+}
+
+fn collect_leaves(table: &toml::Table, path: &mut Vec<String>, leaves: &mut Vec<(Vec<String>, toml::Value)>) {
+    for (key, value) in table {
+        path.push(key.clone());
+        match value {
+            toml::Value::Table(nested) => collect_leaves(nested, path, leaves),
+            value => leaves.push((path.clone(), value.clone())),
+        }
+        path.pop();
+    }
+}
+
+fn shorten_floats(value: &mut toml::Value) {
+    match value {
+        toml::Value::Float(float) => *float = (*float as f32).to_string().parse().unwrap_or(*float),
+        toml::Value::Array(items) => items.iter_mut().for_each(shorten_floats),
+        toml::Value::Table(table) => table.iter_mut().for_each(|(_, value)| shorten_floats(value)),
+        _ => {}
+    }
+}
+
+fn lookup<'a>(table: &'a toml::Table, path: &[&str]) -> Option<&'a toml::Value> {
+    match path {
+        [] => None,
+        [field] => table.get(*field),
+        [segment, rest @ ..] => lookup(table.get(*segment)?.as_table()?, rest),
+    }
+}
+
+/// Set `path` in an edited file, creating tables as needed. A value that is
+/// replaced keeps its trailing comment.
+fn set_item(table: &mut dyn toml_edit::TableLike, path: &[&str], mut value: toml_edit::Value) -> Result<()> {
+    match path {
+        [] => Ok(()),
+        [field] => {
+            match table.get_mut(field) {
+                Some(toml_edit::Item::Value(existing)) => {
+                    *value.decor_mut() = existing.decor().clone();
+                    *existing = value;
+                }
+                Some(existing) => *existing = toml_edit::Item::Value(value),
+                None => {
+                    table.insert(field, toml_edit::Item::Value(value));
+                }
+            }
+            Ok(())
+        }
+        [segment, rest @ ..] => {
+            if table.get(segment).is_none() {
+                let mut new_table = toml_edit::Table::new();
+                // Only write a header for tables that end up holding values
+                new_table.set_implicit(true);
+                table.insert(segment, toml_edit::Item::Table(new_table));
+            }
+            match table.get_mut(segment).and_then(toml_edit::Item::as_table_like_mut) {
+                Some(next) => set_item(next, rest, value),
+                None => bail!("{} is not a table", segment),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch_file(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("system-monitor-settings-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    const V1_FILE: &str = "\
+# Written for an old build
+[dashboard]
+title = \"Old box\"
+refresh_rate_ms = 500 # every tick
+
+[display]
+show_cpu_graph = true
+";
+
+    #[test]
+    fn saving_into_a_v1_file_migrates_it() {
+        let path = scratch_file("v1.toml");
+        fs::write(&path, V1_FILE).unwrap();
+
+        let mut settings = Settings::load(&path).unwrap().settings;
+        assert_eq!(settings.system.intervals.memory_ms, 500);
+        settings.system.intervals.cpu_ms = 250;
+        settings.display.show_cpu_graph = false;
+        settings
+            .save_keys(&path, &["system.intervals.cpu_ms".to_string(), "display.show_cpu_graph".to_string()])
+            .unwrap();
+
+        let saved = fs::read_to_string(&path).unwrap();
+        assert!(saved.contains("version = 2"), "{}", saved);
+        assert!(!saved.contains("refresh_rate_ms"), "{}", saved);
+        assert!(saved.contains("# Written for an old build"), "{}", saved);
+
+        let reloaded = Settings::load(&path).unwrap();
+        assert!(reloaded.warnings.is_empty(), "{:?}", reloaded.warnings);
+        assert_eq!(reloaded.settings.system.intervals.cpu_ms, 250);
+        assert_eq!(reloaded.settings.system.intervals.memory_ms, 500);
+        assert_eq!(reloaded.settings.system.intervals.network_ms, 500);
+        assert!(!reloaded.settings.display.show_cpu_graph);
+        assert_eq!(reloaded.settings.dashboard.title, "Old box");
+    }
+
+    #[test]
+    fn saving_creates_a_current_file() {
+        let path = scratch_file("new.toml");
+        let mut settings = Settings::default();
+        settings.alerts.disk_warning_percent = 75.5;
+        settings.save_keys(&path, &["alerts.disk_warning_percent".to_string()]).unwrap();
+
+        let reloaded = Settings::load(&path).unwrap();
+        assert!(reloaded.warnings.is_empty(), "{:?}", reloaded.warnings);
+        assert_eq!(reloaded.settings, settings);
+    }
+
+    #[test]
+    fn saving_into_a_newer_file_is_refused() {
+        let path = scratch_file("v9.toml");
+        fs::write(&path, "version = 9\n").unwrap();
+        let error = Settings::default().save_keys(&path, &["display.show_cpu_graph".to_string()]).unwrap_err();
+        assert!(format!("{:#}", error).contains("newer"), "{:#}", error);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "version = 9\n");
+    }
+}
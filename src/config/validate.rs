@@ -0,0 +1,491 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::ops::RangeInclusive;
+use toml_edit::{ImDocument, Item, TableLike, Value};
+
+use super::keys;
+use super::migrate;
+use super::overrides::{self, Override};
+use super::settings::{CollectorIntervals, Settings};
+
+/// One thing wrong with a config file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Problem {
+    /// Dotted TOML key, e.g. `system.intervals.cpu_ms` or `agent.clients[1].token`
+    pub key: String,
+    /// 1-based line in the file, when the key (or its table) appears there
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl Problem {
+    pub fn new(key: impl Into<String>, message: impl Into<String>) -> Self {
+        Self { key: key.into(), line: None, message: message.into() }
+    }
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {}: {}: {}", line, self.key, self.message),
+            None => write!(f, "{}: {}", self.key, self.message),
+        }
+    }
+}
+
+/// Every problem found in one config file
+#[derive(Debug)]
+pub struct InvalidConfig {
+    pub path: String,
+    pub problems: Vec<Problem>,
+}
+
+impl fmt::Display for InvalidConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let count = self.problems.len();
+        write!(f, "{} has {} problem{}:", self.path, count, if count == 1 { "" } else { "s" })?;
+        for problem in &self.problems {
+            write!(f, "\n  {}", problem)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for InvalidConfig {}
+
+/// Settings that loaded, with anything that was ignored or migrated on the way
+#[derive(Debug, Clone)]
+pub struct Parsed {
+    pub settings: Settings,
+    /// Unknown keys and migrated keys; the file still loads
+    pub warnings: Vec<Problem>,
+}
+
+/// Parse, migrate and validate a config file's contents, reporting every
+/// problem with the line it is on. Missing keys take their defaults.
+pub fn parse(source: &str) -> Result<Parsed, Vec<Problem>> {
+    parse_with_overrides(source, &[])
+}
+
+/// `parse`, with `overrides` written over the file before it is checked.
+/// Problems with an overridden key name the override instead of a line.
+pub fn parse_with_overrides(source: &str, overrides: &[Override]) -> Result<Parsed, Vec<Problem>> {
+    let document = ImDocument::parse(source).ok();
+    let line_for = |key: &str| {
+        document
+            .as_ref()
+            .and_then(|document| locate(document.as_table(), key))
+            .map(|offset| line_of(source, offset))
+    };
+    let with_lines = |problems: Vec<Problem>| -> Vec<Problem> {
+        problems
+            .into_iter()
+            .map(|problem| match overrides.iter().rev().find(|entry| entry.covers(&problem.key)) {
+                Some(entry) => Problem {
+                    line: None,
+                    message: format!("{} (set by {})", problem.message, entry.origin),
+                    ..problem
+                },
+                None => Problem { line: line_for(&problem.key), ..problem },
+            })
+            .collect()
+    };
+
+    let mut table: toml::Table = match source.parse() {
+        Ok(table) => table,
+        Err(err) => {
+            let err: toml::de::Error = err;
+            let line = err.span().map(|span| line_of(source, span.start));
+            return Err(vec![Problem { key: "(file)".to_string(), line, message: err.message().trim().to_string() }]);
+        }
+    };
+    let mut warnings = migrate::migrate(&mut table).map_err(|problem| with_lines(vec![problem]))?;
+    let blocked = overrides::apply(&mut table, overrides);
+    if !blocked.is_empty() {
+        return Err(blocked);
+    }
+
+    // Deserialize the migrated text so errors carry a span we can name a key from
+    let migrated = toml::to_string(&table).map_err(|err| vec![Problem::new("(file)", err.to_string())])?;
+    let mut ignored = Vec::new();
+    let deserializer = toml::Deserializer::new(&migrated);
+    let settings: Settings = match serde_ignored::deserialize(deserializer, |path| ignored.push(dotted(&path))) {
+        Ok(settings) => settings,
+        Err(err) => {
+            let key = err
+                .span()
+                .zip(ImDocument::parse(migrated.as_str()).ok())
+                .and_then(|(span, document)| key_at(document.as_table(), span.start, ""))
+                .unwrap_or_else(|| "(file)".to_string());
+            return Err(with_lines(vec![Problem::new(key, err.message().trim())]));
+        }
+    };
+    warnings.extend(ignored.into_iter().map(|key| Problem::new(key, "unknown key, ignored")));
+
+    let problems = validate(&settings);
+    if problems.is_empty() {
+        Ok(Parsed { settings, warnings: with_lines(warnings) })
+    } else {
+        Err(with_lines(problems))
+    }
+}
+
+/// A path from serde_ignored in the same form as `Problem::key`
+fn dotted(path: &serde_ignored::Path) -> String {
+    use serde_ignored::Path;
+    match path {
+        Path::Root => String::new(),
+        Path::Seq { parent, index } => format!("{}[{}]", dotted(parent), index),
+        Path::Map { parent, key } => match dotted(parent) {
+            parent if parent.is_empty() => key.clone(),
+            parent => format!("{}.{}", parent, key),
+        },
+        Path::Some { parent } | Path::NewtypeStruct { parent } | Path::NewtypeVariant { parent } => dotted(parent),
+    }
+}
+
+/// Range and consistency checks on already deserialized settings
+pub fn validate(settings: &Settings) -> Vec<Problem> {
+    let mut problems = Vec::new();
+
+    let dashboard = &settings.dashboard;
+    check_range(&mut problems, "dashboard.max_history_entries", dashboard.max_history_entries, 2..=100_000);
+
+    let system = &settings.system;
+    check_range(&mut problems, "system.max_processes_displayed", system.max_processes_displayed, 1..=10_000);
+    for (key, length) in [
+        ("system.cpu_history_length", system.cpu_history_length),
+        ("system.memory_history_length", system.memory_history_length),
+    ] {
+        check_range(&mut problems, key, length, 2..=100_000);
+        if length > dashboard.max_history_entries {
+            problems.push(Problem::new(
+                key,
+                format!(
+                    "{} exceeds dashboard.max_history_entries ({})",
+                    length, dashboard.max_history_entries
+                ),
+            ));
+        }
+    }
+    check_intervals(&mut problems, &system.intervals);
+
+    let alerts = &settings.alerts;
+    for (key, value) in [
+        ("alerts.psi_some_avg10", alerts.psi_some_avg10),
+        ("alerts.psi_full_avg10", alerts.psi_full_avg10),
+        ("alerts.disk_warning_percent", alerts.disk_warning_percent),
+        ("alerts.disk_critical_percent", alerts.disk_critical_percent),
+    ] {
+        if !(0.0..=100.0).contains(&value) {
+            problems.push(Problem::new(key, format!("must be a percentage between 0 and 100, not {}", value)));
+        }
+    }
+    if alerts.disk_warning_percent > alerts.disk_critical_percent {
+        problems.push(Problem::new(
+            "alerts.disk_warning_percent",
+            format!(
+                "{} is above alerts.disk_critical_percent ({})",
+                alerts.disk_warning_percent, alerts.disk_critical_percent
+            ),
+        ));
+    }
+
+    if let Err(err) = keys::resolve(&settings.keybindings) {
+        // Binding errors lead with the key they are about
+        let message = err.to_string();
+        let problem = match message.split_once(": ") {
+            Some((key, rest)) if key.starts_with("keybindings.") => Problem::new(key, rest),
+            _ => Problem::new("keybindings.bindings", message),
+        };
+        problems.push(problem);
+    }
+
+    check_agent(&mut problems, settings);
+    check_fleet(&mut problems, settings);
+    problems
+}
+
+fn check_range<T>(problems: &mut Vec<Problem>, key: &str, value: T, range: RangeInclusive<T>)
+where
+    T: PartialOrd + fmt::Display,
+{
+    if !range.contains(&value) {
+        problems.push(Problem::new(
+            key,
+            format!("must be between {} and {}, not {}", range.start(), range.end(), value),
+        ));
+    }
+}
+
+fn check_intervals(problems: &mut Vec<Problem>, intervals: &CollectorIntervals) {
+    for (name, value) in [
+        ("cpu_ms", intervals.cpu_ms),
+        ("memory_ms", intervals.memory_ms),
+        ("processes_ms", intervals.processes_ms),
+        ("disks_ms", intervals.disks_ms),
+        ("network_ms", intervals.network_ms),
+        ("sensors_ms", intervals.sensors_ms),
+        ("pressure_ms", intervals.pressure_ms),
+        ("cgroups_ms", intervals.cgroups_ms),
+    ] {
+        // Same bounds the +/- keys keep to
+        check_range(problems, &format!("system.intervals.{}", name), value, 100..=60_000);
+    }
+}
+
+fn check_agent(problems: &mut Vec<Problem>, settings: &Settings) {
+    let agent = &settings.agent;
+    if agent.listen.trim().is_empty() {
+        problems.push(Problem::new("agent.listen", "must not be empty"));
+    }
+
+    let verifies_clients = agent.tls.as_ref().is_some_and(|tls| tls.client_ca.is_some());
+    let mut names = HashSet::new();
+    let mut tokens = HashSet::new();
+    for (index, client) in agent.clients.iter().enumerate() {
+        let key = |field: &str| format!("agent.clients[{}].{}", index, field);
+        if !names.insert(client.name.as_str()) {
+            problems.push(Problem::new(key("name"), format!("\"{}\" is used by another client", client.name)));
+        }
+        match &client.token {
+            Some(token) if token.is_empty() => problems.push(Problem::new(key("token"), "must not be empty")),
+            Some(token) if !tokens.insert(token.as_str()) => {
+                problems.push(Problem::new(key("token"), "is shared with another client"))
+            }
+            None if client.certificate.is_none() => {
+                problems.push(Problem::new(key("name"), "set a token or a certificate"))
+            }
+            _ => {}
+        }
+        if client.certificate.is_some() && !verifies_clients {
+            problems.push(Problem::new(key("certificate"), "needs [agent.tls] with client_ca"));
+        }
+    }
+}
+
+fn check_fleet(problems: &mut Vec<Problem>, settings: &Settings) {
+    let mut names = HashSet::new();
+    for (index, host) in settings.fleet.hosts.iter().enumerate() {
+        let key = |field: &str| format!("fleet.hosts[{}].{}", index, field);
+        if host.name.trim().is_empty() {
+            problems.push(Problem::new(key("name"), "must not be empty"));
+        } else if !names.insert(host.name.as_str()) {
+            problems.push(Problem::new(key("name"), format!("\"{}\" is used by another host", host.name)));
+        }
+        if host.address.trim().is_empty() {
+            problems.push(Problem::new(key("address"), "must not be empty"));
+        }
+        if let Some(tls) = &host.tls {
+            if tls.cert.is_some() != tls.key.is_some() {
+                problems.push(Problem::new(key("tls"), "cert and key must be set together"));
+            }
+        }
+    }
+}
+
+/// Byte offset of `key` in the document, or of the nearest enclosing table
+/// that is written out
+fn locate(root: &dyn TableLike, key: &str) -> Option<usize> {
+    let mut table = Some(root);
+    let mut offset = None;
+
+    for segment in key.split('.') {
+        let (name, index) = match segment.split_once('[') {
+            Some((name, index)) => (name, index.trim_end_matches(']').parse::<usize>().ok()),
+            None => (segment, None),
+        };
+        let Some((key, item)) = table.and_then(|table| table.get_key_value(name)) else {
+            break;
+        };
+        offset = key.span().or_else(|| item.span()).map(|span| span.start).or(offset);
+
+        table = match (item, index) {
+            (_, None) => item.as_table_like(),
+            (Item::ArrayOfTables(tables), Some(index)) => tables.get(index).map(|entry| {
+                offset = entry.span().map(|span| span.start).or(offset);
+                entry as &dyn TableLike
+            }),
+            (Item::Value(Value::Array(values)), Some(index)) => values.get(index).and_then(|entry| {
+                offset = entry.span().map(|span| span.start).or(offset);
+                entry.as_inline_table().map(|entry| entry as &dyn TableLike)
+            }),
+            _ => None,
+        };
+    }
+    offset
+}
+
+/// The deepest key whose value contains byte `offset`, for parse errors
+fn key_at(table: &dyn TableLike, offset: usize, prefix: &str) -> Option<String> {
+    let join = |name: &str| if prefix.is_empty() { name.to_string() } else { format!("{}.{}", prefix, name) };
+    let contains = |span: Option<std::ops::Range<usize>>| span.is_some_and(|span| span.contains(&offset));
+
+    for (name, item) in table.iter() {
+        let path = join(name);
+        match item {
+            Item::ArrayOfTables(tables) => {
+                for (index, entry) in tables.iter().enumerate() {
+                    let path = format!("{}[{}]", path, index);
+                    if let Some(found) = key_at(entry, offset, &path) {
+                        return Some(found);
+                    }
+                    if contains(entry.span()) {
+                        return Some(path);
+                    }
+                }
+            }
+            Item::Table(entry) => {
+                if let Some(found) = key_at(entry, offset, &path) {
+                    return Some(found);
+                }
+                if contains(entry.span()) {
+                    return Some(path);
+                }
+            }
+            _ => {
+                let key_span = table.get_key_value(name).and_then(|(key, _)| key.span());
+                if contains(item.span()) || contains(key_span) {
+                    return Some(path);
+                }
+            }
+        }
+    }
+    None
+}
+
+fn line_of(source: &str, offset: usize) -> usize {
+    source[..offset.min(source.len())].matches('\n').count() + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn problems(source: &str) -> Vec<Problem> {
+        parse(source).map(|parsed| parsed.settings).unwrap_err()
+    }
+
+    fn find<'a>(problems: &'a [Problem], key: &str) -> &'a Problem {
+        problems
+            .iter()
+            .find(|problem| problem.key == key)
+            .unwrap_or_else(|| panic!("no problem at {} in {:?}", key, problems))
+    }
+
+    #[test]
+    fn wrong_type_names_the_dotted_key_and_line() {
+        let source = "\
+version = 2
+
+[dashboard]
+title = \"db1\"
+
+[system.intervals]
+memory_ms = 1000
+cpu_ms = \"fast\"
+
+[display]
+show_cpu_graph = true
+";
+        let problems = problems(source);
+        assert_eq!(problems.len(), 1, "{:?}", problems);
+        assert_eq!(problems[0].key, "system.intervals.cpu_ms");
+        assert_eq!(problems[0].line, Some(8));
+    }
+
+    #[test]
+    fn every_problem_is_reported_with_its_line() {
+        let source = "\
+[system]
+max_processes_displayed = 0
+
+[system.intervals]
+cpu_ms = 10
+disks_ms = 120000
+
+[alerts]
+psi_some_avg10 = 150.0
+";
+        let problems = problems(source);
+        assert_eq!(problems.len(), 4, "{:?}", problems);
+        assert_eq!(find(&problems, "system.max_processes_displayed").line, Some(2));
+        assert_eq!(find(&problems, "system.intervals.cpu_ms").line, Some(5));
+        assert_eq!(find(&problems, "system.intervals.disks_ms").line, Some(6));
+        assert_eq!(find(&problems, "alerts.psi_some_avg10").line, Some(9));
+    }
+
+    #[test]
+    fn history_lengths_are_checked_against_max_history_entries() {
+        let source = "\
+[dashboard]
+max_history_entries = 100
+
+[system]
+cpu_history_length = 500
+memory_history_length = 100
+";
+        let problems = problems(source);
+        assert_eq!(problems.len(), 1, "{:?}", problems);
+        let problem = find(&problems, "system.cpu_history_length");
+        assert_eq!(problem.line, Some(5));
+        assert_eq!(problem.message, "500 exceeds dashboard.max_history_entries (100)");
+
+        // Raising the limit in the same file makes it valid
+        assert!(parse("[dashboard]\nmax_history_entries = 500\n[system]\ncpu_history_length = 500\n").is_ok());
+    }
+
+    #[test]
+    fn disk_warning_above_critical_is_a_problem() {
+        let problems = problems("[alerts]\ndisk_warning_percent = 95.0\ndisk_critical_percent = 90.0\n");
+        assert_eq!(find(&problems, "alerts.disk_warning_percent").line, Some(2));
+    }
+
+    #[test]
+    fn keymap_conflicts_point_at_the_bindings_table() {
+        let source = "\
+[display]
+show_cpu_graph = true
+
+[keybindings.bindings]
+refresh = [\"q\"]
+";
+        let problems = problems(source);
+        let problem = find(&problems, "keybindings.bindings");
+        assert_eq!(problem.line, Some(4));
+        assert!(problem.message.contains("\"q\" is bound to both"), "{}", problem.message);
+    }
+
+    #[test]
+    fn unknown_action_names_its_binding() {
+        let problems = problems("[keybindings.bindings]\nreboot = [\"R\"]\n");
+        let problem = find(&problems, "keybindings.bindings.reboot");
+        assert_eq!(problem.line, Some(2));
+    }
+
+    #[test]
+    fn problems_in_arrays_of_tables_name_the_entry() {
+        let source = "\
+[agent]
+allow_anonymous = true
+
+[[agent.clients]]
+name = \"a\"
+token = \"same\"
+
+[[agent.clients]]
+name = \"b\"
+token = \"same\"
+";
+        let problems = problems(source);
+        assert_eq!(problems.len(), 1, "{:?}", problems);
+        assert_eq!(find(&problems, "agent.clients[1].token").line, Some(10));
+    }
+
+    #[test]
+    fn syntax_errors_have_a_line() {
+        let problems = problems("[dashboard]\ntitle = \"unterminated\n");
+        assert_eq!(problems[0].key, "(file)");
+        assert_eq!(problems[0].line, Some(2));
+    }
+}
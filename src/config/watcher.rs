@@ -0,0 +1,65 @@
+use anyhow::{Context, Result};
+use log::debug;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::Path;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::time::timeout;
+
+/// Editors save in several steps (truncate then write, or write a temporary
+/// file and rename it over); wait this long for a burst of events to end
+const SETTLE_TIME: Duration = Duration::from_millis(250);
+
+/// Keeps a config file watched; dropping it stops the notifications
+pub struct ConfigWatcher {
+    _watcher: RecommendedWatcher,
+}
+
+/// Watch `path` and send once after each burst of changes to it.
+///
+/// The directory is watched rather than the file itself, so saves that
+/// replace the file with a new one keep being seen.
+pub fn watch(path: &Path) -> Result<(ConfigWatcher, mpsc::UnboundedReceiver<()>)> {
+    let file_name = path
+        .file_name()
+        .with_context(|| format!("{} is not a file", path.display()))?
+        .to_os_string();
+    let directory = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => Path::new(".").to_path_buf(),
+    };
+
+    let (events, mut raw) = mpsc::unbounded_channel();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| match event {
+        Ok(event) if matches!(event.kind, EventKind::Access(_)) => {}
+        Ok(event) => {
+            if event.paths.iter().any(|changed| changed.file_name() == Some(file_name.as_os_str())) {
+                let _ = events.send(());
+            }
+        }
+        Err(err) => debug!("Config watch error: {}", err),
+    })
+    .context("Failed to start watching the config file")?;
+    watcher
+        .watch(&directory, RecursiveMode::NonRecursive)
+        .with_context(|| format!("Failed to watch {}", directory.display()))?;
+
+    let (changes, receiver) = mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        // Ends when the watcher, and with it the sender, is dropped
+        while raw.recv().await.is_some() {
+            loop {
+                match timeout(SETTLE_TIME, raw.recv()).await {
+                    Ok(Some(())) => continue,
+                    Ok(None) => return,
+                    Err(_) => break,
+                }
+            }
+            if changes.send(()).is_err() {
+                return;
+            }
+        }
+    });
+
+    Ok((ConfigWatcher { _watcher: watcher }, receiver))
+}
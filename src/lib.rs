@@ -1,8 +1,9 @@
 pub mod config;
+pub mod remote;
 pub mod system;
 pub mod ui;
 pub mod utils;
 
 pub use config::Settings;
 pub use system::SystemMonitor;
-pub use ui::{Dashboard, EventHandler};
\ No newline at end of file
+pub use ui::{AppEvent, Dashboard, EventHandler};
\ No newline at end of file
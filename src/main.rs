@@ -1,42 +1,96 @@
-use anyhow::Result;
-use clap::Parser;
+use anyhow::{anyhow, Context, Result};
+use clap::{Parser, Subcommand};
 use crossterm::{
     event::{DisableMouseCapture, EnableMouseCapture},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
-use log::info;
+use log::{info, warn};
 use ratatui::{
     backend::CrosstermBackend,
     Terminal,
 };
+use std::fs;
 use std::io;
-use tokio::time::{interval, Duration};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::{mpsc, watch};
 
-mod config;
-mod system;
-mod ui;
-mod utils;
-
-use config::Settings;
-use system::SystemMonitor;
-use ui::{Dashboard, EventHandler};
+use system_monitor::config::{watcher, ConfigWatcher, InvalidConfig, Override, Parsed};
+use system_monitor::remote::{self, Access, Credentials, Endpoint, FleetHost, Source};
+use system_monitor::system::{spawn_collector, CollectorCommand, CollectorHandle, SystemSnapshot};
+use system_monitor::ui::events::AppAction;
+use system_monitor::ui::{Command, Keymap, UiState};
+use system_monitor::{AppEvent, Dashboard, EventHandler, Settings, SystemMonitor};
 
 #[derive(Parser)]
 #[command(name = "system-monitor")]
 #[command(about = "Real-time System Monitor Dashboard")]
 struct Cli {
-    /// Configuration file path
-    #[arg(short, long, default_value = "config.toml")]
-    config: String,
+    /// Configuration file; without it the first of
+    /// $XDG_CONFIG_HOME/system-monitor/config.toml and /etc/system-monitor/config.toml
+    /// that exists is used, or the built-in defaults
+    #[arg(short, long)]
+    config: Option<PathBuf>,
     
-    /// Refresh interval in seconds
-    #[arg(short, long, default_value = "1")]
-    refresh: u64,
+    /// Refresh interval in seconds for every collector, overriding [system.intervals]
+    #[arg(short, long)]
+    refresh: Option<u64>,
+
+    /// Override one setting, e.g. --set alerts.disk_warning_percent=80; repeatable,
+    /// and applied after the config file and SYSMON_* variables
+    #[arg(long = "set", value_name = "SECTION.KEY=VALUE", value_parser = Override::parse_assignment)]
+    set: Vec<Override>,
     
     /// Enable debug logging
     #[arg(short, long)]
     debug: bool,
+
+    #[command(subcommand)]
+    mode: Option<Mode>,
+}
+
+#[derive(Subcommand)]
+enum Mode {
+    /// Collect headless and serve snapshots to remote dashboards
+    Agent {
+        /// `host:port` or `unix:/path/to/socket`, overriding [agent] listen
+        #[arg(short, long)]
+        listen: Option<String>,
+    },
+    /// Show a remote agent's data instead of this machine's
+    Connect {
+        /// The agent's `host:port` or `unix:/path/to/socket`, or the name of a [[fleet.hosts]] entry
+        address: String,
+        /// Pre-shared token, overriding the matching [[fleet.hosts]] entry's
+        #[arg(short, long)]
+        token: Option<String>,
+    },
+    /// Work with the configuration file
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigCommand {
+    /// Validate the configuration file, with any overrides, without starting the dashboard
+    Check,
+    /// Print the configuration file
+    Show {
+        /// Print the settings in use instead: defaults, the file, SYSMON_* variables,
+        /// --set and --refresh merged
+        #[arg(long)]
+        effective: bool,
+    },
+    /// Write the default settings to --config, or to
+    /// $XDG_CONFIG_HOME/system-monitor/config.toml
+    Init {
+        /// Replace a file that already exists
+        #[arg(long)]
+        force: bool,
+    },
 }
 
 #[tokio::main]
@@ -54,83 +108,516 @@ async fn main() -> Result<()> {
             .init();
     }
     
+    // Later overrides win: the environment, then --set, then --refresh
+    let mut overrides = Override::from_env(std::env::vars());
+    overrides.extend(cli.set.iter().cloned());
+    if let Some(seconds) = cli.refresh {
+        overrides.extend(Override::refresh(seconds)?);
+    }
+    let source = SettingsSource {
+        path: Settings::find(cli.config.as_deref()),
+        overrides,
+    };
+
+    if let Some(Mode::Config { command }) = &cli.mode {
+        return run_config_command(&source, cli.config.as_deref(), command);
+    }
+
     info!("Starting System Monitor Dashboard");
     
     // Load configuration
-    let settings = Settings::load(&cli.config)?;
-    info!("Configuration loaded from: {}", cli.config);
+    let Parsed { settings, warnings } = source.load()?;
+    match &source.path {
+        Some(path) => {
+            for warning in &warnings {
+                warn!("{}: {}", path.display(), warning);
+            }
+            info!("Configuration loaded from: {}", path.display());
+        }
+        None => info!("No configuration file found, using defaults; `config init` writes one"),
+    }
+
+    // Resolve key bindings up front so conflicts are reported before the TUI starts
+    let keymap = Keymap::from_settings(&settings.keybindings).context("Invalid [keybindings]")?;
     
-    // Initialize system monitor
-    let mut system_monitor = SystemMonitor::new();
-    system_monitor.refresh_all();
+    let (hosts, active) = match cli.mode {
+        Some(Mode::Agent { listen }) => {
+            let endpoint = Endpoint::parse(&listen.unwrap_or_else(|| settings.agent.listen.clone()));
+            return run_agent(&settings, &endpoint).await;
+        }
+        Some(Mode::Config { .. }) => unreachable!("handled before loading settings"),
+        Some(Mode::Connect { address, token }) => {
+            // A configured host supplies its address, token and TLS settings
+            let configured = settings
+                .fleet
+                .hosts
+                .iter()
+                .find(|host| host.name == address || host.address == address);
+            let (endpoint, credentials) = match configured {
+                Some(host) => {
+                    let endpoint = Endpoint::parse(&host.address);
+                    let token = token.or_else(|| host.token.clone());
+                    let credentials = Credentials::new(&endpoint, token, host.tls.as_ref())
+                        .with_context(|| format!("Invalid [[fleet.hosts]] entry {}", host.name))?;
+                    (endpoint, credentials)
+                }
+                None => {
+                    let endpoint = Endpoint::parse(&address);
+                    let credentials = Credentials::new(&endpoint, token, None)?;
+                    (endpoint, credentials)
+                }
+            };
+            let (snapshots, remote) = remote::connect(endpoint.clone(), credentials).await?;
+            let mut hosts = start_fleet(&settings)?;
+            let host = FleetHost {
+                name: endpoint.to_string(),
+                address: Some(endpoint.clone()),
+                source: Source::Remote(remote),
+                snapshots,
+            };
+            // Reuse the configured entry (and its name) when the agent is already listed
+            let active = match hosts.iter().position(|existing| existing.address.as_ref() == Some(&endpoint)) {
+                Some(index) => {
+                    let name = hosts[index].name.clone();
+                    let replaced = std::mem::replace(&mut hosts[index], FleetHost { name, ..host });
+                    replaced.source.abort();
+                    index
+                }
+                None => {
+                    hosts.push(host);
+                    hosts.len() - 1
+                }
+            };
+            (hosts, active)
+        }
+        None => (start_fleet(&settings)?, 0),
+    };
     
+    // Read the state and start watching the config before the terminal
+    // switches over, so their warnings stay readable
+    let state_path = UiState::default_path();
+    let saved_state = state_path.as_deref().and_then(|path| match UiState::load(path) {
+        Ok(state) => state,
+        Err(err) => {
+            warn!("Starting with the default view: {:#}", err);
+            None
+        }
+    });
+    let config_watch = source.path.as_ref().and_then(|path| match watcher::watch(path) {
+        Ok(watch) => Some(watch),
+        Err(err) => {
+            warn!("Settings will not reload on change: {:#}", err);
+            None
+        }
+    });
+
     // Setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    execute!(stdout, EnterAlternateScreen)?;
+    if settings.dashboard.mouse_capture {
+        execute!(stdout, EnableMouseCapture)?;
+    }
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
     
     // Initialize dashboard and event handler
-    let mut dashboard = Dashboard::new(settings.clone());
-    let mut event_handler = EventHandler::new();
-    
-    // Create refresh interval
-    let mut refresh_interval = interval(Duration::from_secs(cli.refresh));
+    let mut dashboard = Dashboard::new(settings.clone(), keymap);
+    if let Some(state) = saved_state {
+        dashboard.restore_ui_state(state);
+    }
+    if let Some(path) = source.path.as_ref().filter(|_| !warnings.is_empty()) {
+        dashboard.show_message(format!(
+            "{} has {} warning{}; run `system-monitor config check`",
+            path.display(),
+            warnings.len(),
+            if warnings.len() == 1 { "" } else { "s" }
+        ));
+    }
+    let mut event_handler = EventHandler::new(hosts.iter().map(|host| host.snapshots.clone()).collect());
+    let _config_watcher: Option<ConfigWatcher> = config_watch.map(|(config_watcher, changes)| {
+        event_handler.watch_config(changes);
+        config_watcher
+    });
     
     // Main application loop
     let result = run_app(
         &mut terminal,
         &mut dashboard,
         &mut event_handler,
-        &mut system_monitor,
-        &mut refresh_interval,
+        &hosts,
+        active,
+        &source,
     ).await;
+    for host in &hosts {
+        host.source.abort();
+    }
     
     // Restore terminal
     disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    if dashboard.settings().dashboard.mouse_capture {
+        execute!(terminal.backend_mut(), DisableMouseCapture)?;
+    }
     terminal.show_cursor()?;
     
     if let Err(err) = result {
         eprintln!("Error: {}", err);
     }
+    if let Some(path) = &state_path {
+        if let Err(err) = dashboard.ui_state().save(path) {
+            warn!("Could not save the current view: {:#}", err);
+        }
+    }
     
     info!("System Monitor Dashboard shutdown complete");
     Ok(())
 }
 
+/// Where settings come from, kept so they can be read again when the file changes
+struct SettingsSource {
+    /// None when no config file was found and the defaults are in use
+    path: Option<PathBuf>,
+    /// SYSMON_* variables, then --set, then --refresh, applied over the file
+    overrides: Vec<Override>,
+}
+
+impl SettingsSource {
+    fn load(&self) -> Result<Parsed> {
+        Settings::load_with_overrides(self.path.as_deref(), &self.overrides)
+    }
+}
+
+fn run_config_command(source: &SettingsSource, explicit: Option<&Path>, command: &ConfigCommand) -> Result<()> {
+    let no_file = || {
+        let searched: Vec<String> = Settings::search_path().iter().map(|path| path.display().to_string()).collect();
+        anyhow!("No configuration file in {}; `config init` writes one", searched.join(" or "))
+    };
+    match command {
+        ConfigCommand::Check => {
+            let path = source.path.as_ref().ok_or_else(no_file)?;
+            let parsed = source.load()?;
+            for warning in &parsed.warnings {
+                println!("{}: warning: {}", path.display(), warning);
+            }
+            println!("{}: OK", path.display());
+            Ok(())
+        }
+        ConfigCommand::Show { effective: true } => {
+            let parsed = source.load()?;
+            for warning in &parsed.warnings {
+                eprintln!("warning: {}", warning);
+            }
+            let content = toml::to_string_pretty(&parsed.settings.redacted()).context("Failed to serialize settings to TOML")?;
+            print!("{}", content);
+            Ok(())
+        }
+        ConfigCommand::Show { effective: false } => {
+            let path = source.path.as_ref().ok_or_else(no_file)?;
+            let content = fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+            print!("{}", content);
+            Ok(())
+        }
+        ConfigCommand::Init { force } => {
+            let path = explicit
+                .map(Path::to_path_buf)
+                .or_else(Settings::user_config_path)
+                .ok_or_else(|| anyhow!("Neither XDG_CONFIG_HOME nor HOME is set; pass --config"))?;
+            Settings::init(&path, *force)?;
+            println!("Wrote default settings to {}", path.display());
+            Ok(())
+        }
+    }
+}
+
+/// Initialize the system monitor and hand it to the background collector
+fn start_collector(settings: &Settings) -> (watch::Receiver<Arc<SystemSnapshot>>, CollectorHandle) {
+    let mut system_monitor = SystemMonitor::new();
+    system_monitor.set_history_lengths(settings.history_lengths());
+    system_monitor.refresh_all();
+    let schedule = settings
+        .system
+        .intervals
+        .schedule(settings.system.enable_process_monitoring);
+    spawn_collector(system_monitor, schedule)
+}
+
+/// This machine first, then every agent under [[fleet.hosts]], connecting in the background
+fn start_fleet(settings: &Settings) -> Result<Vec<FleetHost>> {
+    let (snapshots, collector) = start_collector(settings);
+    let name = snapshots.borrow().host.host_name.clone().unwrap_or_else(|| "local".to_string());
+    let mut hosts = vec![FleetHost { name, address: None, source: Source::Local(collector), snapshots }];
+
+    for configured in &settings.fleet.hosts {
+        let endpoint = Endpoint::parse(&configured.address);
+        let credentials = Credentials::new(&endpoint, configured.token.clone(), configured.tls.as_ref())
+            .with_context(|| format!("Invalid [[fleet.hosts]] entry {}", configured.name))?;
+        let (snapshots, remote) = remote::connect_in_background(endpoint.clone(), credentials);
+        hosts.push(FleetHost {
+            name: configured.name.clone(),
+            address: Some(endpoint),
+            source: Source::Remote(remote),
+            snapshots,
+        });
+    }
+    Ok(hosts)
+}
+
+/// Collect without a terminal and serve snapshots until Ctrl+C
+async fn run_agent(settings: &Settings, endpoint: &Endpoint) -> Result<()> {
+    let access = Access::from_settings(&settings.agent).context("Invalid [agent] settings")?;
+    let (snapshots, collector) = start_collector(settings);
+    let collector = Arc::new(collector);
+
+    let result = tokio::select! {
+        result = remote::run_agent(endpoint, access, snapshots, collector.clone()) => result,
+        _ = tokio::signal::ctrl_c() => {
+            info!("Agent shutting down");
+            Ok(())
+        }
+    };
+    collector.abort();
+    result
+}
+
 async fn run_app(
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
     dashboard: &mut Dashboard,
     event_handler: &mut EventHandler,
-    system_monitor: &mut SystemMonitor,
-    refresh_interval: &mut tokio::time::Interval,
+    hosts: &[FleetHost],
+    mut active: usize,
+    source: &SettingsSource,
 ) -> Result<()> {
+    let mut snapshot: Arc<SystemSnapshot> = hosts[active].latest();
+    let mut needs_redraw = true;
+    // The config file as the Settings tab last wrote it
+    let mut last_saved: Option<String> = None;
+
     loop {
-        // Draw the UI
-        terminal.draw(|f| dashboard.render(f, system_monitor))?;
+        // Only draw when something on screen may have changed
+        if needs_redraw {
+            dashboard.set_hosts(hosts.iter().map(FleetHost::status).collect(), active);
+            terminal.draw(|f| dashboard.render(f, &snapshot))?;
+        }
         
-        tokio::select! {
+        needs_redraw = match event_handler.next_event().await {
             // Handle user input events
-            event = event_handler.next_event() => {
-                if let Some(event) = event {
-                    if dashboard.handle_event(event)? {
-                        break; // Exit requested
+            Some(AppEvent::Input(event)) => {
+                match dashboard.handle_event(event)? {
+                    Some(Command::Quit) => break, // Exit requested
+                    Some(Command::SwitchHost(index)) if index < hosts.len() => {
+                        hosts[active].source.send(CollectorCommand::Resume);
+                        active = index;
+                        snapshot = hosts[active].latest();
                     }
+                    Some(Command::ApplySettings(settings)) => match Keymap::from_settings(&settings.keybindings) {
+                        Ok(keymap) => {
+                            switch_settings(terminal, dashboard, hosts, *settings, keymap)?;
+                        }
+                        Err(err) => dashboard.show_message(format!("Invalid [keybindings]: {:#}", err)),
+                    },
+                    Some(Command::SaveSettings(keys)) => {
+                        if let Some(saved) = save_settings(terminal, dashboard, hosts, source, &keys)? {
+                            last_saved = Some(saved);
+                        }
+                    }
+                    Some(command) => execute_command(dashboard, &hosts[active].source, command, &event_handler.messages()),
+                    None => {}
                 }
+                true
             }
+
+            // Terminal size changed; ratatui resizes its buffers on the next draw
+            Some(AppEvent::Resize) => true,
             
-            // Refresh system data
-            _ = refresh_interval.tick() => {
-                system_monitor.refresh_all();
+            // New data from a collector; other hosts only show on the Fleet tab
+            Some(AppEvent::Snapshot { host, snapshot: latest }) if host == active => {
+                snapshot = latest;
+                true
             }
-        }
+            Some(AppEvent::Snapshot { .. }) => dashboard.shows_fleet(),
+
+            // The config file was saved; apply it if it still loads
+            Some(AppEvent::ConfigChanged) => {
+                reload_settings(terminal, dashboard, hosts, source, last_saved.as_deref())?;
+                true
+            }
+
+            Some(AppEvent::Message(message)) => {
+                dashboard.show_message(message);
+                true
+            }
+
+            // Terminal input closed
+            None => break,
+        };
     }
     
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Read the config again and apply it to the dashboard and the local
+/// collector, keeping the current settings when it no longer loads. Outcomes
+/// go to the status bar; logging would draw over the screen.
+///
+/// Nothing happens when the file holds just what the Settings tab
+/// `last_saved`, which is already applied, and the file is not reloaded over
+/// unsaved Settings tab changes.
+fn reload_settings(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    dashboard: &mut Dashboard,
+    hosts: &[FleetHost],
+    source: &SettingsSource,
+    last_saved: Option<&str>,
+) -> Result<()> {
+    let path = match &source.path {
+        Some(path) => {
+            if last_saved.is_some() && fs::read_to_string(path).ok().as_deref() == last_saved {
+                return Ok(());
+            }
+            path.display().to_string()
+        }
+        None => "settings".to_string(),
+    };
+    let unsaved = dashboard.unsaved_setting_changes();
+    if unsaved > 0 {
+        dashboard.show_message(format!(
+            "{} changed on disk but was not reloaded over {} unsaved Settings tab change{}; \
+             {} saves them and loads the rest of the file",
+            path,
+            unsaved,
+            if unsaved == 1 { "" } else { "s" },
+            dashboard.keymap().hint(&AppAction::SaveSettings)
+        ));
+        return Ok(());
+    }
+    let reloaded = source.load().and_then(|parsed| {
+        let keymap = Keymap::from_settings(&parsed.settings.keybindings).context("Invalid [keybindings]")?;
+        Ok((parsed, keymap))
+    });
+    let (Parsed { settings, warnings }, keymap) = match reloaded {
+        Ok(reloaded) => reloaded,
+        Err(err) => {
+            let reason = match err.downcast_ref::<InvalidConfig>() {
+                Some(invalid) if invalid.problems.len() > 1 => {
+                    format!("{} (and {} more)", invalid.problems[0], invalid.problems.len() - 1)
+                }
+                Some(invalid) => invalid.problems[0].to_string(),
+                None => format!("{:#}", err),
+            };
+            dashboard.show_message(format!("Config reload failed, keeping previous settings: {}", reason));
+            return Ok(());
+        }
+    };
+
+    let needs_restart = switch_settings(terminal, dashboard, hosts, settings, keymap)?;
+
+    let mut message = format!("Reloaded {}", path);
+    if !warnings.is_empty() {
+        message.push_str(&format!(" with {} warning{}", warnings.len(), if warnings.len() == 1 { "" } else { "s" }));
+    }
+    if needs_restart {
+        message.push_str("; [agent] and [fleet] changes apply after a restart");
+    }
+    dashboard.show_message(message);
+    Ok(())
+}
+
+/// Put new settings into effect on the dashboard, the terminal and the local
+/// collectors; returns whether some of the change only applies after a restart
+fn switch_settings(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    dashboard: &mut Dashboard,
+    hosts: &[FleetHost],
+    settings: Settings,
+    keymap: Keymap,
+) -> Result<bool> {
+    let previous = dashboard.settings();
+    match (previous.dashboard.mouse_capture, settings.dashboard.mouse_capture) {
+        (false, true) => execute!(terminal.backend_mut(), EnableMouseCapture)?,
+        (true, false) => execute!(terminal.backend_mut(), DisableMouseCapture)?,
+        _ => {}
+    }
+    let needs_restart = previous.agent != settings.agent || previous.fleet != settings.fleet;
+
+    // Remote hosts sample on the agent's own settings
+    for host in hosts.iter().filter(|host| !host.source.is_remote()) {
+        let schedule = settings.system.intervals.schedule(settings.system.enable_process_monitoring);
+        host.source.send(CollectorCommand::SetSchedule(schedule));
+        host.source.send(CollectorCommand::SetHistory(settings.history_lengths()));
+    }
+    dashboard.apply_settings(settings, keymap);
+    Ok(needs_restart)
+}
+
+/// Write the keys changed on the Settings tab into the config file in use,
+/// or start the per-user file when the defaults are in use. Returns the
+/// file as written, so the reload it triggers can be skipped.
+fn save_settings(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    dashboard: &mut Dashboard,
+    hosts: &[FleetHost],
+    source: &SettingsSource,
+    keys: &[String],
+) -> Result<Option<String>> {
+    let Some(path) = source.path.clone().or_else(Settings::user_config_path) else {
+        dashboard.show_message("Neither XDG_CONFIG_HOME nor HOME is set; restart with --config to save settings");
+        return Ok(None);
+    };
+    if let Err(err) = dashboard.settings().save_keys(&path, keys) {
+        dashboard.show_message(format!("Could not save settings: {:#}", err));
+        return Ok(None);
+    }
+    dashboard.settings_saved();
+    let mut message = format!(
+        "Saved {} setting{} to {}",
+        keys.len(),
+        if keys.len() == 1 { "" } else { "s" },
+        path.display()
+    );
+
+    // The file may have been edited elsewhere while these changes were
+    // unsaved; whatever else it now says applies too
+    if source.path.is_some() {
+        let reloaded = source.load().ok().and_then(|parsed| {
+            let keymap = Keymap::from_settings(&parsed.settings.keybindings).ok()?;
+            Some((parsed.settings, keymap))
+        });
+        if let Some((settings, keymap)) = reloaded.filter(|(settings, _)| settings != dashboard.settings()) {
+            if switch_settings(terminal, dashboard, hosts, settings, keymap)? {
+                message.push_str(", and loaded its other changes; [agent] and [fleet] changes apply after a restart");
+            } else {
+                message.push_str(", and loaded its other changes");
+            }
+        }
+    }
+    dashboard.show_message(message);
+    Ok(fs::read_to_string(&path).ok())
+}
+
+/// Carry out a command the dashboard cannot perform on its own
+fn execute_command(dashboard: &mut Dashboard, source: &Source, command: Command, messages: &mpsc::UnboundedSender<String>) {
+    match command {
+        Command::Quit | Command::SwitchHost(_) | Command::ApplySettings(_) | Command::SaveSettings(_) => {}
+        Command::Refresh => source.send(CollectorCommand::RefreshNow),
+        Command::SetPaused(true) => source.send(CollectorCommand::Pause),
+        Command::SetPaused(false) => source.send(CollectorCommand::Resume),
+        Command::SetSchedule(_) if source.is_remote() => {
+            dashboard.show_message("Collector intervals are set in the agent's config");
+        }
+        Command::SetSchedule(schedule) => source.send(CollectorCommand::SetSchedule(schedule)),
+        // Remote PIDs are signalled by the agent, which checks the client's
+        // permission; its answer can take a while, so don't hold up the loop
+        Command::KillProcess { pid, name } => {
+            let outcome = source.terminate(pid);
+            let messages = messages.clone();
+            tokio::spawn(async move {
+                let message = match outcome.await {
+                    Ok(()) => format!("Sent SIGTERM to {} (PID {})", name, pid),
+                    Err(err) => format!("Could not terminate {} (PID {}): {}", name, pid, err),
+                };
+                let _ = messages.send(message);
+            });
+        }
+    }
+}
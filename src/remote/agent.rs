@@ -0,0 +1,641 @@
+use anyhow::{anyhow, bail, Context, Result};
+use log::{debug, info, warn};
+use std::io;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufRead, AsyncWrite, BufReader};
+use tokio::net::TcpListener;
+use tokio::sync::{mpsc, watch, OwnedSemaphorePermit, Semaphore};
+use tokio_rustls::rustls::pki_types::CertificateDer;
+
+use crate::config::settings::Permission;
+use crate::system::collector::MIN_COLLECTOR_INTERVAL;
+use crate::system::{terminate_process, CollectorCommand, CollectorHandle, SystemSnapshot};
+use super::auth::{Access, Identity};
+use super::protocol::{
+    receive, send, AgentMessage, ClientMessage, Connection, Endpoint, MAX_CLIENT_MESSAGE, PROTOCOL_VERSION,
+};
+
+/// How long a client gets to finish the TLS and hello handshakes
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Most clients served at once; more are disconnected straight away
+const MAX_CLIENTS: usize = 64;
+
+/// Pause after a failed accept, e.g. when out of file descriptors, before
+/// trying again
+const ACCEPT_BACKOFF: Duration = Duration::from_millis(100);
+
+/// Serve every snapshot `snapshots` publishes to each client that connects to
+/// `endpoint` and passes `access`, up to `MAX_CLIENTS` at a time. Only
+/// returns if it cannot listen; drop the future to stop.
+pub async fn run_agent(
+    endpoint: &Endpoint,
+    access: Access,
+    snapshots: watch::Receiver<Arc<SystemSnapshot>>,
+    collector: Arc<CollectorHandle>,
+) -> Result<()> {
+    let listener = AgentListener::bind(endpoint).await?;
+    serve(listener, access, snapshots, collector).await
+}
+
+/// Accept clients on an already bound `listener`, as [`run_agent`] does
+pub async fn serve(
+    mut listener: AgentListener,
+    access: Access,
+    snapshots: watch::Receiver<Arc<SystemSnapshot>>,
+    collector: Arc<CollectorHandle>,
+) -> Result<()> {
+    let endpoint = listener.endpoint()?;
+    if access.sends_tokens_in_clear() && !endpoint.is_local() {
+        warn!("Tokens are sent unencrypted to {}; configure [agent.tls] to protect them", endpoint);
+    }
+    info!("Agent listening on {}", endpoint);
+    let access = Arc::new(access);
+    let collector = Arc::new(SharedCollector::new(collector));
+    let slots = Arc::new(Semaphore::new(MAX_CLIENTS));
+
+    loop {
+        let (stream, peer) = match listener.accept().await {
+            Ok(client) => client,
+            Err(err) => {
+                warn!("Failed to accept a connection: {}", err);
+                tokio::time::sleep(ACCEPT_BACKOFF).await;
+                continue;
+            }
+        };
+        let Ok(slot) = slots.clone().try_acquire_owned() else {
+            warn!("Turned away {}: already serving {} clients", peer, MAX_CLIENTS);
+            continue;
+        };
+        spawn_client(stream, peer, slot, access.clone(), snapshots.clone(), collector.clone());
+    }
+}
+
+/// The socket an agent accepts clients on
+pub enum AgentListener {
+    Tcp(TcpListener),
+    #[cfg(unix)]
+    Unix {
+        listener: tokio::net::UnixListener,
+        socket: SocketFile,
+        /// Unix clients have no address, so they are numbered instead
+        next_id: u64,
+    },
+}
+
+impl AgentListener {
+    pub async fn bind(endpoint: &Endpoint) -> Result<AgentListener> {
+        match endpoint {
+            Endpoint::Tcp(address) => {
+                let listener = TcpListener::bind(address)
+                    .await
+                    .with_context(|| format!("Failed to listen on {}", address))?;
+                Ok(AgentListener::Tcp(listener))
+            }
+            #[cfg(unix)]
+            Endpoint::Unix(path) => {
+                remove_stale_socket(path)?;
+                let listener = tokio::net::UnixListener::bind(path)
+                    .with_context(|| format!("Failed to listen on {}", path.display()))?;
+                Ok(AgentListener::Unix { listener, socket: SocketFile(path.clone()), next_id: 0 })
+            }
+            #[cfg(not(unix))]
+            Endpoint::Unix(_) => bail!("Unix sockets are not supported on this platform"),
+        }
+    }
+
+    /// Where clients reach the listener, with the port the system picked
+    /// when bound to port 0
+    pub fn endpoint(&self) -> Result<Endpoint> {
+        match self {
+            AgentListener::Tcp(listener) => Ok(Endpoint::Tcp(listener.local_addr()?.to_string())),
+            #[cfg(unix)]
+            AgentListener::Unix { socket, .. } => Ok(Endpoint::Unix(socket.0.clone())),
+        }
+    }
+
+    /// The next client and a name for it in the log
+    async fn accept(&mut self) -> io::Result<(Box<dyn Connection>, String)> {
+        match self {
+            AgentListener::Tcp(listener) => {
+                let (stream, peer) = listener.accept().await?;
+                Ok((Box::new(stream), peer.to_string()))
+            }
+            #[cfg(unix)]
+            AgentListener::Unix { listener, next_id, .. } => {
+                let (stream, _) = listener.accept().await?;
+                *next_id += 1;
+                Ok((Box::new(stream), format!("unix client {}", next_id)))
+            }
+        }
+    }
+}
+
+/// Remove a socket left behind by an agent that did not shut down cleanly,
+/// which would make bind fail. Anything else at `path`, or a socket another
+/// agent still answers on, is left alone.
+#[cfg(unix)]
+fn remove_stale_socket(path: &std::path::Path) -> Result<()> {
+    use std::os::unix::fs::FileTypeExt;
+
+    let Ok(metadata) = std::fs::symlink_metadata(path) else {
+        return Ok(());
+    };
+    if !metadata.file_type().is_socket() {
+        bail!("{} already exists and is not a socket", path.display());
+    }
+    if std::os::unix::net::UnixStream::connect(path).is_ok() {
+        bail!("{} is already in use by a running agent", path.display());
+    }
+    std::fs::remove_file(path).with_context(|| format!("Failed to remove stale socket {}", path.display()))
+}
+
+/// The collector as clients reach it. Refresh requests from all clients
+/// together get through at most once per `MIN_COLLECTOR_INTERVAL`, so no
+/// client can keep the host busy sampling.
+struct SharedCollector {
+    handle: Arc<CollectorHandle>,
+    last_refresh: Mutex<Option<Instant>>,
+}
+
+impl SharedCollector {
+    fn new(handle: Arc<CollectorHandle>) -> SharedCollector {
+        SharedCollector { handle, last_refresh: Mutex::new(None) }
+    }
+
+    /// Whether a refresh asked for at `now` may go ahead
+    fn allow_refresh(&self, now: Instant) -> bool {
+        let mut last_refresh = self.last_refresh.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if last_refresh.is_some_and(|last| now.saturating_duration_since(last) < MIN_COLLECTOR_INTERVAL) {
+            return false;
+        }
+        *last_refresh = Some(now);
+        true
+    }
+
+    fn refresh(&self, client: &str) {
+        if self.allow_refresh(Instant::now()) {
+            self.handle.send(CollectorCommand::RefreshNow);
+        } else {
+            debug!("Ignoring refresh from {}: the last one was too recent", client);
+        }
+    }
+}
+
+/// Removes the socket file when the agent stops
+#[cfg(unix)]
+pub struct SocketFile(std::path::PathBuf);
+
+#[cfg(unix)]
+impl Drop for SocketFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+fn spawn_client(
+    stream: Box<dyn Connection>,
+    peer: String,
+    slot: OwnedSemaphorePermit,
+    access: Arc<Access>,
+    snapshots: watch::Receiver<Arc<SystemSnapshot>>,
+    collector: Arc<SharedCollector>,
+) {
+    tokio::spawn(async move {
+        debug!("Connection from {}", peer);
+        match serve_client(stream, &peer, &access, snapshots, &collector).await {
+            Ok(()) => info!("Client disconnected: {}", peer),
+            Err(err) => warn!("Client {} dropped: {:#}", peer, err),
+        }
+        drop(slot);
+    });
+}
+
+async fn serve_client(
+    stream: Box<dyn Connection>,
+    peer: &str,
+    access: &Access,
+    mut snapshots: watch::Receiver<Arc<SystemSnapshot>>,
+    collector: &SharedCollector,
+) -> Result<()> {
+    let (mut reader, mut writer, identity) = tokio::time::timeout(HANDSHAKE_TIMEOUT, async {
+        let (stream, certificate) = accept_tls(stream, access).await?;
+        let (reader, mut writer) = tokio::io::split(stream);
+        let mut reader = BufReader::new(reader);
+        let identity = authenticate(&mut reader, &mut writer, access, certificate.as_ref()).await?;
+        Ok::<_, anyhow::Error>((reader, writer, identity))
+    })
+    .await
+    .map_err(|_| anyhow!("handshake timed out"))??;
+    let Some(identity) = identity else {
+        return Ok(());
+    };
+    info!("Client connected: {} as {} ({})", peer, identity.name, identity.permission.name());
+
+    let host_name = snapshots.borrow().host.host_name.clone();
+    let welcome = AgentMessage::Welcome { version: PROTOCOL_VERSION, host_name, permission: identity.permission };
+    send(&mut writer, &welcome).await?;
+
+    // Each side runs until the client goes away
+    let (replies, mut pending_replies) = mpsc::channel(16);
+    tokio::select! {
+        result = handle_requests(&mut reader, &identity, collector, replies) => result,
+        result = write_messages(&mut writer, &mut snapshots, &mut pending_replies) => result,
+    }
+}
+
+/// Complete the TLS handshake when the agent has a certificate, keeping the
+/// client's certificate if it presented one
+async fn accept_tls(
+    stream: Box<dyn Connection>,
+    access: &Access,
+) -> Result<(Box<dyn Connection>, Option<CertificateDer<'static>>)> {
+    let Some(acceptor) = access.tls() else {
+        return Ok((stream, None));
+    };
+    let stream = acceptor.accept(stream).await.context("TLS handshake failed")?;
+    let certificate = stream
+        .get_ref()
+        .1
+        .peer_certificates()
+        .and_then(|certs| certs.first())
+        .cloned();
+    Ok((Box::new(stream), certificate))
+}
+
+/// Read the hello and work out who is asking; None if the client left first
+async fn authenticate<R, W>(
+    reader: &mut R,
+    writer: &mut W,
+    access: &Access,
+    certificate: Option<&CertificateDer<'static>>,
+) -> Result<Option<Identity>>
+where
+    R: AsyncBufRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let mut line = String::new();
+    let token = match receive(reader, &mut line, MAX_CLIENT_MESSAGE).await? {
+        Some(ClientMessage::Hello { version, token }) if version == PROTOCOL_VERSION => token,
+        Some(ClientMessage::Hello { version, .. }) => {
+            let message = format!(
+                "protocol version {} is not supported; this agent speaks version {}",
+                version, PROTOCOL_VERSION
+            );
+            send(writer, &AgentMessage::Error { message: message.clone() }).await?;
+            bail!(message);
+        }
+        Some(_) => {
+            send(writer, &AgentMessage::Error { message: "expected hello".to_string() }).await?;
+            bail!("client did not start with hello");
+        }
+        None => return Ok(None),
+    };
+
+    match access.identify(certificate, token.as_deref()) {
+        Some(identity) => Ok(Some(identity)),
+        None => {
+            // Say no more than necessary about why
+            send(writer, &AgentMessage::Error { message: "authentication failed".to_string() }).await?;
+            bail!("authentication failed");
+        }
+    }
+}
+
+async fn handle_requests<R: AsyncBufRead + Unpin>(
+    reader: &mut R,
+    identity: &Identity,
+    collector: &SharedCollector,
+    replies: mpsc::Sender<AgentMessage>,
+) -> Result<()> {
+    let mut line = String::new();
+    while let Some(message) = receive(reader, &mut line, MAX_CLIENT_MESSAGE).await? {
+        match message {
+            ClientMessage::Refresh => collector.refresh(&identity.name),
+            ClientMessage::Terminate { pid } => {
+                let error = match identity.permission {
+                    Permission::Control => match terminate_process(pid) {
+                        Ok(()) => {
+                            info!("Client {} sent SIGTERM to PID {}", identity.name, pid);
+                            None
+                        }
+                        Err(err) => Some(err.to_string()),
+                    },
+                    Permission::ReadOnly => {
+                        warn!("Client {} is read-only; refused to signal PID {}", identity.name, pid);
+                        Some("this client is read-only on the agent".to_string())
+                    }
+                };
+                if replies.send(AgentMessage::Terminated { pid, error }).await.is_err() {
+                    return Ok(());
+                }
+            }
+            ClientMessage::Hello { .. } => debug!("Ignoring repeated hello"),
+        }
+    }
+    Ok(())
+}
+
+/// Send the current snapshot straight away, then each new one, with replies
+/// to requests in between
+async fn write_messages<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    snapshots: &mut watch::Receiver<Arc<SystemSnapshot>>,
+    replies: &mut mpsc::Receiver<AgentMessage>,
+) -> Result<()> {
+    let snapshot = snapshots.borrow_and_update().clone();
+    send(writer, &AgentMessage::Snapshot { snapshot }).await?;
+    loop {
+        tokio::select! {
+            changed = snapshots.changed() => {
+                if changed.is_err() {
+                    return Ok(());
+                }
+                let snapshot = snapshots.borrow_and_update().clone();
+                send(writer, &AgentMessage::Snapshot { snapshot }).await?;
+            }
+            reply = replies.recv() => match reply {
+                Some(message) => send(writer, &message).await?,
+                None => return Ok(()),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::settings::{AgentClientSettings, AgentSettings, AgentTlsSettings, RemoteTlsSettings};
+    use crate::remote::client::{connect, Credentials};
+    use crate::remote::tls;
+    use crate::system::{spawn_collector, SystemMonitor};
+    use std::path::{Path, PathBuf};
+    use tokio::net::TcpStream;
+    use tokio_rustls::rustls::pki_types::ServerName;
+    use tokio_rustls::TlsConnector;
+
+    fn agent_settings(allow_anonymous: bool) -> AgentSettings {
+        AgentSettings {
+            allow_anonymous,
+            clients: vec![AgentClientSettings {
+                name: "ops".to_string(),
+                token: Some("s3cret".to_string()),
+                certificate: None,
+                permission: Permission::Control,
+            }],
+            ..AgentSettings::default()
+        }
+    }
+
+    /// Certificates from `testdata/gen.sh`: `client` is a listed client,
+    /// `stranger` is signed by the same CA but not listed
+    fn testdata(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("src/remote/testdata").join(name)
+    }
+
+    fn mutual_tls_settings() -> AgentSettings {
+        AgentSettings {
+            allow_anonymous: false,
+            tls: Some(AgentTlsSettings {
+                cert: testdata("agent.pem"),
+                key: testdata("agent.key"),
+                client_ca: Some(testdata("ca.pem")),
+            }),
+            clients: vec![AgentClientSettings {
+                name: "laptop".to_string(),
+                token: None,
+                certificate: Some(testdata("client.pem")),
+                permission: Permission::Control,
+            }],
+            ..AgentSettings::default()
+        }
+    }
+
+    fn client_tls(certificate: Option<&str>) -> RemoteTlsSettings {
+        RemoteTlsSettings {
+            ca: testdata("ca.pem"),
+            cert: certificate.map(|name| testdata(&format!("{}.pem", name))),
+            key: certificate.map(|name| testdata(&format!("{}.key", name))),
+            server_name: None,
+        }
+    }
+
+    /// Bind `endpoint`, start serving on a background task and return the
+    /// endpoint clients should use
+    async fn start_agent(endpoint: &Endpoint, settings: &AgentSettings) -> Endpoint {
+        let listener = AgentListener::bind(endpoint).await.unwrap();
+        let bound = listener.endpoint().unwrap();
+        let access = Access::from_settings(settings).unwrap();
+        let (snapshots, collector) = spawn_collector(SystemMonitor::new(), Vec::new());
+        tokio::spawn(serve(listener, access, snapshots, Arc::new(collector)));
+        bound
+    }
+
+    async fn hello<S: Connection>(stream: S, version: u32, token: Option<&str>) -> Vec<AgentMessage> {
+        let (reader, mut writer) = tokio::io::split(stream);
+        let mut reader = BufReader::new(reader);
+        let hello = ClientMessage::Hello { version, token: token.map(str::to_string) };
+        send(&mut writer, &hello).await.unwrap();
+
+        // The welcome and first snapshot, or the refusal and the hang-up
+        let mut line = String::new();
+        let mut messages = Vec::new();
+        while messages.len() < 2 {
+            match receive(&mut reader, &mut line, u64::MAX).await.unwrap() {
+                Some(message) => messages.push(message),
+                None => break,
+            }
+        }
+        messages
+    }
+
+    /// Log in with `token`, ask the agent to signal `pid` and return its error, if any
+    async fn terminate(address: &str, token: Option<&str>, pid: u32) -> Option<String> {
+        let (reader, mut writer) = tokio::io::split(TcpStream::connect(address).await.unwrap());
+        let mut reader = BufReader::new(reader);
+        let hello = ClientMessage::Hello { version: PROTOCOL_VERSION, token: token.map(str::to_string) };
+        send(&mut writer, &hello).await.unwrap();
+        send(&mut writer, &ClientMessage::Terminate { pid }).await.unwrap();
+
+        // The welcome and snapshots may come first
+        let mut line = String::new();
+        loop {
+            match receive(&mut reader, &mut line, u64::MAX).await.unwrap() {
+                Some(AgentMessage::Terminated { pid: answered, error }) => {
+                    assert_eq!(answered, pid);
+                    return error;
+                }
+                Some(AgentMessage::Error { message }) => panic!("agent refused the client: {}", message),
+                Some(_) => {}
+                None => panic!("agent hung up without answering"),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn tcp_client_receives_snapshots() {
+        let endpoint = start_agent(&Endpoint::Tcp("127.0.0.1:0".to_string()), &agent_settings(true)).await;
+        assert!(!endpoint.to_string().ends_with(":0"));
+
+        let credentials = Credentials::new(&endpoint, None, None).unwrap();
+        // connect returns once the first snapshot has arrived
+        let (_snapshots, handle) = connect(endpoint, credentials).await.unwrap();
+        assert!(handle.is_connected());
+        handle.abort();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn unix_client_receives_snapshots() {
+        let dir = std::env::temp_dir().join(format!("system-monitor-agent-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("agent.sock");
+        let endpoint = start_agent(&Endpoint::Unix(path.clone()), &agent_settings(true)).await;
+        assert_eq!(endpoint, Endpoint::Unix(path.clone()));
+
+        let credentials = Credentials::new(&endpoint, Some("s3cret".to_string()), None).unwrap();
+        let (_snapshots, handle) = connect(endpoint, credentials).await.unwrap();
+        assert!(handle.is_connected());
+        handle.abort();
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn handshake_reports_version_and_permission() {
+        let endpoint = start_agent(&Endpoint::Tcp("127.0.0.1:0".to_string()), &agent_settings(true)).await;
+        let address = endpoint.to_string();
+
+        let messages = hello(TcpStream::connect(&address).await.unwrap(), PROTOCOL_VERSION, Some("s3cret")).await;
+        match &messages[..] {
+            [AgentMessage::Welcome { version, permission, .. }, AgentMessage::Snapshot { .. }] => {
+                assert_eq!(*version, PROTOCOL_VERSION);
+                assert_eq!(*permission, Permission::Control);
+            }
+            other => panic!("unexpected reply {:?}", other),
+        }
+
+        let messages = hello(TcpStream::connect(&address).await.unwrap(), PROTOCOL_VERSION, None).await;
+        assert!(matches!(
+            &messages[..],
+            [AgentMessage::Welcome { permission: Permission::ReadOnly, .. }, AgentMessage::Snapshot { .. }]
+        ));
+    }
+
+    #[tokio::test]
+    async fn handshake_refuses_other_versions_and_unknown_clients() {
+        let endpoint = start_agent(&Endpoint::Tcp("127.0.0.1:0".to_string()), &agent_settings(false)).await;
+        let address = endpoint.to_string();
+
+        let messages = hello(TcpStream::connect(&address).await.unwrap(), PROTOCOL_VERSION + 1, None).await;
+        match &messages[..] {
+            [AgentMessage::Error { message }] => assert!(message.contains("not supported"), "{}", message),
+            other => panic!("unexpected reply {:?}", other),
+        }
+
+        let messages = hello(TcpStream::connect(&address).await.unwrap(), PROTOCOL_VERSION, Some("guess")).await;
+        match &messages[..] {
+            [AgentMessage::Error { message }] => assert_eq!(message, "authentication failed"),
+            other => panic!("unexpected reply {:?}", other),
+        }
+
+        let credentials = Credentials::new(&endpoint, Some("guess".to_string()), None).unwrap();
+        assert!(connect(endpoint, credentials).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn mutual_tls_identifies_clients_by_certificate() {
+        let endpoint = start_agent(&Endpoint::Tcp("127.0.0.1:0".to_string()), &mutual_tls_settings()).await;
+        let address = endpoint.to_string();
+        let tls_stream = |certificate: Option<&'static str>| {
+            let connector = TlsConnector::from(tls::client_config(&client_tls(certificate)).unwrap());
+            let address = address.clone();
+            async move {
+                let stream = TcpStream::connect(&address).await.unwrap();
+                connector.connect(ServerName::try_from("localhost").unwrap(), stream).await.unwrap()
+            }
+        };
+
+        // A listed certificate is enough, without a token
+        let messages = hello(tls_stream(Some("client")).await, PROTOCOL_VERSION, None).await;
+        assert!(matches!(
+            &messages[..],
+            [AgentMessage::Welcome { permission: Permission::Control, .. }, AgentMessage::Snapshot { .. }]
+        ));
+
+        // Signed by the CA but not listed, and anonymous clients are turned away
+        let messages = hello(tls_stream(Some("stranger")).await, PROTOCOL_VERSION, None).await;
+        match &messages[..] {
+            [AgentMessage::Error { message }] => assert_eq!(message, "authentication failed"),
+            other => panic!("unexpected reply {:?}", other),
+        }
+
+        // No certificate at all does not get past TLS
+        let credentials = Credentials::new(&endpoint, None, Some(&client_tls(None))).unwrap();
+        assert!(connect(endpoint.clone(), credentials).await.is_err());
+
+        // The dashboard's own client, checking the agent against the CA
+        let credentials = Credentials::new(&endpoint, None, Some(&client_tls(Some("client")))).unwrap();
+        let (_snapshots, handle) = connect(endpoint, credentials).await.unwrap();
+        assert!(handle.is_connected());
+        handle.abort();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn terminate_needs_control_permission() {
+        use std::os::unix::process::ExitStatusExt;
+
+        let endpoint = start_agent(&Endpoint::Tcp("127.0.0.1:0".to_string()), &agent_settings(true)).await;
+        let address = endpoint.to_string();
+        let mut child = std::process::Command::new("sleep").arg("30").spawn().unwrap();
+
+        // Anonymous clients are read-only
+        let error = terminate(&address, None, child.id()).await;
+        assert_eq!(error.as_deref(), Some("this client is read-only on the agent"));
+        assert!(child.try_wait().unwrap().is_none());
+
+        assert_eq!(terminate(&address, Some("s3cret"), child.id()).await, None);
+        assert_eq!(child.wait().unwrap().signal(), Some(15));
+    }
+
+    #[tokio::test]
+    async fn refreshes_are_throttled_across_clients() {
+        let (_snapshots, handle) = spawn_collector(SystemMonitor::new(), Vec::new());
+        let collector = SharedCollector::new(Arc::new(handle));
+        let start = Instant::now();
+
+        assert!(collector.allow_refresh(start));
+        assert!(!collector.allow_refresh(start + MIN_COLLECTOR_INTERVAL / 2));
+        assert!(collector.allow_refresh(start + MIN_COLLECTOR_INTERVAL));
+        assert!(!collector.allow_refresh(start + MIN_COLLECTOR_INTERVAL));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn unix_socket_is_replaced_only_when_stale() {
+        let dir = std::env::temp_dir().join(format!("system-monitor-stale-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        // A live agent keeps its socket
+        let live = Endpoint::Unix(dir.join("live.sock"));
+        let _listener = AgentListener::bind(&live).await.unwrap();
+        let error = AgentListener::bind(&live).await.err().unwrap();
+        assert!(error.to_string().contains("already in use"), "{}", error);
+
+        // One that died without cleaning up does not
+        let stale = dir.join("stale.sock");
+        drop(std::os::unix::net::UnixListener::bind(&stale).unwrap());
+        assert!(stale.exists());
+        AgentListener::bind(&Endpoint::Unix(stale)).await.unwrap();
+
+        // Nor is anything that is not a socket removed
+        let file = dir.join("notes.txt");
+        std::fs::write(&file, "keep me").unwrap();
+        let error = AgentListener::bind(&Endpoint::Unix(file.clone())).await.err().unwrap();
+        assert!(error.to_string().contains("not a socket"), "{}", error);
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "keep me");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,199 @@
+use anyhow::{bail, Result};
+use tokio_rustls::rustls::pki_types::CertificateDer;
+use tokio_rustls::TlsAcceptor;
+
+use crate::config::settings::{AgentSettings, Permission};
+use super::tls;
+
+/// Who may connect to an agent and what they may do, built from `[agent]`
+pub struct Access {
+    tls: Option<TlsAcceptor>,
+    clients: Vec<KnownClient>,
+    allow_anonymous: bool,
+}
+
+struct KnownClient {
+    name: String,
+    token: Option<String>,
+    certificate: Option<CertificateDer<'static>>,
+    permission: Permission,
+}
+
+/// A client that passed authentication
+#[derive(Debug, Clone)]
+pub struct Identity {
+    pub name: String,
+    pub permission: Permission,
+}
+
+impl Access {
+    /// Load certificates and check that every client can actually be recognised
+    pub fn from_settings(settings: &AgentSettings) -> Result<Access> {
+        if settings.clients.is_empty() && !settings.allow_anonymous {
+            bail!(
+                "[agent] has no clients: add [[agent.clients]] entries with a token or certificate, \
+                 or set allow_anonymous = true"
+            );
+        }
+
+        let tls = settings.tls.as_ref().map(tls::server_config).transpose()?;
+        let verifies_clients = settings.tls.as_ref().is_some_and(|tls| tls.client_ca.is_some());
+
+        let mut clients = Vec::new();
+        for client in &settings.clients {
+            let certificate = match &client.certificate {
+                Some(_) if !verifies_clients => bail!(
+                    "[[agent.clients]] {}: certificates need [agent.tls] with client_ca",
+                    client.name
+                ),
+                Some(path) => tls::load_certs(path)?.into_iter().next(),
+                None => None,
+            };
+            match &client.token {
+                Some(token) if token.is_empty() => bail!("[[agent.clients]] {}: token is empty", client.name),
+                None if certificate.is_none() => {
+                    bail!("[[agent.clients]] {}: set a token or a certificate", client.name)
+                }
+                _ => {}
+            }
+            clients.push(KnownClient {
+                name: client.name.clone(),
+                token: client.token.clone(),
+                certificate,
+                permission: client.permission,
+            });
+        }
+
+        Ok(Access {
+            tls: tls.map(TlsAcceptor::from),
+            clients,
+            allow_anonymous: settings.allow_anonymous,
+        })
+    }
+
+    pub fn tls(&self) -> Option<&TlsAcceptor> {
+        self.tls.as_ref()
+    }
+
+    /// Whether tokens travel in the clear
+    pub fn sends_tokens_in_clear(&self) -> bool {
+        self.tls.is_none() && self.clients.iter().any(|client| client.token.is_some())
+    }
+
+    /// Match a client by its verified certificate first, then by token;
+    /// None when it must be turned away
+    pub fn identify(&self, certificate: Option<&CertificateDer>, token: Option<&str>) -> Option<Identity> {
+        let by_certificate = certificate.and_then(|presented| {
+            self.clients
+                .iter()
+                .find(|client| client.certificate.as_ref().is_some_and(|known| known.as_ref() == presented.as_ref()))
+        });
+        let by_token = || {
+            token.and_then(|token| {
+                self.clients
+                    .iter()
+                    .find(|client| client.token.as_deref().is_some_and(|known| tokens_match(known, token)))
+            })
+        };
+
+        match by_certificate.or_else(by_token) {
+            Some(client) => Some(Identity { name: client.name.clone(), permission: client.permission }),
+            None if self.allow_anonymous => Some(Identity {
+                name: "anonymous".to_string(),
+                permission: Permission::ReadOnly,
+            }),
+            None => None,
+        }
+    }
+}
+
+/// Compare without returning early, so timing does not reveal how much of a
+/// guess was right
+fn tokens_match(known: &str, presented: &str) -> bool {
+    let (known, presented) = (known.as_bytes(), presented.as_bytes());
+    if known.len() != presented.len() {
+        return false;
+    }
+    known.iter().zip(presented).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::settings::AgentClientSettings;
+
+    fn access(allow_anonymous: bool) -> Access {
+        Access {
+            tls: None,
+            clients: vec![
+                KnownClient {
+                    name: "laptop".to_string(),
+                    token: None,
+                    certificate: Some(CertificateDer::from(vec![1, 2, 3])),
+                    permission: Permission::Control,
+                },
+                KnownClient {
+                    name: "grafana".to_string(),
+                    token: Some("s3cret".to_string()),
+                    certificate: None,
+                    permission: Permission::ReadOnly,
+                },
+            ],
+            allow_anonymous,
+        }
+    }
+
+    fn who(identity: Option<Identity>) -> Option<(String, Permission)> {
+        identity.map(|identity| (identity.name, identity.permission))
+    }
+
+    #[test]
+    fn certificate_match_wins_over_token() {
+        let presented = CertificateDer::from(vec![1, 2, 3]);
+        assert_eq!(
+            who(access(false).identify(Some(&presented), Some("s3cret"))),
+            Some(("laptop".to_string(), Permission::Control))
+        );
+    }
+
+    #[test]
+    fn token_match_identifies_client() {
+        let unknown = CertificateDer::from(vec![9, 9]);
+        assert_eq!(
+            who(access(false).identify(Some(&unknown), Some("s3cret"))),
+            Some(("grafana".to_string(), Permission::ReadOnly))
+        );
+    }
+
+    #[test]
+    fn wrong_token_is_refused() {
+        assert!(access(false).identify(None, Some("s3cre")).is_none());
+        assert!(access(false).identify(None, Some("s3cres")).is_none());
+        assert!(access(false).identify(None, None).is_none());
+    }
+
+    #[test]
+    fn anonymous_clients_fall_back_to_read_only() {
+        assert_eq!(
+            who(access(true).identify(None, Some("wrong"))),
+            Some(("anonymous".to_string(), Permission::ReadOnly))
+        );
+        assert_eq!(who(access(true).identify(None, None)), Some(("anonymous".to_string(), Permission::ReadOnly)));
+    }
+
+    #[test]
+    fn settings_without_a_way_in_are_rejected() {
+        assert!(Access::from_settings(&AgentSettings::default()).is_err());
+
+        let tokenless = AgentSettings {
+            clients: vec![AgentClientSettings {
+                name: "ops".to_string(),
+                token: None,
+                certificate: None,
+                permission: Permission::Control,
+            }],
+            ..AgentSettings::default()
+        };
+        assert!(Access::from_settings(&tokenless).is_err());
+    }
+}
@@ -0,0 +1,339 @@
+use anyhow::{anyhow, bail, Context, Result};
+use log::debug;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{BufReader, ReadHalf, WriteHalf};
+use tokio::net::TcpStream;
+use tokio::sync::{mpsc, oneshot, watch};
+use tokio::task::JoinHandle;
+use tokio_rustls::rustls::pki_types::ServerName;
+use tokio_rustls::TlsConnector;
+
+use crate::config::settings::RemoteTlsSettings;
+use crate::system::{CollectorCommand, SystemSnapshot};
+use super::protocol::{
+    receive, send, AgentMessage, ClientMessage, Connection, Endpoint, MAX_AGENT_MESSAGE, PROTOCOL_VERSION,
+};
+use super::tls;
+
+/// How long to wait before reconnecting to an agent that went away
+const RECONNECT_DELAY: Duration = Duration::from_secs(2);
+
+/// How long to wait for the agent to answer a terminate request
+const TERMINATE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How the dashboard proves itself to an agent
+#[derive(Clone, Default)]
+pub struct Credentials {
+    token: Option<String>,
+    tls: Option<(TlsConnector, ServerName<'static>)>,
+}
+
+impl Credentials {
+    /// Load the TLS files up front so mistakes show before connecting
+    pub fn new(endpoint: &Endpoint, token: Option<String>, tls: Option<&RemoteTlsSettings>) -> Result<Credentials> {
+        let tls = match tls {
+            Some(settings) => {
+                let name = settings
+                    .server_name
+                    .as_deref()
+                    .or(endpoint.host())
+                    .ok_or_else(|| anyhow!("tls server_name is required for {}", endpoint))?;
+                let server_name = ServerName::try_from(name.to_string())
+                    .with_context(|| format!("Invalid TLS server name {:?}", name))?;
+                Some((TlsConnector::from(tls::client_config(settings)?), server_name))
+            }
+            None => None,
+        };
+        Ok(Credentials { token, tls })
+    }
+}
+
+/// What the dashboard asks of the relay task
+enum Request {
+    Collector(CollectorCommand),
+    Terminate { pid: u32, reply: oneshot::Sender<Result<(), String>> },
+}
+
+type Reader = BufReader<ReadHalf<Box<dyn Connection>>>;
+type Writer = WriteHalf<Box<dyn Connection>>;
+
+/// Owner's side of a connection to an agent. Accepts the same commands as a
+/// local collector: refreshes go to the agent, pausing only stops this
+/// client from publishing, and schedule changes stay with the agent.
+pub struct RemoteHandle {
+    commands: mpsc::UnboundedSender<Request>,
+    connected: Arc<AtomicBool>,
+    task: JoinHandle<()>,
+}
+
+impl RemoteHandle {
+    pub fn send(&self, command: CollectorCommand) {
+        if self.commands.send(Request::Collector(command)).is_err() {
+            debug!("Remote connection has stopped; command dropped");
+        }
+    }
+
+    /// Ask the agent to send SIGTERM to `pid`; it refuses read-only clients
+    /// Ask the agent to send SIGTERM to `pid`. The request goes out right
+    /// away; the returned future only waits for the answer, so it can be
+    /// spawned without holding on to the handle.
+    pub fn terminate(&self, pid: u32) -> impl Future<Output = Result<()>> + Send + 'static {
+        let request = if self.is_connected() {
+            let (reply, answer) = oneshot::channel();
+            self.commands
+                .send(Request::Terminate { pid, reply })
+                .map(|()| answer)
+                .map_err(|_| anyhow!("the remote connection has stopped"))
+        } else {
+            Err(anyhow!("the agent is not connected"))
+        };
+        async move {
+            match tokio::time::timeout(TERMINATE_TIMEOUT, request?).await {
+                Ok(Ok(Ok(()))) => Ok(()),
+                Ok(Ok(Err(message))) => bail!(message),
+                Ok(Err(_)) => bail!("the connection dropped before the agent answered"),
+                Err(_) => bail!("the agent did not answer"),
+            }
+        }
+    }
+
+    pub fn abort(&self) {
+        self.task.abort();
+    }
+
+    /// Whether the agent is reachable right now; receivers are woken when this changes
+    pub fn is_connected(&self) -> bool {
+        self.connected.load(Ordering::Relaxed)
+    }
+}
+
+/// Connect to an agent and wait for its first snapshot, then keep publishing
+/// snapshots in the background, reconnecting whenever the link drops
+pub async fn connect(
+    endpoint: Endpoint,
+    credentials: Credentials,
+) -> Result<(watch::Receiver<Arc<SystemSnapshot>>, RemoteHandle)> {
+    let (mut reader, writer) = open_session(&endpoint, &credentials).await?;
+    let first = loop {
+        match next_message(&mut reader).await? {
+            Some(AgentMessage::Snapshot { snapshot }) => break snapshot,
+            Some(_) => continue,
+            None => bail!("{} closed the connection before sending data", endpoint),
+        }
+    };
+    Ok(spawn_relay(endpoint, credentials, first, Some((reader, writer))))
+}
+
+/// Like `connect`, but returns straight away: an empty snapshot stands in
+/// until the agent is first reached, and failures are retried quietly
+pub fn connect_in_background(
+    endpoint: Endpoint,
+    credentials: Credentials,
+) -> (watch::Receiver<Arc<SystemSnapshot>>, RemoteHandle) {
+    spawn_relay(endpoint, credentials, Arc::new(SystemSnapshot::default()), None)
+}
+
+fn spawn_relay(
+    endpoint: Endpoint,
+    credentials: Credentials,
+    first: Arc<SystemSnapshot>,
+    session: Option<(Reader, Writer)>,
+) -> (watch::Receiver<Arc<SystemSnapshot>>, RemoteHandle) {
+    let (sender, receiver) = watch::channel(first);
+    let (command_sender, mut commands) = mpsc::unbounded_channel();
+    let connected = Arc::new(AtomicBool::new(session.is_some()));
+    let link = connected.clone();
+
+    let task = tokio::spawn(async move {
+        let mut session = session;
+        let mut paused = false;
+        loop {
+            let (reader, mut writer) = match session.take() {
+                Some(session) => session,
+                None => match open_session(&endpoint, &credentials).await {
+                    Ok(session) => session,
+                    Err(err) => {
+                        debug!("Connecting to {} failed: {:#}", endpoint, err);
+                        tokio::time::sleep(RECONNECT_DELAY).await;
+                        if sender.is_closed() {
+                            return;
+                        }
+                        continue;
+                    }
+                },
+            };
+
+            // The TUI owns the terminal, so link changes are drawn from
+            // `is_online` rather than logged above debug
+            set_connected(&link, &sender, true);
+            match run_session(reader, &mut writer, &sender, &mut commands, &mut paused).await {
+                Ok(true) => return,
+                Ok(false) => debug!("Agent {} closed the connection", endpoint),
+                Err(err) => debug!("Lost connection to {}: {:#}", endpoint, err),
+            }
+            // Keep showing the last snapshot until the agent is back
+            set_connected(&link, &sender, false);
+            tokio::time::sleep(RECONNECT_DELAY).await;
+            if sender.is_closed() {
+                return;
+            }
+        }
+    });
+
+    (receiver, RemoteHandle { commands: command_sender, connected, task })
+}
+
+/// Record a link change and wake receivers so it gets drawn
+fn set_connected(link: &AtomicBool, sender: &watch::Sender<Arc<SystemSnapshot>>, connected: bool) {
+    link.store(connected, Ordering::Relaxed);
+    sender.send_modify(|_| {});
+}
+
+/// Connect and complete the TLS and hello/welcome handshakes
+async fn open_session(endpoint: &Endpoint, credentials: &Credentials) -> Result<(Reader, Writer)> {
+    let stream: Box<dyn Connection> = match endpoint {
+        Endpoint::Tcp(address) => Box::new(
+            TcpStream::connect(address)
+                .await
+                .with_context(|| format!("Failed to connect to {}", address))?,
+        ),
+        #[cfg(unix)]
+        Endpoint::Unix(path) => Box::new(
+            tokio::net::UnixStream::connect(path)
+                .await
+                .with_context(|| format!("Failed to connect to {}", path.display()))?,
+        ),
+        #[cfg(not(unix))]
+        Endpoint::Unix(_) => bail!("Unix sockets are not supported on this platform"),
+    };
+    let stream: Box<dyn Connection> = match &credentials.tls {
+        Some((connector, server_name)) => Box::new(
+            connector
+                .connect(server_name.clone(), stream)
+                .await
+                .with_context(|| format!("TLS handshake with {} failed", endpoint))?,
+        ),
+        None => stream,
+    };
+
+    let (reader, mut writer) = tokio::io::split(stream);
+    let mut reader = BufReader::new(reader);
+    let hello = ClientMessage::Hello { version: PROTOCOL_VERSION, token: credentials.token.clone() };
+    send(&mut writer, &hello).await?;
+
+    let mut line = String::new();
+    match receive(&mut reader, &mut line, MAX_AGENT_MESSAGE).await? {
+        Some(AgentMessage::Welcome { version, host_name, permission }) if version == PROTOCOL_VERSION => {
+            debug!(
+                "Connected to {} ({}, {})",
+                endpoint,
+                host_name.as_deref().unwrap_or("unknown host"),
+                permission.name()
+            );
+            Ok((reader, writer))
+        }
+        Some(AgentMessage::Welcome { version, .. }) => bail!(
+            "{} speaks protocol version {}; this dashboard speaks version {}",
+            endpoint, version, PROTOCOL_VERSION
+        ),
+        Some(AgentMessage::Error { message }) => bail!("{} refused the connection: {}", endpoint, message),
+        Some(AgentMessage::Snapshot { .. } | AgentMessage::Terminated { .. }) => {
+            bail!("{} sent data before the handshake", endpoint)
+        }
+        None => bail!("{} closed the connection during the handshake", endpoint),
+    }
+}
+
+/// The next snapshot or reply from the agent; None once it closes the connection
+async fn next_message(reader: &mut Reader) -> Result<Option<AgentMessage>> {
+    let mut line = String::new();
+    loop {
+        match receive(reader, &mut line, MAX_AGENT_MESSAGE).await? {
+            Some(AgentMessage::Error { message }) => bail!("agent error: {}", message),
+            Some(AgentMessage::Welcome { .. }) => debug!("Ignoring repeated welcome"),
+            message => return Ok(message),
+        }
+    }
+}
+
+/// Relay snapshots and commands until the link drops. Returns Ok(true) when
+/// the dashboard has gone away and there is nothing left to do.
+async fn run_session(
+    mut reader: Reader,
+    writer: &mut Writer,
+    sender: &watch::Sender<Arc<SystemSnapshot>>,
+    commands: &mut mpsc::UnboundedReceiver<Request>,
+    paused: &mut bool,
+) -> Result<bool> {
+    // Reading a line is not cancel-safe, so it gets its own task and
+    // hands complete messages over a channel
+    let (message_sender, mut incoming) = mpsc::channel(1);
+    let reader_task = tokio::spawn(async move {
+        loop {
+            let next = next_message(&mut reader).await;
+            let done = !matches!(next, Ok(Some(_)));
+            if message_sender.send(next).await.is_err() || done {
+                return;
+            }
+        }
+    });
+    let _abort_reader = AbortOnDrop(reader_task);
+
+    // Publish one snapshot after a refresh requested while paused
+    let mut step = false;
+    // Terminate requests awaiting an answer; the agent replies in order.
+    // Dropping them when the link goes tells the waiting dashboard.
+    let mut pending: VecDeque<(u32, oneshot::Sender<Result<(), String>>)> = VecDeque::new();
+
+    loop {
+        tokio::select! {
+            next = incoming.recv() => match next {
+                Some(Ok(Some(AgentMessage::Snapshot { snapshot }))) => {
+                    if !*paused || step {
+                        step = false;
+                        if sender.send(snapshot).is_err() {
+                            return Ok(true);
+                        }
+                    }
+                }
+                Some(Ok(Some(AgentMessage::Terminated { pid, error }))) => match pending.pop_front() {
+                    Some((requested, reply)) if requested == pid => {
+                        let _ = reply.send(error.map_or(Ok(()), Err));
+                    }
+                    _ => debug!("Unexpected terminate answer for PID {}", pid),
+                },
+                Some(Ok(Some(_))) => {}
+                Some(Ok(None)) | None => return Ok(false),
+                Some(Err(err)) => return Err(err),
+            },
+            request = commands.recv() => match request {
+                Some(Request::Collector(CollectorCommand::RefreshNow)) => {
+                    step = *paused;
+                    send(writer, &ClientMessage::Refresh).await?;
+                }
+                Some(Request::Collector(CollectorCommand::Pause)) => *paused = true,
+                Some(Request::Collector(CollectorCommand::Resume)) => *paused = false,
+                Some(Request::Collector(CollectorCommand::SetSchedule(_) | CollectorCommand::SetHistory(_))) => {
+                    debug!("Collector intervals and history are set on the agent")
+                }
+                Some(Request::Terminate { pid, reply }) => {
+                    send(writer, &ClientMessage::Terminate { pid }).await?;
+                    pending.push_back((pid, reply));
+                }
+                None => return Ok(true),
+            },
+        }
+    }
+}
+
+struct AbortOnDrop(JoinHandle<()>);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
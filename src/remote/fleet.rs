@@ -0,0 +1,98 @@
+use anyhow::Result;
+use futures::future::BoxFuture;
+use std::sync::Arc;
+use tokio::sync::watch;
+
+use crate::system::{terminate_process, CollectorCommand, CollectorHandle, SystemSnapshot};
+use super::client::RemoteHandle;
+use super::protocol::Endpoint;
+
+/// Where a host's snapshots come from
+pub enum Source {
+    Local(CollectorHandle),
+    Remote(RemoteHandle),
+}
+
+impl Source {
+    pub fn send(&self, command: CollectorCommand) {
+        match self {
+            Source::Local(collector) => collector.send(command),
+            Source::Remote(remote) => remote.send(command),
+        }
+    }
+
+    pub fn abort(&self) {
+        match self {
+            Source::Local(collector) => collector.abort(),
+            Source::Remote(remote) => remote.abort(),
+        }
+    }
+
+    /// Send SIGTERM to `pid` on this source's host; agents only allow it
+    /// for clients with `control` permission. The future does not borrow
+    /// the source, so the caller can spawn it and carry on.
+    pub fn terminate(&self, pid: u32) -> BoxFuture<'static, Result<()>> {
+        match self {
+            Source::Local(_) => Box::pin(async move { terminate_process(pid) }),
+            Source::Remote(remote) => Box::pin(remote.terminate(pid)),
+        }
+    }
+
+    pub fn is_remote(&self) -> bool {
+        matches!(self, Source::Remote(_))
+    }
+
+    /// The local collector is always online; agents while their link is up
+    pub fn is_online(&self) -> bool {
+        match self {
+            Source::Local(_) => true,
+            Source::Remote(remote) => remote.is_connected(),
+        }
+    }
+}
+
+/// One machine the dashboard can show: this one or a remote agent
+pub struct FleetHost {
+    pub name: String,
+    /// None for this machine
+    pub address: Option<Endpoint>,
+    pub source: Source,
+    pub snapshots: watch::Receiver<Arc<SystemSnapshot>>,
+}
+
+impl FleetHost {
+    pub fn latest(&self) -> Arc<SystemSnapshot> {
+        self.snapshots.borrow().clone()
+    }
+
+    pub fn status(&self) -> HostStatus {
+        HostStatus {
+            name: self.name.clone(),
+            address: self
+                .address
+                .as_ref()
+                .map_or_else(|| "local".to_string(), |address| address.to_string()),
+            remote: self.source.is_remote(),
+            online: self.source.is_online(),
+            snapshot: self.latest(),
+        }
+    }
+}
+
+/// What the Fleet tab shows for one host
+#[derive(Debug, Clone)]
+pub struct HostStatus {
+    pub name: String,
+    pub address: String,
+    pub remote: bool,
+    pub online: bool,
+    /// The last snapshot received; empty until a remote host is first reached
+    pub snapshot: Arc<SystemSnapshot>,
+}
+
+impl HostStatus {
+    /// A remote host that has not sent anything yet
+    pub fn is_connecting(&self) -> bool {
+        !self.online && self.snapshot.cpu_history.is_empty()
+    }
+}
@@ -0,0 +1,14 @@
+// Serving snapshots to, and reading them from, other machines
+
+pub mod agent;
+pub mod auth;
+pub mod client;
+pub mod fleet;
+pub mod protocol;
+pub mod tls;
+
+pub use agent::run_agent;
+pub use auth::{Access, Identity};
+pub use client::{connect, connect_in_background, Credentials, RemoteHandle};
+pub use fleet::{FleetHost, HostStatus, Source};
+pub use protocol::{AgentMessage, ClientMessage, Endpoint, PROTOCOL_VERSION};
@@ -0,0 +1,221 @@
+use anyhow::{bail, Context, Result};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::config::settings::Permission;
+use crate::system::SystemSnapshot;
+
+/// Bumped whenever a message changes incompatibly; both ends must agree
+pub const PROTOCOL_VERSION: u32 = 2;
+
+/// Longest line an agent accepts from a client
+pub const MAX_CLIENT_MESSAGE: u64 = 64 * 1024;
+
+/// Longest line a client accepts from an agent; snapshots carry the whole
+/// process table and chart history
+pub const MAX_AGENT_MESSAGE: u64 = 64 * 1024 * 1024;
+
+/// Sent by the dashboard. The first message on a connection must be `Hello`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    Hello {
+        version: u32,
+        /// Pre-shared token, for agents that do not identify clients by certificate
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        token: Option<String>,
+    },
+    /// Sample every collector now instead of waiting for the schedule
+    Refresh,
+    /// Send SIGTERM to a process on the agent's host; needs `control`
+    Terminate { pid: u32 },
+}
+
+/// Sent by the agent: `Welcome` (or `Error`) answers `Hello`, then a
+/// `Snapshot` follows every collector pass, and `Terminated` answers each
+/// `Terminate` in order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AgentMessage {
+    Welcome { version: u32, host_name: Option<String>, permission: Permission },
+    Snapshot { snapshot: Arc<SystemSnapshot> },
+    /// Outcome of a `Terminate`; `error` is None when the signal was sent
+    Terminated { pid: u32, error: Option<String> },
+    /// The agent is closing the connection, and why
+    Error { message: String },
+}
+
+/// Where an agent listens: `host:port`, or `unix:/path/to/socket`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Endpoint {
+    Tcp(String),
+    Unix(PathBuf),
+}
+
+impl Endpoint {
+    pub fn parse(address: &str) -> Endpoint {
+        match address.strip_prefix("unix:") {
+            Some(path) => Endpoint::Unix(PathBuf::from(path)),
+            None => Endpoint::Tcp(address.to_string()),
+        }
+    }
+
+    /// The host part of a TCP address, without port or IPv6 brackets
+    pub fn host(&self) -> Option<&str> {
+        match self {
+            Endpoint::Tcp(address) => {
+                let host = address.rsplit_once(':').map_or(address.as_str(), |(host, _)| host);
+                Some(host.trim_start_matches('[').trim_end_matches(']'))
+            }
+            Endpoint::Unix(_) => None,
+        }
+    }
+
+    /// Whether only this machine can reach the endpoint
+    pub fn is_local(&self) -> bool {
+        match self {
+            Endpoint::Tcp(_) => self
+                .host()
+                .is_some_and(|host| host == "localhost" || host.parse::<std::net::IpAddr>().is_ok_and(|ip| ip.is_loopback())),
+            Endpoint::Unix(_) => true,
+        }
+    }
+}
+
+impl fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Endpoint::Tcp(address) => write!(f, "{}", address),
+            Endpoint::Unix(path) => write!(f, "unix:{}", path.display()),
+        }
+    }
+}
+
+/// A byte stream either end can talk over, whatever the transport
+pub trait Connection: AsyncRead + AsyncWrite + Unpin + Send {}
+
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> Connection for T {}
+
+/// Write one message as a line of JSON
+pub async fn send<W, M>(writer: &mut W, message: &M) -> Result<()>
+where
+    W: AsyncWrite + Unpin,
+    M: Serialize,
+{
+    let mut line = serde_json::to_vec(message).context("Failed to encode message")?;
+    line.push(b'\n');
+    writer.write_all(&line).await?;
+    writer.flush().await?;
+    Ok(())
+}
+
+/// Read the next message; None once the peer has closed the connection.
+/// `line` is a reusable buffer.
+pub async fn receive<R, M>(reader: &mut R, line: &mut String, limit: u64) -> Result<Option<M>>
+where
+    R: AsyncBufRead + Unpin,
+    M: DeserializeOwned,
+{
+    line.clear();
+    let read = match (&mut *reader).take(limit).read_line(line).await {
+        Ok(read) => read,
+        // TLS peers that hang up without close_notify; only a cut-off message is an error
+        Err(err) if err.kind() == io::ErrorKind::UnexpectedEof && line.is_empty() => 0,
+        Err(err) => return Err(err.into()),
+    };
+    if read == 0 {
+        return Ok(None);
+    }
+    if !line.ends_with('\n') {
+        if read as u64 >= limit {
+            bail!("message longer than {} bytes", limit);
+        }
+        bail!("connection closed mid-message");
+    }
+    let message = serde_json::from_str(line).context("Malformed message")?;
+    Ok(Some(message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::BufReader;
+
+    #[tokio::test]
+    async fn messages_round_trip() {
+        let (client, agent) = tokio::io::duplex(1024);
+        let (_, mut writer) = tokio::io::split(client);
+        let (reader, _) = tokio::io::split(agent);
+        let mut reader = BufReader::new(reader);
+
+        let sent = [
+            ClientMessage::Hello { version: PROTOCOL_VERSION, token: Some("s3cret".to_string()) },
+            ClientMessage::Hello { version: PROTOCOL_VERSION, token: None },
+            ClientMessage::Refresh,
+            ClientMessage::Terminate { pid: 4242 },
+        ];
+        for message in &sent {
+            send(&mut writer, message).await.unwrap();
+        }
+        drop(writer);
+
+        let mut line = String::new();
+        for message in &sent {
+            let received: Option<ClientMessage> = receive(&mut reader, &mut line, MAX_CLIENT_MESSAGE).await.unwrap();
+            assert_eq!(received.as_ref(), Some(message));
+        }
+        let end: Option<ClientMessage> = receive(&mut reader, &mut line, MAX_CLIENT_MESSAGE).await.unwrap();
+        assert_eq!(end, None);
+    }
+
+    #[tokio::test]
+    async fn snapshot_round_trips() {
+        let mut snapshot = SystemSnapshot::default();
+        snapshot.host.host_name = Some("db1".to_string());
+        let mut encoded = Vec::new();
+        send(&mut encoded, &AgentMessage::Snapshot { snapshot: Arc::new(snapshot) }).await.unwrap();
+
+        let mut line = String::new();
+        match receive(&mut encoded.as_slice(), &mut line, MAX_AGENT_MESSAGE).await.unwrap() {
+            Some(AgentMessage::Snapshot { snapshot }) => assert_eq!(snapshot.host.host_name.as_deref(), Some("db1")),
+            other => panic!("unexpected message {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn oversized_client_message_is_rejected() {
+        let token = "x".repeat(MAX_CLIENT_MESSAGE as usize);
+        let mut encoded = Vec::new();
+        send(&mut encoded, &ClientMessage::Hello { version: PROTOCOL_VERSION, token: Some(token) }).await.unwrap();
+
+        let mut line = String::new();
+        let error = receive::<_, ClientMessage>(&mut encoded.as_slice(), &mut line, MAX_CLIENT_MESSAGE)
+            .await
+            .unwrap_err();
+        assert_eq!(error.to_string(), format!("message longer than {} bytes", MAX_CLIENT_MESSAGE));
+    }
+
+    #[tokio::test]
+    async fn truncated_and_malformed_messages_are_errors() {
+        let mut line = String::new();
+        let cut_off = receive::<_, ClientMessage>(&mut &b"{\"type\":\"refresh\""[..], &mut line, MAX_CLIENT_MESSAGE).await;
+        assert_eq!(cut_off.unwrap_err().to_string(), "connection closed mid-message");
+
+        let malformed = receive::<_, ClientMessage>(&mut &b"{\"type\":\"reboot\"}\n"[..], &mut line, MAX_CLIENT_MESSAGE).await;
+        assert!(malformed.is_err());
+    }
+
+    #[test]
+    fn endpoints_parse_and_display() {
+        assert_eq!(Endpoint::parse("unix:/run/agent.sock"), Endpoint::Unix(PathBuf::from("/run/agent.sock")));
+        assert_eq!(Endpoint::parse("[::1]:7878").host(), Some("::1"));
+        assert!(Endpoint::parse("localhost:7878").is_local());
+        assert!(!Endpoint::parse("10.0.0.5:7878").is_local());
+        assert_eq!(Endpoint::parse("unix:/run/agent.sock").to_string(), "unix:/run/agent.sock");
+    }
+}
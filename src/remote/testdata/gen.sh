@@ -0,0 +1,27 @@
+#!/bin/sh
+# Regenerates the TLS fixtures used by the agent tests: a CA, a certificate
+# for the agent (localhost and 127.0.0.1) and two client certificates, of
+# which only `client` is listed in the tests' [[agent.clients]].
+set -e
+cd "$(dirname "$0")"
+days=36500
+
+openssl req -x509 -new -newkey ec -pkeyopt ec_paramgen_curve:P-256 -nodes \
+    -keyout ca.key -out ca.pem -days $days -subj "/CN=system-monitor test CA" \
+    -addext "basicConstraints=critical,CA:TRUE" -addext "keyUsage=critical,keyCertSign,cRLSign"
+
+issue() {
+    openssl req -new -newkey ec -pkeyopt ec_paramgen_curve:P-256 -nodes \
+        -keyout "$1.key" -out "$1.csr" -subj "/CN=$2"
+    openssl x509 -req -in "$1.csr" -CA ca.pem -CAkey ca.key -CAcreateserial \
+        -out "$1.pem" -days $days -extfile "$1.ext"
+    rm "$1.csr" "$1.ext"
+}
+
+printf 'basicConstraints=CA:FALSE\nkeyUsage=digitalSignature\nextendedKeyUsage=serverAuth\nsubjectAltName=DNS:localhost,IP:127.0.0.1\n' > agent.ext
+issue agent localhost
+for name in client stranger; do
+    printf 'basicConstraints=CA:FALSE\nkeyUsage=digitalSignature\nextendedKeyUsage=clientAuth\n' > $name.ext
+    issue $name $name
+done
+rm -f ca.key ca.srl
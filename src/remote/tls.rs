@@ -0,0 +1,79 @@
+use anyhow::{anyhow, bail, Context, Result};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use std::sync::Arc;
+use tokio_rustls::rustls::crypto::{ring, CryptoProvider};
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use tokio_rustls::rustls::server::WebPkiClientVerifier;
+use tokio_rustls::rustls::{ClientConfig, RootCertStore, ServerConfig};
+
+use crate::config::settings::{AgentTlsSettings, RemoteTlsSettings};
+
+fn provider() -> Arc<CryptoProvider> {
+    Arc::new(ring::default_provider())
+}
+
+/// Every certificate in a PEM file, leaf first
+pub fn load_certs(path: &Path) -> Result<Vec<CertificateDer<'static>>> {
+    let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let certs = rustls_pemfile::certs(&mut BufReader::new(file))
+        .collect::<Result<Vec<_>, _>>()
+        .with_context(|| format!("Failed to read certificates from {}", path.display()))?;
+    if certs.is_empty() {
+        bail!("{} contains no PEM certificates", path.display());
+    }
+    Ok(certs)
+}
+
+fn load_key(path: &Path) -> Result<PrivateKeyDer<'static>> {
+    let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    rustls_pemfile::private_key(&mut BufReader::new(file))
+        .with_context(|| format!("Failed to read private key from {}", path.display()))?
+        .ok_or_else(|| anyhow!("{} contains no PEM private key", path.display()))
+}
+
+fn load_roots(path: &Path) -> Result<RootCertStore> {
+    let mut roots = RootCertStore::empty();
+    for cert in load_certs(path)? {
+        roots
+            .add(cert)
+            .with_context(|| format!("Invalid CA certificate in {}", path.display()))?;
+    }
+    Ok(roots)
+}
+
+/// The agent's side: present `cert`, and with `client_ca` refuse clients
+/// without a certificate signed by it
+pub fn server_config(settings: &AgentTlsSettings) -> Result<Arc<ServerConfig>> {
+    let builder = ServerConfig::builder_with_provider(provider()).with_safe_default_protocol_versions()?;
+    let builder = match &settings.client_ca {
+        Some(ca) => {
+            let verifier = WebPkiClientVerifier::builder_with_provider(Arc::new(load_roots(ca)?), provider())
+                .build()
+                .context("Failed to set up client certificate checks")?;
+            builder.with_client_cert_verifier(verifier)
+        }
+        None => builder.with_no_client_auth(),
+    };
+    let config = builder
+        .with_single_cert(load_certs(&settings.cert)?, load_key(&settings.key)?)
+        .with_context(|| format!("{} does not match {}", settings.key.display(), settings.cert.display()))?;
+    Ok(Arc::new(config))
+}
+
+/// The dashboard's side: trust agents signed by `ca`, and present a client
+/// certificate when one is configured
+pub fn client_config(settings: &RemoteTlsSettings) -> Result<Arc<ClientConfig>> {
+    let builder = ClientConfig::builder_with_provider(provider())
+        .with_safe_default_protocol_versions()?
+        .with_root_certificates(load_roots(&settings.ca)?);
+    let config = match (&settings.cert, &settings.key) {
+        (Some(cert), Some(key)) => builder
+            .with_client_auth_cert(load_certs(cert)?, load_key(key)?)
+            .with_context(|| format!("{} does not match {}", key.display(), cert.display()))?,
+        (None, None) => builder.with_no_client_auth(),
+        _ => bail!("tls `cert` and `key` must be set together"),
+    };
+    Ok(Arc::new(config))
+}
@@ -0,0 +1,75 @@
+use std::cmp::Reverse;
+
+use crate::config::settings::AlertSettings;
+
+use super::monitor::DiskInfo;
+use super::pressure::PressureData;
+use super::snapshot::SystemSnapshot;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AlertLevel {
+    Warning,
+    Critical,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alert {
+    pub level: AlertLevel,
+    pub message: String,
+}
+
+/// Every threshold `snapshot` currently exceeds, most severe first
+pub fn evaluate_alerts(snapshot: &SystemSnapshot, thresholds: &AlertSettings) -> Vec<Alert> {
+    let mut alerts = Vec::new();
+    if let Some(pressure) = snapshot.pressure() {
+        alerts.extend(pressure_alerts(pressure, thresholds));
+    }
+    alerts.extend(disk_alerts(&snapshot.disks, thresholds));
+    alerts.sort_by_key(|alert| Reverse(alert.level));
+    alerts
+}
+
+/// PSI rules: `full` avg10 is critical, `some` avg10 is a warning; a resource
+/// only raises its most severe alert
+pub fn pressure_alerts(pressure: &PressureData, thresholds: &AlertSettings) -> Vec<Alert> {
+    pressure
+        .resources()
+        .into_iter()
+        .filter_map(|(name, resource)| {
+            let resource = resource?;
+            match resource.full {
+                Some(full) if full.avg10 >= thresholds.psi_full_avg10 => Some(Alert {
+                    level: AlertLevel::Critical,
+                    message: format!("{} pressure: all tasks stalled {:.1}% of the last 10s", name, full.avg10),
+                }),
+                _ if resource.some.avg10 >= thresholds.psi_some_avg10 => Some(Alert {
+                    level: AlertLevel::Warning,
+                    message: format!("{} pressure: tasks stalled {:.1}% of the last 10s", name, resource.some.avg10),
+                }),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Filesystems filling up: critical from `disk_critical_percent`, a warning
+/// from `disk_warning_percent`
+pub fn disk_alerts(disks: &[DiskInfo], thresholds: &AlertSettings) -> Vec<Alert> {
+    disks
+        .iter()
+        .filter(|disk| !disk.is_image())
+        .filter_map(|disk| {
+            let level = if disk.usage_percent >= thresholds.disk_critical_percent {
+                AlertLevel::Critical
+            } else if disk.usage_percent >= thresholds.disk_warning_percent {
+                AlertLevel::Warning
+            } else {
+                return None;
+            };
+            Some(Alert {
+                level,
+                message: format!("{} is {:.0}% full", disk.mount_point, disk.usage_percent),
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,469 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use serde::{Deserialize, Serialize};
+
+/// `cpu.max`: at most `quota_usec` of CPU time per `period_usec`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CpuMax {
+    /// None when the quota is `max` (unlimited)
+    pub quota_usec: Option<u64>,
+    pub period_usec: u64,
+}
+
+impl CpuMax {
+    /// The limit expressed as a number of CPU cores, e.g. 1.5
+    pub fn cores(&self) -> Option<f64> {
+        match self.quota_usec {
+            Some(quota) if self.period_usec > 0 => Some(quota as f64 / self.period_usec as f64),
+            _ => None,
+        }
+    }
+}
+
+/// The counters of `cpu.stat` this dashboard uses, in microseconds
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct CgroupCpuStat {
+    pub usage_usec: u64,
+    pub user_usec: u64,
+    pub system_usec: u64,
+    pub nr_periods: u64,
+    pub nr_throttled: u64,
+    pub throttled_usec: u64,
+}
+
+/// Usage of the cgroup this process runs in, relative to its own limits
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ContainerUsage {
+    /// Path inside the cgroup hierarchy, e.g. `/system.slice/docker-1234.scope`
+    pub cgroup_path: String,
+    /// CPU limit in cores from `cpu.max`; None when unlimited
+    pub cpu_limit: Option<f64>,
+    /// Percent of the CPU limit (of all host cores when unlimited) used since
+    /// the previous sample
+    pub cpu_usage: f32,
+    pub cpu_stat: CgroupCpuStat,
+    pub memory_current: u64,
+    /// `memory.max`; None when unlimited
+    pub memory_max: Option<u64>,
+}
+
+impl ContainerUsage {
+    /// Whether a CPU or memory limit is set, i.e. host totals would mislead
+    pub fn has_limits(&self) -> bool {
+        self.cpu_limit.is_some() || self.memory_max.is_some()
+    }
+
+    /// The memory the cgroup can actually use: its limit, capped at host RAM
+    pub fn memory_limit(&self, host_total: u64) -> u64 {
+        self.memory_max.map_or(host_total, |max| max.min(host_total))
+    }
+
+    pub fn memory_usage_percent(&self, host_total: u64) -> f32 {
+        let limit = self.memory_limit(host_total);
+        if limit > 0 {
+            (self.memory_current as f32 / limit as f32) * 100.0
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Parse `cpu.max`, e.g. `150000 100000` or `max 100000`
+pub fn parse_cpu_max(text: &str) -> Option<CpuMax> {
+    let mut fields = text.split_whitespace();
+    let quota = fields.next()?;
+    let period_usec = fields.next().map_or(Some(100_000), |period| period.parse().ok())?;
+    let quota_usec = match quota {
+        "max" => None,
+        quota => Some(quota.parse().ok()?),
+    };
+    Some(CpuMax { quota_usec, period_usec })
+}
+
+/// Parse a byte limit such as `memory.max`; `max` (unlimited) gives None
+pub fn parse_limit(text: &str) -> Option<u64> {
+    text.trim().parse().ok()
+}
+
+/// Parse `cpu.stat`'s `key value` lines; missing keys stay zero
+pub fn parse_cpu_stat(text: &str) -> CgroupCpuStat {
+    let mut stat = CgroupCpuStat::default();
+    for line in text.lines() {
+        let mut fields = line.split_whitespace();
+        let (Some(key), Some(Ok(value))) = (fields.next(), fields.next().map(str::parse::<u64>)) else {
+            continue;
+        };
+        match key {
+            "usage_usec" => stat.usage_usec = value,
+            "user_usec" => stat.user_usec = value,
+            "system_usec" => stat.system_usec = value,
+            "nr_periods" => stat.nr_periods = value,
+            "nr_throttled" => stat.nr_throttled = value,
+            "throttled_usec" => stat.throttled_usec = value,
+            _ => {}
+        }
+    }
+    stat
+}
+
+/// The cgroup v2 path from `/proc/<pid>/cgroup`, i.e. the `0::/path` line
+pub fn parse_proc_cgroup(text: &str) -> Option<String> {
+    text.lines()
+        .find_map(|line| line.strip_prefix("0::"))
+        .map(|path| path.trim().to_string())
+}
+
+/// Where the cgroup2 filesystem is mounted, from `/proc/self/mountinfo`.
+///
+/// Usually `/sys/fs/cgroup`, or `/sys/fs/cgroup/unified` on hybrid hosts.
+pub fn find_cgroup2_mount(mountinfo: &str) -> Option<PathBuf> {
+    mountinfo.lines().find_map(|line| {
+        // Optional fields end at " - ", followed by the filesystem type
+        let (mount_fields, fs_fields) = line.split_once(" - ")?;
+        if fs_fields.split_whitespace().next()? != "cgroup2" {
+            return None;
+        }
+        mount_fields.split_whitespace().nth(4).map(PathBuf::from)
+    })
+}
+
+/// The cgroup2 mount point on this host, if cgroup v2 is in use
+pub fn cgroup2_root() -> Option<PathBuf> {
+    let mountinfo = fs::read_to_string("/proc/self/mountinfo").ok()?;
+    find_cgroup2_mount(&mountinfo)
+}
+
+fn read_file(dir: &Path, name: &str) -> Option<String> {
+    fs::read_to_string(dir.join(name)).ok()
+}
+
+/// Samples the cgroup this process belongs to
+#[derive(Debug)]
+pub struct CgroupReader {
+    dir: PathBuf,
+    usage: ContainerUsage,
+    last_cpu: Option<(u64, Instant)>,
+}
+
+impl CgroupReader {
+    /// Find our own cgroup under cgroup v2; None on cgroup v1-only hosts and
+    /// other platforms
+    pub fn detect() -> Option<Self> {
+        let root = cgroup2_root()?;
+        let path = parse_proc_cgroup(&fs::read_to_string("/proc/self/cgroup").ok()?)?;
+        let dir = root.join(path.trim_start_matches('/'));
+        if !dir.is_dir() {
+            return None;
+        }
+
+        let mut reader = Self {
+            dir,
+            usage: ContainerUsage {
+                cgroup_path: path,
+                ..ContainerUsage::default()
+            },
+            last_cpu: None,
+        };
+        reader.refresh_memory();
+        Some(reader)
+    }
+
+    pub fn usage(&self) -> &ContainerUsage {
+        &self.usage
+    }
+
+    /// Re-read `cpu.max` and `cpu.stat`; usage is relative to the limit, or to
+    /// `host_cores` when there is none
+    pub fn refresh_cpu(&mut self, host_cores: usize) {
+        self.usage.cpu_limit = read_file(&self.dir, "cpu.max")
+            .and_then(|text| parse_cpu_max(&text))
+            .and_then(|max| max.cores());
+        let Some(stat) = read_file(&self.dir, "cpu.stat").map(|text| parse_cpu_stat(&text)) else {
+            return;
+        };
+
+        let now = Instant::now();
+        if let Some((last_usage, last_time)) = self.last_cpu {
+            let elapsed_usec = now.duration_since(last_time).as_micros() as f64;
+            let cores = self.usage.cpu_limit.unwrap_or(host_cores.max(1) as f64);
+            if elapsed_usec > 0.0 && cores > 0.0 {
+                let used_usec = stat.usage_usec.saturating_sub(last_usage) as f64;
+                self.usage.cpu_usage = (used_usec / (elapsed_usec * cores) * 100.0) as f32;
+            }
+        }
+        self.last_cpu = Some((stat.usage_usec, now));
+        self.usage.cpu_stat = stat;
+    }
+
+    /// Re-read `memory.current` and `memory.max`
+    pub fn refresh_memory(&mut self) {
+        self.usage.memory_max = read_file(&self.dir, "memory.max").and_then(|text| parse_limit(&text));
+        if let Some(current) = read_file(&self.dir, "memory.current").and_then(|text| parse_limit(&text)) {
+            self.usage.memory_current = current;
+        }
+    }
+}
+
+/// Parse `io.stat` into total (read, written) bytes across all devices
+pub fn parse_io_stat(text: &str) -> (u64, u64) {
+    let mut read = 0;
+    let mut written = 0;
+    for field in text.split_whitespace() {
+        if let Some(bytes) = field.strip_prefix("rbytes=") {
+            read += bytes.parse::<u64>().unwrap_or(0);
+        } else if let Some(bytes) = field.strip_prefix("wbytes=") {
+            written += bytes.parse::<u64>().unwrap_or(0);
+        }
+    }
+    (read, written)
+}
+
+/// Resource usage of one group in the cgroup v2 hierarchy
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CgroupStats {
+    /// Path inside the hierarchy, `/` for the root
+    pub path: String,
+    /// CPU time used since the previous scan, in percent of one core
+    pub cpu_usage: f32,
+    /// Absent where the memory controller is not enabled for the group
+    pub memory_current: Option<u64>,
+    pub memory_max: Option<u64>,
+    /// Bytes per second since the previous scan
+    pub io_read_rate: f64,
+    pub io_write_rate: f64,
+    pub pids_current: Option<u64>,
+}
+
+impl CgroupStats {
+    /// Last path component, e.g. `ssh.service`
+    pub fn name(&self) -> &str {
+        match self.path.rsplit('/').next() {
+            Some(name) if !name.is_empty() => name,
+            _ => "/",
+        }
+    }
+
+    /// Whether `cgroup_path` (a process's group) is this group or below it
+    pub fn contains(&self, cgroup_path: &str) -> bool {
+        if self.path == "/" {
+            return true;
+        }
+        cgroup_path
+            .strip_prefix(self.path.as_str())
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+    }
+}
+
+/// Columns the cgroup list can be ordered by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CgroupSort {
+    Name,
+    Cpu,
+    Memory,
+    Io,
+    Pids,
+}
+
+impl CgroupSort {
+    pub fn name(&self) -> &'static str {
+        match self {
+            CgroupSort::Name => "Name",
+            CgroupSort::Cpu => "CPU",
+            CgroupSort::Memory => "Memory",
+            CgroupSort::Io => "IO",
+            CgroupSort::Pids => "Pids",
+        }
+    }
+
+    /// Whether a fresh sort on this column starts with the largest values first
+    pub fn descending_by_default(&self) -> bool {
+        !matches!(self, CgroupSort::Name)
+    }
+}
+
+pub fn sort_cgroups(groups: &mut [&CgroupStats], sort: CgroupSort, descending: bool) {
+    groups.sort_by(|a, b| {
+        let ordering = match sort {
+            CgroupSort::Name => a.path.cmp(&b.path),
+            CgroupSort::Cpu => a.cpu_usage.partial_cmp(&b.cpu_usage).unwrap_or(std::cmp::Ordering::Equal),
+            CgroupSort::Memory => a.memory_current.cmp(&b.memory_current),
+            CgroupSort::Io => (a.io_read_rate + a.io_write_rate)
+                .partial_cmp(&(b.io_read_rate + b.io_write_rate))
+                .unwrap_or(std::cmp::Ordering::Equal),
+            CgroupSort::Pids => a.pids_current.cmp(&b.pids_current),
+        };
+        if descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    });
+}
+
+// Cumulative counters from the previous scan, for rates
+#[derive(Debug, Clone, Copy)]
+struct CgroupCounters {
+    cpu_usec: u64,
+    io_read: u64,
+    io_written: u64,
+}
+
+/// Walks the whole cgroup v2 hierarchy and tracks per-group rates
+#[derive(Debug)]
+pub struct CgroupScanner {
+    root: PathBuf,
+    previous: HashMap<String, CgroupCounters>,
+    last_scan: Option<Instant>,
+    groups: Vec<CgroupStats>,
+}
+
+impl CgroupScanner {
+    /// None where cgroup v2 is not mounted
+    pub fn new() -> Option<Self> {
+        Some(Self {
+            root: cgroup2_root()?,
+            previous: HashMap::new(),
+            last_scan: None,
+            groups: Vec::new(),
+        })
+    }
+
+    /// Groups from the last scan, parents before children
+    pub fn groups(&self) -> &[CgroupStats] {
+        &self.groups
+    }
+
+    pub fn refresh(&mut self) {
+        let now = Instant::now();
+        let elapsed = self.last_scan.map(|last| now.duration_since(last).as_secs_f64());
+        self.last_scan = Some(now);
+
+        let mut directories = Vec::new();
+        collect_directories(&self.root, &mut directories);
+
+        let mut counters = HashMap::with_capacity(directories.len());
+        self.groups = directories
+            .into_iter()
+            .map(|dir| {
+                let relative = dir.strip_prefix(&self.root).unwrap_or(&dir);
+                let path = format!("/{}", relative.display());
+                let cpu_usec = read_file(&dir, "cpu.stat").map_or(0, |text| parse_cpu_stat(&text).usage_usec);
+                let (io_read, io_written) = read_file(&dir, "io.stat").map_or((0, 0), |text| parse_io_stat(&text));
+                let current = CgroupCounters { cpu_usec, io_read, io_written };
+
+                let mut stats = CgroupStats {
+                    path: path.clone(),
+                    memory_current: read_file(&dir, "memory.current").and_then(|text| parse_limit(&text)),
+                    memory_max: read_file(&dir, "memory.max").and_then(|text| parse_limit(&text)),
+                    pids_current: read_file(&dir, "pids.current").and_then(|text| parse_limit(&text)),
+                    ..CgroupStats::default()
+                };
+                if let (Some(seconds), Some(previous)) = (elapsed.filter(|s| *s > 0.0), self.previous.get(&path)) {
+                    let used_usec = current.cpu_usec.saturating_sub(previous.cpu_usec) as f64;
+                    stats.cpu_usage = (used_usec / (seconds * 1_000_000.0) * 100.0) as f32;
+                    stats.io_read_rate = current.io_read.saturating_sub(previous.io_read) as f64 / seconds;
+                    stats.io_write_rate = current.io_written.saturating_sub(previous.io_written) as f64 / seconds;
+                }
+
+                counters.insert(path, current);
+                stats
+            })
+            .collect();
+        self.previous = counters;
+    }
+}
+
+/// `dir` and every cgroup directory below it, parents first
+fn collect_directories(dir: &Path, out: &mut Vec<PathBuf>) {
+    out.push(dir.to_path_buf());
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let mut children: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_dir()))
+        .map(|entry| entry.path())
+        .collect();
+    children.sort();
+    for child in children {
+        collect_directories(&child, out);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cpu_max_with_quota() {
+        let max = parse_cpu_max("150000 100000\n").unwrap();
+        assert_eq!(max, CpuMax { quota_usec: Some(150_000), period_usec: 100_000 });
+        assert_eq!(max.cores(), Some(1.5));
+    }
+
+    #[test]
+    fn cpu_max_unlimited() {
+        let max = parse_cpu_max("max 100000\n").unwrap();
+        assert_eq!(max.quota_usec, None);
+        assert_eq!(max.cores(), None);
+    }
+
+    #[test]
+    fn cpu_max_period_defaults_and_garbage() {
+        assert_eq!(parse_cpu_max("50000"), Some(CpuMax { quota_usec: Some(50_000), period_usec: 100_000 }));
+        assert_eq!(parse_cpu_max(""), None);
+        assert_eq!(parse_cpu_max("lots 100000"), None);
+        assert_eq!(parse_cpu_max("50000 often"), None);
+    }
+
+    #[test]
+    fn memory_max_limit() {
+        assert_eq!(parse_limit("536870912\n"), Some(536_870_912));
+        assert_eq!(parse_limit("max\n"), None);
+    }
+
+    #[test]
+    fn cpu_stat_fields() {
+        let stat = parse_cpu_stat(
+            "usage_usec 2000\nuser_usec 1500\nsystem_usec 500\ncore_sched.force_idle_usec 0\n\
+             nr_periods 10\nnr_throttled 2\nthrottled_usec 3000\n",
+        );
+        assert_eq!(
+            stat,
+            CgroupCpuStat {
+                usage_usec: 2000,
+                user_usec: 1500,
+                system_usec: 500,
+                nr_periods: 10,
+                nr_throttled: 2,
+                throttled_usec: 3000,
+            }
+        );
+    }
+
+    #[test]
+    fn io_stat_sums_every_device() {
+        let text = "8:0 rbytes=1024 wbytes=2048 rios=1 wios=2 dbytes=0 dios=0\n\
+                    259:0 rbytes=4096 wbytes=0 rios=3 wios=0 dbytes=0 dios=0\n";
+        assert_eq!(parse_io_stat(text), (5120, 2048));
+        assert_eq!(parse_io_stat(""), (0, 0));
+    }
+
+    #[test]
+    fn proc_cgroup_v2_line() {
+        let text = "12:cpu,cpuacct:/legacy\n0::/user.slice/user-1000.slice/session-2.scope\n";
+        assert_eq!(parse_proc_cgroup(text).as_deref(), Some("/user.slice/user-1000.slice/session-2.scope"));
+        assert_eq!(parse_proc_cgroup("12:cpu:/legacy\n"), None);
+    }
+
+    #[test]
+    fn cgroup2_mount_from_mountinfo() {
+        let mountinfo = "22 1 0:21 / /proc rw,nosuid - proc proc rw\n\
+                         35 24 0:30 / /sys/fs/cgroup rw,nosuid shared:9 - cgroup2 cgroup2 rw,nsdelegate\n";
+        assert_eq!(find_cgroup2_mount(mountinfo), Some(PathBuf::from("/sys/fs/cgroup")));
+        assert_eq!(find_cgroup2_mount("22 1 0:21 / /proc rw - proc proc rw\n"), None);
+    }
+}
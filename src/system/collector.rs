@@ -0,0 +1,174 @@
+use std::sync::Arc;
+use std::time::Duration;
+use log::{debug, error};
+use tokio::sync::{mpsc, watch};
+use tokio::task::JoinHandle;
+use tokio::time::{sleep_until, Instant};
+
+use super::monitor::{CollectorKind, HistoryLengths, SystemMonitor};
+use super::snapshot::SystemSnapshot;
+
+/// Shortest interval the scheduler will honour, to keep a zero or tiny
+/// setting from spinning the collector
+pub const MIN_COLLECTOR_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Control messages for a running collector
+#[derive(Debug, Clone, PartialEq)]
+pub enum CollectorCommand {
+    /// Sample every collector right away, even while paused
+    RefreshNow,
+    /// Stop sampling until resumed; the last snapshot stays published
+    Pause,
+    Resume,
+    /// Replace the per-collector intervals
+    SetSchedule(Vec<(CollectorKind, Duration)>),
+    /// Keep this many samples of history, dropping the oldest beyond it
+    SetHistory(HistoryLengths),
+}
+
+/// Owner's side of a spawned collector
+pub struct CollectorHandle {
+    commands: mpsc::UnboundedSender<CollectorCommand>,
+    task: JoinHandle<()>,
+}
+
+impl CollectorHandle {
+    pub fn send(&self, command: CollectorCommand) {
+        if self.commands.send(command).is_err() {
+            debug!("Collector has stopped; command dropped");
+        }
+    }
+
+    pub fn abort(&self) {
+        self.task.abort();
+    }
+}
+
+fn build_due(schedule: Vec<(CollectorKind, Duration)>, first_run: Instant) -> Vec<(CollectorKind, Duration, Instant)> {
+    schedule
+        .into_iter()
+        .map(|(kind, every)| (kind, every.max(MIN_COLLECTOR_INTERVAL), first_run))
+        .collect()
+}
+
+/// Move `monitor` onto a background task that refreshes each collector on its
+/// own interval and publishes a fresh snapshot after every pass.
+///
+/// Collectors that fall due at the same moment are refreshed together. The
+/// sysinfo calls run on tokio's blocking pool, so a slow refresh (large
+/// process tables, hung network mounts) never stalls input handling or drawing.
+/// The task ends when every receiver has been dropped.
+pub fn spawn_collector(
+    monitor: SystemMonitor,
+    schedule: Vec<(CollectorKind, Duration)>,
+) -> (watch::Receiver<Arc<SystemSnapshot>>, CollectorHandle) {
+    let (sender, receiver) = watch::channel(Arc::new(monitor.snapshot()));
+    let (command_sender, mut commands) = mpsc::unbounded_channel();
+
+    let task = tokio::spawn(async move {
+        let mut monitor = monitor;
+        let mut next_due = build_due(schedule, Instant::now());
+        let mut paused = false;
+        let mut commands_open = true;
+
+        loop {
+            // Far-future wake-up when nothing is scheduled or sampling is paused
+            let wake_at = next_due
+                .iter()
+                .map(|(_, _, due)| *due)
+                .min()
+                .filter(|_| !paused)
+                .unwrap_or_else(|| Instant::now() + Duration::from_secs(3600));
+
+            let due: Vec<CollectorKind> = tokio::select! {
+                _ = sleep_until(wake_at) => {
+                    if paused {
+                        continue;
+                    }
+                    let now = Instant::now();
+                    next_due
+                        .iter()
+                        .filter(|(_, _, at)| *at <= now)
+                        .map(|(kind, _, _)| *kind)
+                        .collect()
+                }
+                command = commands.recv(), if commands_open => match command {
+                    Some(CollectorCommand::RefreshNow) => {
+                        next_due.iter().map(|(kind, _, _)| *kind).collect()
+                    }
+                    Some(CollectorCommand::Pause) => {
+                        paused = true;
+                        continue;
+                    }
+                    Some(CollectorCommand::Resume) => {
+                        paused = false;
+                        // Catch up immediately instead of showing stale data
+                        // until each interval comes round
+                        for (_, _, at) in next_due.iter_mut() {
+                            *at = Instant::now();
+                        }
+                        continue;
+                    }
+                    Some(CollectorCommand::SetSchedule(schedule)) => {
+                        next_due = build_due(schedule, Instant::now());
+                        continue;
+                    }
+                    Some(CollectorCommand::SetHistory(lengths)) => {
+                        monitor.set_history_lengths(lengths);
+                        continue;
+                    }
+                    None => {
+                        commands_open = false;
+                        continue;
+                    }
+                },
+            };
+
+            let started = Instant::now();
+            let refreshed_kinds = due.clone();
+            let refreshed = tokio::task::spawn_blocking(move || {
+                for kind in due {
+                    monitor.refresh(kind);
+                }
+                let snapshot = monitor.snapshot();
+                (monitor, snapshot)
+            })
+            .await;
+
+            let snapshot = match refreshed {
+                Ok((returned, snapshot)) => {
+                    monitor = returned;
+                    snapshot
+                }
+                Err(err) => {
+                    error!("System collector stopped: {}", err);
+                    break;
+                }
+            };
+
+            // A refresh that overran its interval runs again straight away
+            // rather than queueing up missed passes
+            let finished = Instant::now();
+            for (kind, every, at) in next_due.iter_mut() {
+                if refreshed_kinds.contains(kind) {
+                    // Keep the cadence for passes that were due; a forced
+                    // refresh restarts the interval from now
+                    *at = ((*at).min(started) + *every).max(finished);
+                }
+            }
+
+            if sender.send(Arc::new(snapshot)).is_err() {
+                debug!("All snapshot receivers dropped, stopping collector");
+                break;
+            }
+        }
+    });
+
+    (
+        receiver,
+        CollectorHandle {
+            commands: command_sender,
+            task,
+        },
+    )
+}
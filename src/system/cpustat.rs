@@ -0,0 +1,258 @@
+use std::collections::BTreeMap;
+use std::fs;
+use serde::{Deserialize, Serialize};
+
+/// Cumulative CPU time counters from one `cpu` line of `/proc/stat`, in
+/// clock ticks since boot
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CpuTimes {
+    pub user: u64,
+    pub nice: u64,
+    pub system: u64,
+    pub idle: u64,
+    pub iowait: u64,
+    pub irq: u64,
+    pub softirq: u64,
+    pub steal: u64,
+}
+
+/// Share of elapsed CPU time spent in each state, in percent (sums to ~100)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct CpuBreakdown {
+    pub user: f32,
+    pub nice: f32,
+    pub system: f32,
+    pub idle: f32,
+    pub iowait: f32,
+    pub irq: f32,
+    pub softirq: f32,
+    pub steal: f32,
+}
+
+/// The aggregate `cpu` line and the per-core `cpuN` lines of `/proc/stat`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProcStat {
+    pub total: CpuTimes,
+    /// By core number; offline cores have no line, so numbers can skip
+    pub cores: BTreeMap<u32, CpuTimes>,
+}
+
+impl CpuTimes {
+    fn sum(&self) -> u64 {
+        self.user + self.nice + self.system + self.idle + self.iowait + self.irq + self.softirq + self.steal
+    }
+
+    /// Percentages for the interval between `earlier` and `self`.
+    ///
+    /// Counters that went backwards (CPU hotplug, counter reset) count as
+    /// zero; an interval with no ticks at all reports 100% idle.
+    pub fn breakdown_since(&self, earlier: &CpuTimes) -> CpuBreakdown {
+        let delta = CpuTimes {
+            user: self.user.saturating_sub(earlier.user),
+            nice: self.nice.saturating_sub(earlier.nice),
+            system: self.system.saturating_sub(earlier.system),
+            idle: self.idle.saturating_sub(earlier.idle),
+            iowait: self.iowait.saturating_sub(earlier.iowait),
+            irq: self.irq.saturating_sub(earlier.irq),
+            softirq: self.softirq.saturating_sub(earlier.softirq),
+            steal: self.steal.saturating_sub(earlier.steal),
+        };
+
+        let total = delta.sum();
+        if total == 0 {
+            return CpuBreakdown { idle: 100.0, ..CpuBreakdown::default() };
+        }
+        let percent = |ticks: u64| (ticks as f64 / total as f64 * 100.0) as f32;
+
+        CpuBreakdown {
+            user: percent(delta.user),
+            nice: percent(delta.nice),
+            system: percent(delta.system),
+            idle: percent(delta.idle),
+            iowait: percent(delta.iowait),
+            irq: percent(delta.irq),
+            softirq: percent(delta.softirq),
+            steal: percent(delta.steal),
+        }
+    }
+}
+
+impl CpuBreakdown {
+    /// Everything except idle and iowait
+    pub fn busy(&self) -> f32 {
+        self.user + self.nice + self.system + self.irq + self.softirq + self.steal
+    }
+}
+
+impl ProcStat {
+    /// Global and per-core breakdowns for the interval since `earlier`, by
+    /// core number; cores that went offline or came online in between are
+    /// left out
+    pub fn breakdown_since(&self, earlier: &ProcStat) -> (CpuBreakdown, BTreeMap<u32, CpuBreakdown>) {
+        let cores = self
+            .cores
+            .iter()
+            .filter_map(|(core, now)| Some((*core, now.breakdown_since(earlier.cores.get(core)?))))
+            .collect();
+        (self.total.breakdown_since(&earlier.total), cores)
+    }
+}
+
+/// Parse the CPU lines of `/proc/stat`.
+///
+/// Lines look like `cpu0 4705 356 584 3699 23 23 0 0 0 0`; the guest columns
+/// after `steal` are already included in user/nice and are ignored, and
+/// columns missing on old kernels read as zero.
+pub fn parse_proc_stat(text: &str) -> ProcStat {
+    let mut stat = ProcStat::default();
+
+    for line in text.lines() {
+        let mut fields = line.split_whitespace();
+        let Some(label) = fields.next() else {
+            continue;
+        };
+        let Some(core) = label.strip_prefix("cpu") else {
+            continue;
+        };
+        let core = match core {
+            "" => None,
+            number => match number.parse::<u32>() {
+                Ok(number) => Some(number),
+                Err(_) => continue,
+            },
+        };
+
+        let mut values = fields.map(|field| field.parse::<u64>().unwrap_or(0));
+        let mut next = || values.next().unwrap_or(0);
+        let times = CpuTimes {
+            user: next(),
+            nice: next(),
+            system: next(),
+            idle: next(),
+            iowait: next(),
+            irq: next(),
+            softirq: next(),
+            steal: next(),
+        };
+
+        match core {
+            None => stat.total = times,
+            Some(core) => {
+                stat.cores.insert(core, times);
+            }
+        }
+    }
+
+    stat
+}
+
+/// Read and parse `/proc/stat`; None where it does not exist
+pub fn read_proc_stat() -> Option<ProcStat> {
+    fs::read_to_string("/proc/stat")
+        .ok()
+        .map(|text| parse_proc_stat(&text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Captured from a two-core host, with the guest columns kernels append
+    const PROC_STAT: &str = "\
+cpu  4705 356 584 3699 23 23 0 0 0 0
+cpu0 2353 178 292 1849 12 12 0 0 0 0
+cpu1 2352 178 292 1850 11 11 0 0 0 0
+intr 114930548 113199788 3 0 5 263 0 4 [...]
+ctxt 1990473
+btime 1062191376
+processes 2915
+";
+
+    #[test]
+    fn parses_total_and_cores() {
+        let stat = parse_proc_stat(PROC_STAT);
+        assert_eq!(
+            stat.total,
+            CpuTimes { user: 4705, nice: 356, system: 584, idle: 3699, iowait: 23, irq: 23, softirq: 0, steal: 0 }
+        );
+        assert_eq!(stat.cores.len(), 2);
+        assert_eq!(stat.cores[&1].idle, 1850);
+    }
+
+    #[test]
+    fn missing_columns_read_as_zero() {
+        // Kernels before 2.6.11 stop after iowait/irq
+        let stat = parse_proc_stat("cpu 10 20 30 40\n");
+        assert_eq!(stat.total, CpuTimes { user: 10, nice: 20, system: 30, idle: 40, ..CpuTimes::default() });
+    }
+
+    #[test]
+    fn breakdown_is_share_of_elapsed_ticks() {
+        let earlier = CpuTimes { user: 100, system: 50, idle: 800, iowait: 50, ..CpuTimes::default() };
+        let now = CpuTimes { user: 150, system: 75, idle: 900, iowait: 75, ..CpuTimes::default() };
+        let breakdown = now.breakdown_since(&earlier);
+        assert_eq!(breakdown.user, 25.0);
+        assert_eq!(breakdown.system, 12.5);
+        assert_eq!(breakdown.idle, 50.0);
+        assert_eq!(breakdown.iowait, 12.5);
+        assert_eq!(breakdown.busy(), 37.5);
+    }
+
+    #[test]
+    fn no_elapsed_ticks_is_all_idle() {
+        let times = CpuTimes { user: 5, idle: 5, ..CpuTimes::default() };
+        assert_eq!(times.breakdown_since(&times), CpuBreakdown { idle: 100.0, ..CpuBreakdown::default() });
+    }
+
+    #[test]
+    fn counters_going_backwards_count_as_zero() {
+        let earlier = CpuTimes { user: 500, idle: 100, ..CpuTimes::default() };
+        let now = CpuTimes { user: 10, idle: 200, ..CpuTimes::default() };
+        let breakdown = now.breakdown_since(&earlier);
+        assert_eq!(breakdown.user, 0.0);
+        assert_eq!(breakdown.idle, 100.0);
+    }
+
+    #[test]
+    fn proc_stat_breakdown_covers_every_core() {
+        let earlier = parse_proc_stat(PROC_STAT);
+        let now = parse_proc_stat("\
+cpu  4805 356 584 3799 23 23 0 0 0 0
+cpu0 2453 178 292 1849 12 12 0 0 0 0
+cpu1 2352 178 292 1950 11 11 0 0 0 0
+");
+        let (total, cores) = now.breakdown_since(&earlier);
+        assert_eq!(total.user, 50.0);
+        assert_eq!(total.idle, 50.0);
+        assert_eq!(cores.len(), 2);
+        assert_eq!(cores[&0].user, 100.0);
+        assert_eq!(cores[&1].idle, 100.0);
+    }
+
+    #[test]
+    fn cores_are_matched_by_number_across_hotplug() {
+        // cpu1 went offline and cpu3 came online between the samples
+        let earlier = parse_proc_stat("\
+cpu  400 0 0 400 0 0 0 0
+cpu0 100 0 0 100 0 0 0 0
+cpu1 100 0 0 100 0 0 0 0
+cpu2 200 0 0 200 0 0 0 0
+");
+        let now = parse_proc_stat("\
+cpu  600 0 0 500 0 0 0 0
+cpu0 100 0 0 200 0 0 0 0
+cpu2 300 0 0 200 0 0 0 0
+cpu3 50 0 0 50 0 0 0 0
+");
+        let (_, cores) = now.breakdown_since(&earlier);
+        assert_eq!(cores.keys().copied().collect::<Vec<_>>(), [0, 2]);
+        assert_eq!(cores[&0].idle, 100.0);
+        assert_eq!(cores[&2].user, 100.0);
+    }
+
+    #[test]
+    fn unknown_cpu_labels_are_ignored() {
+        let stat = parse_proc_stat("cpu 1 2 3 4\ncpu0 1 1 1 1\ncpufreq 9 9 9 9\n");
+        assert_eq!(stat.cores.keys().copied().collect::<Vec<_>>(), [0]);
+    }
+}
@@ -0,0 +1,155 @@
+use std::fs;
+use serde::{Deserialize, Serialize};
+
+/// Kernel memory accounting from `/proc/meminfo`, in bytes.
+///
+/// Fields missing from the file (older kernels, other platforms) stay zero.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct MemInfo {
+    pub total: u64,
+    pub free: u64,
+    pub available: u64,
+    pub buffers: u64,
+    pub cached: u64,
+    pub shared: u64,
+    pub slab: u64,
+    pub slab_reclaimable: u64,
+    pub dirty: u64,
+    pub writeback: u64,
+    pub swap_cached: u64,
+    pub hugepages_total: u64,
+    pub hugepages_free: u64,
+    pub hugepage_size: u64,
+}
+
+impl MemInfo {
+    /// Memory held by all huge pages, whether in use or not
+    pub fn hugepages_bytes(&self) -> u64 {
+        self.hugepages_total * self.hugepage_size
+    }
+
+    /// Page cache plus buffers, the part of "used" memory the kernel can reclaim
+    pub fn buffers_cached(&self) -> u64 {
+        self.buffers + self.cached
+    }
+}
+
+/// Parse the contents of `/proc/meminfo`.
+///
+/// Lines look like `MemTotal:       16318412 kB`; unknown keys and malformed
+/// lines are ignored. `HugePages_*` counts are page counts, not sizes.
+pub fn parse_meminfo(text: &str) -> MemInfo {
+    let mut info = MemInfo::default();
+
+    for line in text.lines() {
+        let Some((key, rest)) = line.split_once(':') else {
+            continue;
+        };
+        let mut parts = rest.split_whitespace();
+        let Some(Ok(value)) = parts.next().map(str::parse::<u64>) else {
+            continue;
+        };
+        let value = match parts.next() {
+            Some("kB") => value.saturating_mul(1024),
+            _ => value,
+        };
+
+        let field = match key.trim() {
+            "MemTotal" => &mut info.total,
+            "MemFree" => &mut info.free,
+            "MemAvailable" => &mut info.available,
+            "Buffers" => &mut info.buffers,
+            "Cached" => &mut info.cached,
+            "Shmem" => &mut info.shared,
+            "Slab" => &mut info.slab,
+            "SReclaimable" => &mut info.slab_reclaimable,
+            "Dirty" => &mut info.dirty,
+            "Writeback" => &mut info.writeback,
+            "SwapCached" => &mut info.swap_cached,
+            "HugePages_Total" => &mut info.hugepages_total,
+            "HugePages_Free" => &mut info.hugepages_free,
+            "Hugepagesize" => &mut info.hugepage_size,
+            _ => continue,
+        };
+        *field = value;
+    }
+
+    info
+}
+
+/// Read and parse `/proc/meminfo`; None where it does not exist
+pub fn read_meminfo() -> Option<MemInfo> {
+    fs::read_to_string("/proc/meminfo")
+        .ok()
+        .map(|text| parse_meminfo(&text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Captured from a 16 GB x86_64 host, trimmed
+    const MEMINFO: &str = "\
+MemTotal:       16318412 kB
+MemFree:         1024000 kB
+MemAvailable:    9876543 kB
+Buffers:          204800 kB
+Cached:          6144000 kB
+SwapCached:         1024 kB
+Active:          7000000 kB
+Shmem:            512000 kB
+Slab:             800000 kB
+SReclaimable:     600000 kB
+Dirty:               128 kB
+Writeback:             0 kB
+HugePages_Total:       4
+HugePages_Free:        3
+HugePages_Rsvd:        0
+Hugepagesize:       2048 kB
+";
+
+    #[test]
+    fn parses_kilobyte_fields_as_bytes() {
+        let info = parse_meminfo(MEMINFO);
+        assert_eq!(info.total, 16318412 * 1024);
+        assert_eq!(info.available, 9876543 * 1024);
+        assert_eq!(info.shared, 512000 * 1024);
+        assert_eq!(info.slab_reclaimable, 600000 * 1024);
+        assert_eq!(info.buffers_cached(), (204800 + 6144000) * 1024);
+    }
+
+    #[test]
+    fn hugepage_counts_are_not_scaled() {
+        let info = parse_meminfo(MEMINFO);
+        assert_eq!(info.hugepages_total, 4);
+        assert_eq!(info.hugepages_free, 3);
+        assert_eq!(info.hugepage_size, 2048 * 1024);
+        assert_eq!(info.hugepages_bytes(), 4 * 2048 * 1024);
+    }
+
+    #[test]
+    fn missing_keys_stay_zero() {
+        let info = parse_meminfo("MemTotal: 1000 kB\nMemFree: 500 kB\n");
+        assert_eq!(info.total, 1000 * 1024);
+        assert_eq!(info.available, 0);
+        assert_eq!(info.hugepages_total, 0);
+    }
+
+    #[test]
+    fn value_without_unit_is_taken_as_is() {
+        let info = parse_meminfo("MemTotal: 4096\n");
+        assert_eq!(info.total, 4096);
+    }
+
+    #[test]
+    fn malformed_lines_are_skipped() {
+        let info = parse_meminfo("garbage\nMemFree: lots kB\nMemTotal: 2 kB\n");
+        assert_eq!(info, MemInfo { total: 2048, ..MemInfo::default() });
+    }
+
+    #[test]
+    fn huge_values_saturate() {
+        let info = parse_meminfo(&format!("MemTotal: {} kB\n", u64::MAX / 2));
+        assert_eq!(info.total, u64::MAX);
+    }
+}
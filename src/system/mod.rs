@@ -1,5 +1,19 @@
+pub mod alerts;
+pub mod cgroup;
+pub mod collector;
+pub mod cpustat;
+pub mod meminfo;
 pub mod monitor;
+pub mod pressure;
 pub mod processes;
+pub mod snapshot;
 
-pub use monitor::{SystemMonitor, CpuData, MemoryData, DiskInfo, NetworkInfo};
-// pub use processes::{ProcessInfo, ProcessManager}; // Will uncomment when we create these
\ No newline at end of file
+pub use alerts::{evaluate_alerts, Alert, AlertLevel};
+pub use cgroup::{sort_cgroups, CgroupReader, CgroupScanner, CgroupSort, CgroupStats, ContainerUsage};
+pub use collector::{spawn_collector, CollectorCommand, CollectorHandle};
+pub use cpustat::{parse_proc_stat, CpuBreakdown, CpuTimes, ProcStat};
+pub use meminfo::{parse_meminfo, MemInfo};
+pub use monitor::{SystemMonitor, CollectorKind, HistoryLengths, CpuData, LoadData, MemoryData, DiskInfo, NetworkInfo, SensorInfo};
+pub use pressure::{parse_pressure, Pressure, PressureAverages, PressureData};
+pub use processes::{sort_processes, terminate_process, ProcessInfo, ProcessSort};
+pub use snapshot::{HostInfo, LoadAverage, SystemSnapshot};
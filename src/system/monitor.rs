@@ -1,23 +1,68 @@
-use std::collections::VecDeque;
-use sysinfo::{System, Disks, Networks};
+use std::collections::{HashMap, VecDeque};
+use sysinfo::{Components, System, Disks, Networks};
 use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone)]
+use super::cgroup::{parse_proc_cgroup, CgroupReader, CgroupScanner, CgroupStats, ContainerUsage};
+use super::cpustat::{read_proc_stat, CpuBreakdown, ProcStat};
+use super::meminfo::{read_meminfo, MemInfo};
+use super::pressure::{read_pressure, PressureData};
+use super::processes::ProcessInfo;
+use super::snapshot::{HostInfo, LoadAverage, SystemSnapshot};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CpuData {
     pub timestamp: DateTime<Local>,
     pub usage: f32,
     pub frequency: u64,
+    pub per_core: Vec<f32>,
+    /// Time by state since the previous sample, where `/proc/stat` is available
+    pub breakdown: Option<CpuBreakdown>,
+    /// The same for each online core, in `per_core` order; empty right after
+    /// a core went offline or online
+    pub per_core_breakdown: Vec<CpuBreakdown>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MemoryData {
     pub timestamp: DateTime<Local>,
     pub used: u64,
     pub total: u64,
     pub usage_percent: f32,
+    pub available: u64,
+    pub free: u64,
+    pub swap_used: u64,
+    pub swap_total: u64,
+    /// Kernel breakdown, where `/proc/meminfo` is available
+    pub details: Option<MemInfo>,
+}
+
+impl MemoryData {
+    /// Page cache and buffers; estimated as available minus free without
+    /// `/proc/meminfo`
+    pub fn cache(&self) -> u64 {
+        match &self.details {
+            Some(details) => details.buffers_cached().min(self.total.saturating_sub(self.used)),
+            None => self.available.saturating_sub(self.free),
+        }
+    }
+
+    pub fn swap_usage_percent(&self) -> f32 {
+        if self.swap_total > 0 {
+            (self.swap_used as f32 / self.swap_total as f32) * 100.0
+        } else {
+            0.0
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoadData {
+    pub timestamp: DateTime<Local>,
+    pub load: LoadAverage,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DiskInfo {
     pub name: String,
     pub mount_point: String,
@@ -28,7 +73,14 @@ pub struct DiskInfo {
     pub file_system: String,
 }
 
-#[derive(Debug, Clone)]
+impl DiskInfo {
+    /// Read-only images such as snaps, which are always 100% full
+    pub fn is_image(&self) -> bool {
+        matches!(self.file_system.as_str(), "squashfs" | "iso9660")
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NetworkInfo {
     pub interface: String,
     pub bytes_received: u64,
@@ -37,12 +89,90 @@ pub struct NetworkInfo {
     pub packets_transmitted: u64,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SensorInfo {
+    pub label: String,
+    pub temperature: f32,
+    pub critical: Option<f32>,
+}
+
+/// The independently scheduled parts of a refresh
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CollectorKind {
+    Cpu,
+    Memory,
+    Processes,
+    Disks,
+    Network,
+    Sensors,
+    Pressure,
+    Cgroups,
+}
+
+impl CollectorKind {
+    pub const ALL: [CollectorKind; 8] = [
+        CollectorKind::Cpu,
+        CollectorKind::Memory,
+        CollectorKind::Processes,
+        CollectorKind::Disks,
+        CollectorKind::Network,
+        CollectorKind::Sensors,
+        CollectorKind::Pressure,
+        CollectorKind::Cgroups,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CollectorKind::Cpu => "cpu",
+            CollectorKind::Memory => "memory",
+            CollectorKind::Processes => "processes",
+            CollectorKind::Disks => "disks",
+            CollectorKind::Network => "network",
+            CollectorKind::Sensors => "sensors",
+            CollectorKind::Pressure => "pressure",
+            CollectorKind::Cgroups => "cgroups",
+        }
+    }
+}
+
+/// How many samples each history keeps; CPU also sizes the load history
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HistoryLengths {
+    pub cpu: usize,
+    pub memory: usize,
+    pub pressure: usize,
+}
+
+impl Default for HistoryLengths {
+    fn default() -> Self {
+        Self { cpu: 60, memory: 60, pressure: 60 }
+    }
+}
+
 #[derive(Debug)]
 pub struct SystemMonitor {
     system: System,
+    disks: Disks,
+    networks: Networks,
+    components: Components,
     cpu_history: VecDeque<CpuData>,
     memory_history: VecDeque<MemoryData>,
-    max_history: usize,
+    pressure_history: VecDeque<PressureData>,
+    load_history: VecDeque<LoadData>,
+    history: HistoryLengths,
+    // Counters from the previous CPU refresh, for /proc/stat deltas
+    last_proc_stat: Option<ProcStat>,
+    // Our own cgroup under cgroup v2, for container-relative usage
+    cgroup: Option<CgroupReader>,
+    // The whole cgroup v2 hierarchy, and which group each process is in
+    cgroups: Option<CgroupScanner>,
+    process_cgroups: HashMap<u32, String>,
+}
+
+impl Default for SystemMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl SystemMonitor {
@@ -52,42 +182,153 @@ impl SystemMonitor {
         
         Self {
             system,
+            disks: Disks::new_with_refreshed_list(),
+            networks: Networks::new_with_refreshed_list(),
+            components: Components::new_with_refreshed_list(),
             cpu_history: VecDeque::new(),
             memory_history: VecDeque::new(),
-            max_history: 60, // Keep 60 data points by default
+            pressure_history: VecDeque::new(),
+            load_history: VecDeque::new(),
+            history: HistoryLengths::default(),
+            last_proc_stat: read_proc_stat(),
+            cgroup: CgroupReader::detect(),
+            cgroups: CgroupScanner::new(),
+            process_cgroups: HashMap::new(),
         }
     }
 
     pub fn refresh_all(&mut self) {
-        self.system.refresh_all();
-        self.update_cpu_history();
-        self.update_memory_history();
+        for kind in CollectorKind::ALL {
+            self.refresh(kind);
+        }
+    }
+
+    /// Refresh a single collector's data
+    pub fn refresh(&mut self, kind: CollectorKind) {
+        match kind {
+            CollectorKind::Cpu => self.refresh_cpu(),
+            CollectorKind::Memory => self.refresh_memory(),
+            CollectorKind::Processes => self.refresh_processes(),
+            CollectorKind::Disks => self.refresh_disks(),
+            CollectorKind::Network => self.refresh_networks(),
+            CollectorKind::Sensors => self.refresh_sensors(),
+            CollectorKind::Pressure => self.refresh_pressure(),
+            CollectorKind::Cgroups => self.refresh_cgroups(),
+        }
     }
 
     pub fn refresh_cpu(&mut self) {
         self.system.refresh_cpu();
+        if let Some(cgroup) = &mut self.cgroup {
+            cgroup.refresh_cpu(self.system.cpus().len());
+        }
         self.update_cpu_history();
+        self.update_load_history();
     }
 
     pub fn refresh_memory(&mut self) {
         self.system.refresh_memory();
+        if let Some(cgroup) = &mut self.cgroup {
+            cgroup.refresh_memory();
+        }
         self.update_memory_history();
     }
 
+    pub fn refresh_processes(&mut self) {
+        self.system.refresh_processes();
+        if self.cgroups.is_some() {
+            self.process_cgroups = self
+                .system
+                .processes()
+                .keys()
+                .filter_map(|pid| {
+                    let text = std::fs::read_to_string(format!("/proc/{}/cgroup", pid)).ok()?;
+                    Some((pid.as_u32(), parse_proc_cgroup(&text)?))
+                })
+                .collect();
+        }
+    }
+
+    pub fn refresh_disks(&mut self) {
+        // Re-list so mounts that come and go are picked up
+        self.disks.refresh_list();
+    }
+
+    pub fn refresh_networks(&mut self) {
+        // Re-list so interfaces that come and go are picked up
+        self.networks.refresh_list();
+    }
+
+    pub fn refresh_sensors(&mut self) {
+        self.components.refresh();
+    }
+
+    /// Rescan the cgroup v2 hierarchy; a no-op without cgroup v2
+    pub fn refresh_cgroups(&mut self) {
+        if let Some(cgroups) = &mut self.cgroups {
+            cgroups.refresh();
+        }
+    }
+
+    pub fn cgroup_stats(&self) -> Vec<CgroupStats> {
+        self.cgroups.as_ref().map(|cgroups| cgroups.groups().to_vec()).unwrap_or_default()
+    }
+
+    /// Sample PSI; kernels without it leave the history empty
+    pub fn refresh_pressure(&mut self) {
+        let pressure = read_pressure();
+        if !pressure.is_available() {
+            return;
+        }
+
+        self.pressure_history.push_back(pressure);
+        if self.pressure_history.len() > self.history.pressure {
+            self.pressure_history.pop_front();
+        }
+    }
+
     fn update_cpu_history(&mut self) {
+        let proc_stat = read_proc_stat();
+        let (breakdown, per_core_breakdown) = match (&proc_stat, &self.last_proc_stat) {
+            (Some(now), Some(earlier)) => {
+                let (total, cores) = now.breakdown_since(earlier);
+                // The per-core list lines up with the online cores by position,
+                // so after a hotplug it waits for two samples with the same cores
+                let cores = if cores.len() == now.cores.len() { cores.into_values().collect() } else { Vec::new() };
+                (Some(total), cores)
+            }
+            _ => (None, Vec::new()),
+        };
+        self.last_proc_stat = proc_stat;
+
         let global_cpu = self.system.global_cpu_info();
         let cpu_data = CpuData {
             timestamp: Local::now(),
             usage: global_cpu.cpu_usage(),
             frequency: global_cpu.frequency(),
+            per_core: self.system.cpus().iter().map(|cpu| cpu.cpu_usage()).collect(),
+            breakdown,
+            per_core_breakdown,
         };
 
         self.cpu_history.push_back(cpu_data);
-        if self.cpu_history.len() > self.max_history {
+        if self.cpu_history.len() > self.history.cpu {
             self.cpu_history.pop_front();
         }
     }
 
+    fn update_load_history(&mut self) {
+        let load_data = LoadData {
+            timestamp: Local::now(),
+            load: self.load(),
+        };
+
+        self.load_history.push_back(load_data);
+        if self.load_history.len() > self.history.cpu {
+            self.load_history.pop_front();
+        }
+    }
+
     fn update_memory_history(&mut self) {
         let used = self.system.used_memory();
         let total = self.system.total_memory();
@@ -102,10 +343,15 @@ impl SystemMonitor {
             used,
             total,
             usage_percent,
+            available: self.system.available_memory(),
+            free: self.system.free_memory(),
+            swap_used: self.system.used_swap(),
+            swap_total: self.system.total_swap(),
+            details: read_meminfo(),
         };
 
         self.memory_history.push_back(memory_data);
-        if self.memory_history.len() > self.max_history {
+        if self.memory_history.len() > self.history.memory {
             self.memory_history.pop_front();
         }
     }
@@ -119,6 +365,10 @@ impl SystemMonitor {
         self.system.cpus().len()
     }
 
+    pub fn cpu_core_usage(&self) -> Vec<f32> {
+        self.system.cpus().iter().map(|cpu| cpu.cpu_usage()).collect()
+    }
+
     pub fn cpu_history(&self) -> &VecDeque<CpuData> {
         &self.cpu_history
     }
@@ -161,18 +411,31 @@ impl SystemMonitor {
         System::boot_time()
     }
 
+    /// Usage of our own cgroup, when running under cgroup v2
+    pub fn container_usage(&self) -> Option<&ContainerUsage> {
+        self.cgroup.as_ref().map(CgroupReader::usage)
+    }
+
     pub fn load_average(&self) -> sysinfo::LoadAvg {
         System::load_average()
     }
 
+    fn load(&self) -> LoadAverage {
+        let load_avg = self.load_average();
+        LoadAverage {
+            one: load_avg.one,
+            five: load_avg.five,
+            fifteen: load_avg.fifteen,
+        }
+    }
+
     pub fn disk_info(&self) -> Vec<DiskInfo> {
-        let disks = Disks::new_with_refreshed_list();
-        disks
+        self.disks
             .iter()
             .map(|disk| {
                 let total = disk.total_space();
                 let available = disk.available_space();
-                let used = total - available;
+                let used = total.saturating_sub(available);
                 let usage_percent = if total > 0 {
                     (used as f32 / total as f32) * 100.0
                 } else {
@@ -193,19 +456,84 @@ impl SystemMonitor {
     }
 
     pub fn network_info(&self) -> Vec<NetworkInfo> {
-        let networks = Networks::new_with_refreshed_list();
-        networks
+        let mut networks: Vec<NetworkInfo> = self
+            .networks
             .iter()
             .map(|(interface, data)| NetworkInfo {
                 interface: interface.clone(),
-                bytes_received: data.received(),
-                bytes_transmitted: data.transmitted(),
-                packets_received: data.packets_received(),
-                packets_transmitted: data.packets_transmitted(),
+                bytes_received: data.total_received(),
+                bytes_transmitted: data.total_transmitted(),
+                packets_received: data.total_packets_received(),
+                packets_transmitted: data.total_packets_transmitted(),
+            })
+            .collect();
+        networks.sort_by(|a, b| a.interface.cmp(&b.interface));
+        networks
+    }
+
+    pub fn sensor_info(&self) -> Vec<SensorInfo> {
+        self.components
+            .iter()
+            .map(|component| SensorInfo {
+                label: component.label().to_string(),
+                temperature: component.temperature(),
+                critical: component.critical(),
             })
             .collect()
     }
 
+    /// Processes sorted by CPU usage (descending)
+    pub fn process_info(&self) -> Vec<ProcessInfo> {
+        let mut processes: Vec<ProcessInfo> = self
+            .system
+            .processes()
+            .iter()
+            .map(|(pid, process)| ProcessInfo {
+                pid: pid.as_u32(),
+                name: process.name().to_string(),
+                cpu_usage: process.cpu_usage(),
+                memory: process.memory(),
+                cgroup: self.process_cgroups.get(&pid.as_u32()).cloned(),
+            })
+            .collect();
+        processes.sort_by(|a, b| b.cpu_usage.partial_cmp(&a.cpu_usage).unwrap_or(std::cmp::Ordering::Equal));
+        processes
+    }
+
+    /// Capture everything the UI needs as an immutable value
+    pub fn snapshot(&self) -> SystemSnapshot {
+        SystemSnapshot {
+            timestamp: Local::now(),
+            host: HostInfo {
+                host_name: System::host_name(),
+                os_version: System::long_os_version(),
+                kernel_version: System::kernel_version(),
+                cpu_arch: System::cpu_arch(),
+            },
+            cpu_usage: self.cpu_usage(),
+            cpu_core_usage: self.cpu_core_usage(),
+            cpu_history: self.cpu_history.clone(),
+            memory_used: self.memory_used(),
+            memory_total: self.memory_total(),
+            memory_available: self.system.available_memory(),
+            memory_free: self.system.free_memory(),
+            memory_history: self.memory_history.clone(),
+            pressure_history: self.pressure_history.clone(),
+            swap_used: self.swap_used(),
+            swap_total: self.swap_total(),
+            uptime: self.uptime(),
+            boot_time: self.boot_time(),
+            load_average: self.load(),
+            load_history: self.load_history.clone(),
+            container: self.container_usage().cloned(),
+            cgroups: self.cgroup_stats(),
+            disks: self.disk_info(),
+            networks: self.network_info(),
+            processes: self.process_info(),
+            sensors: self.sensor_info(),
+        }
+    }
+
     pub fn process_count(&self) -> usize {
         self.system.processes().len()
     }
@@ -215,13 +543,20 @@ impl SystemMonitor {
     }
 
     pub fn set_max_history(&mut self, max: usize) {
-        self.max_history = max;
-        // Trim existing history if needed
-        while self.cpu_history.len() > max {
-            self.cpu_history.pop_front();
-        }
-        while self.memory_history.len() > max {
-            self.memory_history.pop_front();
-        }
+        self.set_history_lengths(HistoryLengths { cpu: max, memory: max, pressure: max });
+    }
+
+    /// Change how many samples each history keeps, trimming the oldest
+    pub fn set_history_lengths(&mut self, lengths: HistoryLengths) {
+        self.history = lengths;
+        truncate_front(&mut self.cpu_history, lengths.cpu);
+        truncate_front(&mut self.load_history, lengths.cpu);
+        truncate_front(&mut self.memory_history, lengths.memory);
+        truncate_front(&mut self.pressure_history, lengths.pressure);
     }
-}
\ No newline at end of file
+}
+
+fn truncate_front<T>(history: &mut VecDeque<T>, max: usize) {
+    let excess = history.len().saturating_sub(max);
+    history.drain(..excess);
+}
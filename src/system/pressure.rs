@@ -0,0 +1,137 @@
+use std::fs;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+/// One line of a `/proc/pressure/*` file: the share of wall time (percent)
+/// that tasks were stalled, averaged over 10 s, 60 s and 300 s
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct PressureAverages {
+    pub avg10: f32,
+    pub avg60: f32,
+    pub avg300: f32,
+    /// Total stall time in microseconds
+    pub total: u64,
+}
+
+/// `some`: at least one task stalled; `full`: all non-idle tasks stalled at once
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Pressure {
+    pub some: PressureAverages,
+    /// Absent for CPU on kernels before 5.13
+    pub full: Option<PressureAverages>,
+}
+
+/// Pressure Stall Information for one sample. A resource is None when its
+/// file could not be read; all three are None on kernels without PSI.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PressureData {
+    pub timestamp: DateTime<Local>,
+    pub cpu: Option<Pressure>,
+    pub memory: Option<Pressure>,
+    pub io: Option<Pressure>,
+}
+
+impl PressureData {
+    pub fn is_available(&self) -> bool {
+        self.cpu.is_some() || self.memory.is_some() || self.io.is_some()
+    }
+
+    /// (name, pressure) for each resource, in display order
+    pub fn resources(&self) -> [(&'static str, Option<&Pressure>); 3] {
+        [
+            ("cpu", self.cpu.as_ref()),
+            ("memory", self.memory.as_ref()),
+            ("io", self.io.as_ref()),
+        ]
+    }
+}
+
+/// Parse one `/proc/pressure/{cpu,memory,io}` file, e.g.
+///
+/// ```text
+/// some avg10=1.69 avg60=3.00 avg300=2.65 total=54733803
+/// full avg10=0.00 avg60=0.00 avg300=0.00 total=0
+/// ```
+///
+/// Returns None when there is no `some` line.
+pub fn parse_pressure(text: &str) -> Option<Pressure> {
+    let mut some = None;
+    let mut full = None;
+
+    for line in text.lines() {
+        let mut fields = line.split_whitespace();
+        let slot = match fields.next() {
+            Some("some") => &mut some,
+            Some("full") => &mut full,
+            _ => continue,
+        };
+
+        let mut averages = PressureAverages::default();
+        for field in fields {
+            let Some((key, value)) = field.split_once('=') else {
+                continue;
+            };
+            match key {
+                "avg10" => averages.avg10 = value.parse().unwrap_or(0.0),
+                "avg60" => averages.avg60 = value.parse().unwrap_or(0.0),
+                "avg300" => averages.avg300 = value.parse().unwrap_or(0.0),
+                "total" => averages.total = value.parse().unwrap_or(0),
+                _ => {}
+            }
+        }
+        *slot = Some(averages);
+    }
+
+    some.map(|some| Pressure { some, full })
+}
+
+fn read_resource(name: &str) -> Option<Pressure> {
+    fs::read_to_string(format!("/proc/pressure/{}", name))
+        .ok()
+        .and_then(|text| parse_pressure(&text))
+}
+
+/// Read all three PSI files
+pub fn read_pressure() -> PressureData {
+    PressureData {
+        timestamp: Local::now(),
+        cpu: read_resource("cpu"),
+        memory: read_resource("memory"),
+        io: read_resource("io"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_some_and_full_lines() {
+        let pressure = parse_pressure(
+            "some avg10=1.69 avg60=3.00 avg300=2.65 total=54733803\n\
+             full avg10=0.50 avg60=0.25 avg300=0.10 total=1200\n",
+        )
+        .unwrap();
+        assert_eq!(pressure.some, PressureAverages { avg10: 1.69, avg60: 3.0, avg300: 2.65, total: 54733803 });
+        assert_eq!(pressure.full, Some(PressureAverages { avg10: 0.5, avg60: 0.25, avg300: 0.1, total: 1200 }));
+    }
+
+    #[test]
+    fn cpu_before_5_13_has_no_full_line() {
+        let pressure = parse_pressure("some avg10=0.00 avg60=0.12 avg300=0.08 total=7\n").unwrap();
+        assert_eq!(pressure.some.avg60, 0.12);
+        assert_eq!(pressure.full, None);
+    }
+
+    #[test]
+    fn without_some_line_there_is_no_pressure() {
+        assert_eq!(parse_pressure(""), None);
+        assert_eq!(parse_pressure("full avg10=1.00 avg60=1.00 avg300=1.00 total=1\n"), None);
+    }
+
+    #[test]
+    fn bad_fields_read_as_zero() {
+        let pressure = parse_pressure("some avg10=x avg60 avg300=2.5 total=-1 extra=1\n").unwrap();
+        assert_eq!(pressure.some, PressureAverages { avg300: 2.5, ..PressureAverages::default() });
+    }
+}
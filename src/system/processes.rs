@@ -1,2 +1,75 @@
-// Process management module - to be implemented
-// This is a placeholder to prevent compilation errors
\ No newline at end of file
+// Process information carried in snapshots
+
+use anyhow::{anyhow, bail, Result};
+use sysinfo::{Pid, Signal, System};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    pub name: String,
+    pub cpu_usage: f32,
+    pub memory: u64,
+    /// cgroup v2 path from `/proc/<pid>/cgroup`
+    pub cgroup: Option<String>,
+}
+
+/// Columns the process list can be ordered by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProcessSort {
+    Cpu,
+    Memory,
+    Pid,
+    Name,
+}
+
+impl ProcessSort {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ProcessSort::Cpu => "CPU",
+            ProcessSort::Memory => "Memory",
+            ProcessSort::Pid => "PID",
+            ProcessSort::Name => "Name",
+        }
+    }
+
+    /// Whether a fresh sort on this column starts with the largest values first
+    pub fn descending_by_default(&self) -> bool {
+        matches!(self, ProcessSort::Cpu | ProcessSort::Memory)
+    }
+}
+
+pub fn sort_processes(processes: &mut [&ProcessInfo], sort: ProcessSort, descending: bool) {
+    processes.sort_by(|a, b| {
+        let ordering = match sort {
+            ProcessSort::Cpu => a.cpu_usage.partial_cmp(&b.cpu_usage).unwrap_or(std::cmp::Ordering::Equal),
+            ProcessSort::Memory => a.memory.cmp(&b.memory),
+            ProcessSort::Pid => a.pid.cmp(&b.pid),
+            ProcessSort::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+        };
+        if descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    });
+}
+
+/// Ask a process to exit with SIGTERM (or the platform's closest equivalent)
+pub fn terminate_process(pid: u32) -> Result<()> {
+    let pid = Pid::from_u32(pid);
+    let mut system = System::new();
+    if !system.refresh_process(pid) {
+        bail!("process {} no longer exists", pid);
+    }
+
+    let process = system
+        .process(pid)
+        .ok_or_else(|| anyhow!("process {} no longer exists", pid))?;
+    match process.kill_with(Signal::Term) {
+        Some(true) => Ok(()),
+        Some(false) => bail!("failed to signal process {} (permission denied?)", pid),
+        None => bail!("SIGTERM is not supported on this platform"),
+    }
+}
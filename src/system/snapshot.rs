@@ -0,0 +1,109 @@
+use std::collections::VecDeque;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use super::cgroup::{CgroupStats, ContainerUsage};
+use super::monitor::{CpuData, DiskInfo, LoadData, MemoryData, NetworkInfo, SensorInfo};
+use super::pressure::PressureData;
+use super::processes::ProcessInfo;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HostInfo {
+    pub host_name: Option<String>,
+    pub os_version: Option<String>,
+    pub kernel_version: Option<String>,
+    pub cpu_arch: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct LoadAverage {
+    pub one: f64,
+    pub five: f64,
+    pub fifteen: f64,
+}
+
+impl LoadAverage {
+    /// Load per CPU core, comparable across hosts of different sizes
+    pub fn per_core(&self, cpu_count: usize) -> LoadAverage {
+        let cores = cpu_count.max(1) as f64;
+        LoadAverage {
+            one: self.one / cores,
+            five: self.five / cores,
+            fifteen: self.fifteen / cores,
+        }
+    }
+}
+
+/// An immutable, point-in-time copy of everything the dashboard renders.
+///
+/// Produced by the collector task after each refresh and shared with the UI
+/// behind an `Arc`, so drawing a frame never touches sysinfo.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SystemSnapshot {
+    pub timestamp: DateTime<Local>,
+    pub host: HostInfo,
+    pub cpu_usage: f32,
+    pub cpu_core_usage: Vec<f32>,
+    pub cpu_history: VecDeque<CpuData>,
+    pub memory_used: u64,
+    pub memory_total: u64,
+    pub memory_available: u64,
+    pub memory_free: u64,
+    pub memory_history: VecDeque<MemoryData>,
+    pub swap_used: u64,
+    pub swap_total: u64,
+    /// Empty on kernels without PSI
+    pub pressure_history: VecDeque<PressureData>,
+    pub uptime: u64,
+    pub boot_time: u64,
+    pub load_average: LoadAverage,
+    pub load_history: VecDeque<LoadData>,
+    /// Our own cgroup's usage, when running under cgroup v2
+    pub container: Option<ContainerUsage>,
+    /// Every group in the cgroup v2 hierarchy, parents first; empty without cgroup v2
+    pub cgroups: Vec<CgroupStats>,
+    pub disks: Vec<DiskInfo>,
+    pub networks: Vec<NetworkInfo>,
+    /// Sorted by CPU usage (descending)
+    pub processes: Vec<ProcessInfo>,
+    pub sensors: Vec<SensorInfo>,
+}
+
+impl SystemSnapshot {
+    /// The latest PSI sample, if the kernel provides PSI
+    pub fn pressure(&self) -> Option<&PressureData> {
+        self.pressure_history.back()
+    }
+
+    pub fn cpu_count(&self) -> usize {
+        self.cpu_core_usage.len()
+    }
+
+    pub fn memory_usage_percent(&self) -> f32 {
+        if self.memory_total > 0 {
+            (self.memory_used as f32 / self.memory_total as f32) * 100.0
+        } else {
+            0.0
+        }
+    }
+
+    pub fn swap_usage_percent(&self) -> f32 {
+        if self.swap_total > 0 {
+            (self.swap_used as f32 / self.swap_total as f32) * 100.0
+        } else {
+            0.0
+        }
+    }
+
+    /// The most used writable filesystem
+    pub fn fullest_disk(&self) -> Option<&DiskInfo> {
+        self.disks
+            .iter()
+            .filter(|disk| !disk.is_image())
+            .max_by(|a, b| a.usage_percent.total_cmp(&b.usage_percent))
+    }
+
+    pub fn process_count(&self) -> usize {
+        self.processes.len()
+    }
+}
@@ -5,50 +5,282 @@ use ratatui::{
     widgets::{Block, Borders, Paragraph, Tabs},
     Frame,
 };
-use crossterm::event::Event;
+use crossterm::event::{Event, KeyCode, MouseButton, MouseEvent, MouseEventKind};
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
 
+use crate::config::settings::UsageView;
 use crate::config::Settings;
-use crate::system::SystemMonitor;
-use super::events::{handle_key_event, should_quit, AppAction};
-use super::widgets::{CpuWidget, MemoryWidget, SystemInfoWidget, DiskWidget, ProcessWidget, NetworkWidget};
+use crate::remote::HostStatus;
+use crate::system::{evaluate_alerts, AlertLevel, CgroupSort, ContainerUsage, ProcessInfo, ProcessSort, SystemSnapshot};
+use super::events::{AppAction, Command};
+use super::keybindings::Keymap;
+use super::palette::{CommandPalette, PaletteOutcome};
+use super::settings_editor::{FieldKind, InputOutcome, SettingsEditor};
+use super::state::UiState;
+use super::widgets::{CpuWidget, MemoryWidget, SystemInfoWidget, PressureWidget, DiskWidget, ProcessWidget, CgroupWidget, FleetWidget, NetworkWidget, TABLE_FIRST_ROW_OFFSET};
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum TabIndex {
     Overview = 0,
     Processes = 1,
     Network = 2,
-    Help = 3,
+    Cgroups = 3,
+    Fleet = 4,
+    Settings = 5,
+    Help = 6,
+}
+
+impl TabIndex {
+    const ALL: [TabIndex; 7] = [
+        TabIndex::Overview,
+        TabIndex::Processes,
+        TabIndex::Network,
+        TabIndex::Cgroups,
+        TabIndex::Fleet,
+        TabIndex::Settings,
+        TabIndex::Help,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TabIndex::Overview => "Overview",
+            TabIndex::Processes => "Processes",
+            TabIndex::Network => "Network",
+            TabIndex::Cgroups => "Cgroups",
+            TabIndex::Fleet => "Fleet",
+            TabIndex::Settings => "Settings",
+            TabIndex::Help => "Help",
+        }
+    }
 }
 
 impl From<usize> for TabIndex {
     fn from(index: usize) -> Self {
-        match index {
-            0 => TabIndex::Overview,
-            1 => TabIndex::Processes,
-            2 => TabIndex::Network,
-            3 => TabIndex::Help,
-            _ => TabIndex::Overview,
+        TabIndex::ALL.get(index).cloned().unwrap_or(TabIndex::Overview)
+    }
+}
+
+/// The panels of the Overview grid, in selection order
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Panel {
+    CpuGauge,
+    MemoryGauge,
+    SwapGauge,
+    CpuChart,
+    MemoryChart,
+    SystemInfo,
+    Pressure,
+    Disks,
+}
+
+impl Panel {
+    const ALL: [Panel; 8] = [
+        Panel::CpuGauge,
+        Panel::MemoryGauge,
+        Panel::SwapGauge,
+        Panel::CpuChart,
+        Panel::MemoryChart,
+        Panel::SystemInfo,
+        Panel::Pressure,
+        Panel::Disks,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Panel::CpuGauge => "CPU Usage",
+            Panel::MemoryGauge => "Memory Usage",
+            Panel::SwapGauge => "Swap Usage",
+            Panel::CpuChart => "CPU History",
+            Panel::MemoryChart => "Memory History",
+            Panel::SystemInfo => "System Info",
+            Panel::Pressure => "Pressure (PSI)",
+            Panel::Disks => "Disk Usage",
         }
     }
+
+    fn index(&self) -> usize {
+        Panel::ALL.iter().position(|panel| panel == self).unwrap_or(0)
+    }
+
+    fn next(&self) -> Panel {
+        Panel::ALL[(self.index() + 1) % Panel::ALL.len()]
+    }
+
+    fn prev(&self) -> Panel {
+        Panel::ALL[(self.index() + Panel::ALL.len() - 1) % Panel::ALL.len()]
+    }
 }
 
 pub struct Dashboard {
     settings: Settings,
+    keymap: Keymap,
     current_tab: TabIndex,
     process_scroll_offset: usize,
+    process_sort: ProcessSort,
+    sort_descending: bool,
+    palette: Option<CommandPalette>,
+    selected_panel: Panel,
+    focused_panel: Option<Panel>,
+    selected_pid: Option<u32>,
+    selected_process_name: Option<String>,
+    disk_scroll_offset: usize,
+    selected_disk: Option<String>,
+    // Where things were drawn last frame, for mouse hit-testing
+    panel_areas: Vec<(Panel, Rect)>,
+    tab_areas: Vec<Rect>,
+    process_list_area: Rect,
+    // Every PID in last frame's order, and how many rows fit
+    process_order: Vec<u32>,
+    process_rows: usize,
+    disk_table_area: Rect,
+    visible_disks: Vec<String>,
+    cgroup_sort: CgroupSort,
+    cgroup_sort_descending: bool,
+    cgroup_scroll_offset: usize,
+    selected_cgroup: Option<String>,
+    // The cgroup whose member processes are listed, if drilled in
+    open_cgroup: Option<String>,
+    // Every cgroup path in last frame's order, and how many rows fit
+    cgroup_order: Vec<String>,
+    cgroup_rows: usize,
+    cgroup_table_area: Rect,
+    // Every host the dashboard can show, and which one the other tabs follow
+    hosts: Vec<HostStatus>,
+    active_host: usize,
+    selected_host: usize,
+    fleet_scroll_offset: usize,
+    // How many host rows fit in last frame's Fleet table
+    fleet_rows: usize,
+    fleet_table_area: Rect,
+    settings_editor: SettingsEditor,
+    // Collector state as last requested from the main loop
+    paused: bool,
+    status_message: Option<(String, Instant)>,
+    // A command waiting for y/n, with the question to show
+    confirm: Option<(String, Command)>,
 }
 
+// Rows moved per mouse wheel notch
+const MOUSE_SCROLL_LINES: usize = 3;
+
+// How long a status message stays in the status bar
+const STATUS_MESSAGE_TIMEOUT: Duration = Duration::from_secs(5);
+
 impl Dashboard {
-    pub fn new(settings: Settings) -> Self {
+    pub fn new(settings: Settings, keymap: Keymap) -> Self {
         Self {
             settings,
+            keymap,
             current_tab: TabIndex::Overview,
             process_scroll_offset: 0,
+            process_sort: ProcessSort::Cpu,
+            sort_descending: true,
+            palette: None,
+            selected_panel: Panel::CpuGauge,
+            focused_panel: None,
+            selected_pid: None,
+            selected_process_name: None,
+            disk_scroll_offset: 0,
+            selected_disk: None,
+            panel_areas: Vec::new(),
+            tab_areas: Vec::new(),
+            process_list_area: Rect::default(),
+            process_order: Vec::new(),
+            process_rows: 0,
+            disk_table_area: Rect::default(),
+            visible_disks: Vec::new(),
+            cgroup_sort: CgroupSort::Cpu,
+            cgroup_sort_descending: true,
+            cgroup_scroll_offset: 0,
+            selected_cgroup: None,
+            open_cgroup: None,
+            cgroup_order: Vec::new(),
+            cgroup_rows: 0,
+            cgroup_table_area: Rect::default(),
+            hosts: Vec::new(),
+            active_host: 0,
+            selected_host: 0,
+            fleet_scroll_offset: 0,
+            fleet_rows: 0,
+            fleet_table_area: Rect::default(),
+            settings_editor: SettingsEditor::default(),
+            paused: false,
+            status_message: None,
+            confirm: None,
+        }
+    }
+
+    /// Show a short note in the status bar, e.g. the outcome of a command
+    pub fn show_message(&mut self, message: impl Into<String>) {
+        self.status_message = Some((message.into(), Instant::now()));
+    }
+
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    pub fn keymap(&self) -> &Keymap {
+        &self.keymap
+    }
+
+    /// Switch to reloaded settings; navigation, selections and the paused
+    /// state carry over
+    pub fn apply_settings(&mut self, settings: Settings, keymap: Keymap) {
+        self.settings = settings;
+        self.keymap = keymap;
+    }
+
+    /// The Settings tab's changes are in the config file now
+    pub fn settings_saved(&mut self) {
+        self.settings_editor.clear_changes();
+    }
+
+    /// How many settings were changed on the Settings tab and not saved yet
+    pub fn unsaved_setting_changes(&self) -> usize {
+        self.settings_editor.changed().len()
+    }
+
+    /// Where the user is, to be saved for the next session
+    pub fn ui_state(&self) -> UiState {
+        UiState {
+            tab: self.current_tab.clone(),
+            process_sort: self.process_sort,
+            process_sort_descending: self.sort_descending,
+            cgroup_sort: self.cgroup_sort,
+            cgroup_sort_descending: self.cgroup_sort_descending,
+            selected_panel: self.selected_panel,
+            focused_panel: self.focused_panel,
         }
     }
 
-    pub fn render(&mut self, f: &mut Frame, monitor: &SystemMonitor) {
+    /// Pick up where a previous session left off
+    pub fn restore_ui_state(&mut self, state: UiState) {
+        self.current_tab = state.tab;
+        self.process_sort = state.process_sort;
+        self.sort_descending = state.process_sort_descending;
+        self.cgroup_sort = state.cgroup_sort;
+        self.cgroup_sort_descending = state.cgroup_sort_descending;
+        self.selected_panel = state.selected_panel;
+        self.focused_panel = state.focused_panel;
+    }
+
+    /// Update the Fleet tab; `active` is the host whose snapshot `render` gets
+    pub fn set_hosts(&mut self, hosts: Vec<HostStatus>, active: usize) {
+        self.hosts = hosts;
+        self.active_host = active;
+        self.selected_host = self.selected_host.min(self.hosts.len().saturating_sub(1));
+    }
+
+    /// Whether other hosts' snapshots are on screen, so their updates need a redraw
+    pub fn shows_fleet(&self) -> bool {
+        self.current_tab == TabIndex::Fleet && self.palette.is_none()
+    }
+
+    pub fn render(&mut self, f: &mut Frame, snapshot: &SystemSnapshot) {
         let size = f.size();
 
         // Create main layout
@@ -66,28 +298,46 @@ impl Dashboard {
 
         // Render main content based on current tab
         match self.current_tab {
-            TabIndex::Overview => self.render_overview(f, chunks[1], monitor),
-            TabIndex::Processes => self.render_processes(f, chunks[1], monitor),
-            TabIndex::Network => self.render_network(f, chunks[1], monitor),
+            TabIndex::Overview => self.render_overview(f, chunks[1], snapshot),
+            TabIndex::Processes => self.render_processes(f, chunks[1], snapshot),
+            TabIndex::Network => self.render_network(f, chunks[1], snapshot),
+            TabIndex::Cgroups => self.render_cgroups(f, chunks[1], snapshot),
+            TabIndex::Fleet => self.render_fleet(f, chunks[1]),
+            TabIndex::Settings => self.settings_editor.render(&self.settings, chunks[1], f.buffer_mut()),
             TabIndex::Help => self.render_help(f, chunks[1]),
         }
 
         // Render status bar
-        self.render_status_bar(f, chunks[2]);
+        self.render_status_bar(f, chunks[2], snapshot);
+
+        // Command palette floats above whatever tab is showing
+        if let Some(palette) = &self.palette {
+            palette.render(&self.keymap, chunks[1], f.buffer_mut());
+        }
     }
 
-    fn render_tabs(&self, f: &mut Frame, area: Rect) {
-        let tab_titles = vec![
-            "1. Overview",
-            "2. Processes", 
-            "3. Network",
-            "4. Help"
-        ];
+    fn render_tabs(&mut self, f: &mut Frame, area: Rect) {
+        let tab_titles: Vec<String> = TabIndex::ALL
+            .iter()
+            .enumerate()
+            .map(|(index, tab)| format!("{}. {}", index + 1, tab.name()))
+            .collect();
 
-        let tabs = Tabs::new(tab_titles)
+        let mut title = format!(" {} ", self.settings.dashboard.title);
+        if let Some(host) = self.hosts.get(self.active_host).filter(|host| host.remote) {
+            title.push_str(&format!("@ {} ", host.name));
+            if !host.online {
+                title.push_str("[OFFLINE] ");
+            }
+        }
+        if self.paused {
+            title.push_str("[PAUSED] ");
+        }
+
+        let tabs = Tabs::new(tab_titles.clone())
             .block(
                 Block::default()
-                    .title(format!(" {} ", self.settings.dashboard.title))
+                    .title(title)
                     .borders(Borders::ALL)
                     .border_style(Style::default().fg(Color::White)),
             )
@@ -100,9 +350,27 @@ impl Dashboard {
             .select(self.current_tab.clone() as usize);
 
         f.render_widget(tabs, area);
+
+        // Mirror the Tabs layout: " title " separated by a one-column divider
+        let mut x = area.x + 1;
+        self.tab_areas = tab_titles
+            .iter()
+            .map(|title| {
+                let width = title.chars().count() as u16 + 2;
+                let tab_area = Rect { x, y: area.y + 1, width, height: 1 };
+                x += width + 1;
+                tab_area
+            })
+            .collect();
     }
 
-    fn render_overview(&self, f: &mut Frame, area: Rect, monitor: &SystemMonitor) {
+    fn render_overview(&mut self, f: &mut Frame, area: Rect, snapshot: &SystemSnapshot) {
+        if let Some(panel) = self.focused_panel {
+            self.panel_areas.clear();
+            self.render_focused_panel(f, area, snapshot, panel);
+            return;
+        }
+
         // Create layout for overview
         let main_chunks = Layout::default()
             .direction(Direction::Vertical)
@@ -113,14 +381,20 @@ impl Dashboard {
             ])
             .split(area);
 
-        // Top row: CPU and Memory gauges
+        // Top row: CPU, Memory and Swap gauges
         let gauge_chunks = Layout::default()
             .direction(Direction::Horizontal)
-            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .constraints([
+                Constraint::Percentage(50),
+                Constraint::Percentage(30),
+                Constraint::Percentage(20),
+            ])
             .split(main_chunks[0]);
 
-        CpuWidget::render(monitor, gauge_chunks[0], f.buffer_mut());
-        MemoryWidget::render(monitor, gauge_chunks[1], f.buffer_mut());
+        let container = self.container_view(snapshot);
+        CpuWidget::render(snapshot, container, gauge_chunks[0], f.buffer_mut());
+        MemoryWidget::render(snapshot, container, gauge_chunks[1], f.buffer_mut());
+        MemoryWidget::render_swap(snapshot, gauge_chunks[2], f.buffer_mut());
 
         // Middle row: CPU and Memory history charts
         let chart_chunks = Layout::default()
@@ -128,20 +402,67 @@ impl Dashboard {
             .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
             .split(main_chunks[1]);
 
-        CpuWidget::render_history_chart(monitor, chart_chunks[0], f.buffer_mut());
-        MemoryWidget::render_history_chart(monitor, chart_chunks[1], f.buffer_mut());
+        CpuWidget::render_history_chart(snapshot, chart_chunks[0], f.buffer_mut());
+        MemoryWidget::render_history_chart(snapshot, chart_chunks[1], f.buffer_mut());
 
-        // Bottom row: System info and disk usage
+        // Bottom row: System info, pressure and disk usage
         let bottom_chunks = Layout::default()
             .direction(Direction::Horizontal)
-            .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+            .constraints([
+                Constraint::Percentage(25),
+                Constraint::Length(37),
+                Constraint::Min(0),
+            ])
             .split(main_chunks[2]);
 
-        SystemInfoWidget::render(monitor, bottom_chunks[0], f.buffer_mut());
-        DiskWidget::render(monitor, bottom_chunks[1], f.buffer_mut());
+        SystemInfoWidget::render(snapshot, self.settings.display.normalize_load, bottom_chunks[0], f.buffer_mut());
+        PressureWidget::render(snapshot, &self.settings.alerts, bottom_chunks[1], f.buffer_mut());
+        let disk_rows = bottom_chunks[2].height.saturating_sub(TABLE_FIRST_ROW_OFFSET + 1) as usize;
+        self.disk_scroll_offset = self.disk_scroll_offset.min(snapshot.disks.len().saturating_sub(disk_rows));
+        self.visible_disks = DiskWidget::render(
+            snapshot,
+            bottom_chunks[2],
+            f.buffer_mut(),
+            self.disk_scroll_offset,
+            self.selected_disk.as_deref(),
+        );
+        self.disk_table_area = bottom_chunks[2];
+
+        self.panel_areas = vec![
+            (Panel::CpuGauge, gauge_chunks[0]),
+            (Panel::MemoryGauge, gauge_chunks[1]),
+            (Panel::SwapGauge, gauge_chunks[2]),
+            (Panel::CpuChart, chart_chunks[0]),
+            (Panel::MemoryChart, chart_chunks[1]),
+            (Panel::SystemInfo, bottom_chunks[0]),
+            (Panel::Pressure, bottom_chunks[1]),
+            (Panel::Disks, bottom_chunks[2]),
+        ];
+
+        if let Some((_, selected_area)) = self
+            .panel_areas
+            .iter()
+            .find(|(panel, _)| *panel == self.selected_panel)
+        {
+            highlight_border(f, *selected_area);
+        }
+    }
+
+    fn render_focused_panel(&self, f: &mut Frame, area: Rect, snapshot: &SystemSnapshot, panel: Panel) {
+        match panel {
+            Panel::CpuGauge => CpuWidget::render_core_gauges(snapshot, area, f.buffer_mut()),
+            Panel::MemoryGauge | Panel::SwapGauge => MemoryWidget::render_detailed(snapshot, area, f.buffer_mut()),
+            Panel::CpuChart => CpuWidget::render_detailed_chart(snapshot, area, f.buffer_mut()),
+            Panel::MemoryChart => MemoryWidget::render_detailed_chart(snapshot, area, f.buffer_mut()),
+            Panel::SystemInfo => {
+                SystemInfoWidget::render_detailed(snapshot, self.settings.display.normalize_load, area, f.buffer_mut())
+            }
+            Panel::Pressure => PressureWidget::render_detailed(snapshot, &self.settings.alerts, area, f.buffer_mut()),
+            Panel::Disks => DiskWidget::render_detailed(snapshot, area, f.buffer_mut()),
+        }
     }
 
-    fn render_processes(&self, f: &mut Frame, area: Rect, monitor: &SystemMonitor) {
+    fn render_processes(&mut self, f: &mut Frame, area: Rect, snapshot: &SystemSnapshot) {
         // Create layout for processes
         let chunks = Layout::default()
             .direction(Direction::Vertical)
@@ -157,14 +478,89 @@ impl Dashboard {
             .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
             .split(chunks[0]);
 
-        CpuWidget::render(monitor, summary_chunks[0], f.buffer_mut());
-        MemoryWidget::render(monitor, summary_chunks[1], f.buffer_mut());
+        let container = self.container_view(snapshot);
+        CpuWidget::render(snapshot, container, summary_chunks[0], f.buffer_mut());
+        MemoryWidget::render(snapshot, container, summary_chunks[1], f.buffer_mut());
 
         // Bottom: Process list
-        ProcessWidget::render(monitor, chunks[1], f.buffer_mut(), self.process_scroll_offset);
+        self.render_process_list(f, chunks[1], snapshot.processes.iter().collect(), "Processes");
+    }
+
+    fn render_process_list(&mut self, f: &mut Frame, area: Rect, processes: Vec<&ProcessInfo>, title: &str) {
+        self.process_rows = area.height.saturating_sub(2) as usize; // Account for border
+        self.process_scroll_offset = self.process_scroll_offset.min(processes.len().saturating_sub(self.process_rows));
+        self.selected_process_name = self.selected_pid.and_then(|pid| {
+            processes.iter().find(|process| process.pid == pid).map(|process| process.name.clone())
+        });
+        self.process_order = ProcessWidget::render(
+            processes,
+            title,
+            area,
+            f.buffer_mut(),
+            self.process_scroll_offset,
+            self.selected_pid,
+            (self.process_sort, self.sort_descending),
+        );
+        self.process_list_area = area;
+    }
+
+    fn render_cgroups(&mut self, f: &mut Frame, area: Rect, snapshot: &SystemSnapshot) {
+        // A group that disappeared since it was opened falls back to the list
+        let open_group = self
+            .open_cgroup
+            .as_ref()
+            .and_then(|path| snapshot.cgroups.iter().find(|group| &group.path == path));
+
+        if let Some(group) = open_group {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(3), Constraint::Min(0)])
+                .split(area);
+
+            CgroupWidget::render_summary(group, chunks[0], f.buffer_mut());
+            let members = snapshot
+                .processes
+                .iter()
+                .filter(|process| process.cgroup.as_deref().is_some_and(|path| group.contains(path)))
+                .collect();
+            self.render_process_list(f, chunks[1], members, &group.path);
+            return;
+        }
+        self.open_cgroup = None;
+
+        self.cgroup_rows = area.height.saturating_sub(TABLE_FIRST_ROW_OFFSET + 1) as usize;
+        self.cgroup_scroll_offset = self
+            .cgroup_scroll_offset
+            .min(snapshot.cgroups.len().saturating_sub(self.cgroup_rows));
+        self.cgroup_order = CgroupWidget::render(
+            snapshot,
+            area,
+            f.buffer_mut(),
+            self.cgroup_scroll_offset,
+            self.selected_cgroup.as_deref(),
+            (self.cgroup_sort, self.cgroup_sort_descending),
+        );
+        self.cgroup_table_area = area;
     }
 
-    fn render_network(&self, f: &mut Frame, area: Rect, monitor: &SystemMonitor) {
+    fn render_fleet(&mut self, f: &mut Frame, area: Rect) {
+        self.fleet_rows = area.height.saturating_sub(TABLE_FIRST_ROW_OFFSET + 1) as usize;
+        self.fleet_scroll_offset = self
+            .fleet_scroll_offset
+            .min(self.hosts.len().saturating_sub(self.fleet_rows));
+        FleetWidget::render(
+            &self.hosts,
+            self.active_host,
+            self.selected_host,
+            self.fleet_scroll_offset,
+            (&self.settings.alerts, self.settings.display.normalize_load),
+            area,
+            f.buffer_mut(),
+        );
+        self.fleet_table_area = area;
+    }
+
+    fn render_network(&self, f: &mut Frame, area: Rect, snapshot: &SystemSnapshot) {
         // Create layout for network
         let chunks = Layout::default()
             .direction(Direction::Vertical)
@@ -184,48 +580,53 @@ impl Dashboard {
             ])
             .split(chunks[0]);
 
-        CpuWidget::render(monitor, summary_chunks[0], f.buffer_mut());
-        MemoryWidget::render(monitor, summary_chunks[1], f.buffer_mut());
-        SystemInfoWidget::render(monitor, summary_chunks[2], f.buffer_mut());
+        let container = self.container_view(snapshot);
+        CpuWidget::render(snapshot, container, summary_chunks[0], f.buffer_mut());
+        MemoryWidget::render(snapshot, container, summary_chunks[1], f.buffer_mut());
+        SystemInfoWidget::render(snapshot, self.settings.display.normalize_load, summary_chunks[2], f.buffer_mut());
 
         // Bottom: Network information
-        NetworkWidget::render(monitor, chunks[1], f.buffer_mut());
+        NetworkWidget::render(snapshot, chunks[1], f.buffer_mut());
     }
 
     fn render_help(&self, f: &mut Frame, area: Rect) {
-        let help_text = vec![
+        let heading = |text: &'static str| {
+            Line::from(vec![
+                Span::styled(text, Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+            ])
+        };
+
+        let mut help_text = vec![
             Line::from(""),
             Line::from(vec![
                 Span::styled("System Monitor Dashboard", 
                     Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
             ]),
             Line::from(""),
+            heading("Key Bindings:"),
+        ];
+
+        // Generated from the active keymap so presets and overrides show up here
+        for action in AppAction::ALL {
+            let keys = self.keymap.keys_for(&action);
+            let keys = if keys.is_empty() { "(unbound)".to_string() } else { keys.join(" / ") };
+            let key_color = if action == AppAction::Quit { Color::Red } else { Color::Green };
+            help_text.push(Line::from(vec![
+                Span::styled(format!("  {:<20}", keys), Style::default().fg(key_color)),
+                Span::raw(format!(" - {}", action.description())),
+            ]));
+        }
+
+        help_text.extend([
             Line::from(vec![
-                Span::styled("Navigation:", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
-            ]),
-            Line::from(vec![
-                Span::styled("  Tab / Shift+Tab", Style::default().fg(Color::Green)),
-                Span::raw("  - Switch between tabs (with 150ms delay for smooth navigation)"),
-            ]),
-            Line::from(vec![
-                Span::styled("  1, 2, 3, 4", Style::default().fg(Color::Green)),
-                Span::raw("       - Jump directly to Overview, Processes, Network, Help"),
-            ]),
-            Line::from(vec![
-                Span::styled("  ↑ / ↓", Style::default().fg(Color::Green)),
-                Span::raw("           - Scroll process list (in Processes tab)"),
-            ]),
-            Line::from(vec![
-                Span::styled("  r", Style::default().fg(Color::Green)),
-                Span::raw("               - Force refresh"),
+                Span::styled(format!("  {:<20}", "Mouse"), Style::default().fg(Color::Green)),
+                Span::raw(" - Click tabs, panels and rows, wheel-scroll process and disk tables"),
             ]),
             Line::from(""),
-            Line::from(vec![
-                Span::styled("Tabs:", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
-            ]),
+            heading("Tabs:"),
             Line::from(vec![
                 Span::styled("  1. Overview", Style::default().fg(Color::Green)),
-                Span::raw("     - CPU, Memory, Disk usage with live charts"),
+                Span::raw("     - CPU, Memory, Swap, Disk usage with live charts"),
             ]),
             Line::from(vec![
                 Span::styled("  2. Processes", Style::default().fg(Color::Green)),
@@ -236,30 +637,32 @@ impl Dashboard {
                 Span::raw("      - Network interface statistics"),
             ]),
             Line::from(vec![
-                Span::styled("  4. Help", Style::default().fg(Color::Green)),
-                Span::raw("         - This help screen"),
+                Span::styled("  4. Cgroups", Style::default().fg(Color::Green)),
+                Span::raw("      - CPU, memory, IO and pids per cgroup; Enter lists its processes"),
             ]),
-            Line::from(""),
             Line::from(vec![
-                Span::styled("Note:", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+                Span::styled("  5. Fleet", Style::default().fg(Color::Green)),
+                Span::raw("        - Every configured host at a glance; Enter shows it on the other tabs"),
             ]),
             Line::from(vec![
-                Span::raw("  Charts need ~30 seconds to build history data"),
+                Span::styled("  6. Settings", Style::default().fg(Color::Green)),
+                Span::raw("     - Every setting; Enter changes it live, save to keep it"),
             ]),
-            Line::from(""),
             Line::from(vec![
-                Span::styled("Exit:", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+                Span::styled("  7. Help", Style::default().fg(Color::Green)),
+                Span::raw("         - This help screen"),
             ]),
+            Line::from(""),
+            heading("Note:"),
             Line::from(vec![
-                Span::styled("  q / Esc / Ctrl+C", Style::default().fg(Color::Red)),
-                Span::raw("  - Quit application"),
+                Span::raw("  Charts need ~30 seconds to build history data"),
             ]),
             Line::from(""),
             Line::from(vec![
                 Span::styled("Built with ❤️  in Rust", 
                     Style::default().fg(Color::Magenta)),
             ]),
-        ];
+        ]);
 
         let help_paragraph = Paragraph::new(help_text)
             .block(
@@ -272,85 +675,755 @@ impl Dashboard {
         f.render_widget(help_paragraph, area);
     }
 
-    fn render_status_bar(&self, f: &mut Frame, area: Rect) {
-        let current_tab_name = match self.current_tab {
-            TabIndex::Overview => "Overview",
-            TabIndex::Processes => "Processes", 
-            TabIndex::Network => "Network",
-            TabIndex::Help => "Help",
-        };
+    fn render_status_bar(&mut self, f: &mut Frame, area: Rect, snapshot: &SystemSnapshot) {
+        if let Some((question, _)) = &self.confirm {
+            let status = Paragraph::new(format!("{} [y/N]", question))
+                .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
+            f.render_widget(status, area);
+            return;
+        }
+
+        if let Some((message, shown_at)) = &self.status_message {
+            if shown_at.elapsed() < STATUS_MESSAGE_TIMEOUT {
+                let status = Paragraph::new(message.clone())
+                    .style(Style::default().fg(Color::Yellow));
+                f.render_widget(status, area);
+                return;
+            }
+            self.status_message = None;
+        }
+
+        if let Some(host) = self.hosts.get(self.active_host).filter(|host| host.remote && !host.online) {
+            let status = Paragraph::new(format!("Lost connection to {}; reconnecting…", host.name))
+                .style(Style::default().fg(Color::Red).add_modifier(Modifier::BOLD));
+            f.render_widget(status, area);
+            return;
+        }
+
+        // Threshold alerts outrank the key hints
+        let alerts = evaluate_alerts(snapshot, &self.settings.alerts);
+        if let Some(alert) = alerts.first() {
+            let color = match alert.level {
+                AlertLevel::Critical => Color::Red,
+                AlertLevel::Warning => Color::Yellow,
+            };
+            let more = match alerts.len() {
+                1 => String::new(),
+                count => format!(" (+{} more)", count - 1),
+            };
+            let status = Paragraph::new(format!("⚠ {}{}", alert.message, more))
+                .style(Style::default().fg(color).add_modifier(Modifier::BOLD));
+            f.render_widget(status, area);
+            return;
+        }
+
+        let current_tab_name = self.current_tab.name();
+
+        let key = |action: AppAction| self.keymap.hint(&action);
+        let tabs_hint = format!(
+            "{}/{}-{}: Switch tabs",
+            key(AppAction::NextTab),
+            key(AppAction::GoToTab(0)),
+            key(AppAction::GoToTab(TabIndex::ALL.len() - 1))
+        );
 
-        let status_text = match self.current_tab {
-            TabIndex::Overview => "Tab/1-4: Switch tabs | r: Refresh | q: Quit",
-            TabIndex::Processes => "↑↓: Scroll | Tab/1-4: Switch tabs | r: Refresh | q: Quit",
-            TabIndex::Network => "Tab/1-4: Switch tabs | r: Refresh | q: Quit",
-            TabIndex::Help => "Tab/1-4: Switch tabs | q: Quit",
+        if self.palette.is_some() {
+            let status = Paragraph::new(format!("Current: {} › Command palette | Type to filter | Enter: Run | Esc: Close", current_tab_name))
+                .style(Style::default().fg(Color::Gray));
+            f.render_widget(status, area);
+            return;
+        }
+
+        if self.settings_editor.is_editing() {
+            let key = self.settings_editor.selected_field(&self.settings).map(|field| field.key).unwrap_or_default();
+            let status = Paragraph::new(format!("Current: {} › {} | Type a value | Enter: Apply | Esc: Cancel", current_tab_name, key))
+                .style(Style::default().fg(Color::Gray));
+            f.render_widget(status, area);
+            return;
+        }
+
+        let status_text = match (&self.current_tab, self.focused_panel) {
+            (TabIndex::Overview, Some(panel)) => {
+                format!("{} › {} | {}: Back to grid | {}: Quit", current_tab_name, panel.name(), key(AppAction::Back), key(AppAction::Quit))
+            }
+            (TabIndex::Overview, None) => format!(
+                "{} | {}{}: Select panel | {}: Maximize | {} | {}: Refresh | {}: Quit",
+                current_tab_name, key(AppAction::PrevPanel), key(AppAction::NextPanel), key(AppAction::FocusPanel),
+                tabs_hint, key(AppAction::Refresh), key(AppAction::Quit)
+            ),
+            (TabIndex::Processes, _) => format!(
+                "{} | {}{}: Select | {} | {}: Refresh | {}: Quit",
+                current_tab_name, key(AppAction::ScrollUp), key(AppAction::ScrollDown),
+                tabs_hint, key(AppAction::Refresh), key(AppAction::Quit)
+            ),
+            (TabIndex::Cgroups, _) => match &self.open_cgroup {
+                Some(path) => format!(
+                    "{} › {} | {}{}: Select | {}: Back to cgroups | {}: Quit",
+                    current_tab_name, path, key(AppAction::ScrollUp), key(AppAction::ScrollDown),
+                    key(AppAction::Back), key(AppAction::Quit)
+                ),
+                None => format!(
+                    "{} | {}{}: Select | {}: Processes | {}: Sort by IO | {} | {}: Quit",
+                    current_tab_name, key(AppAction::ScrollUp), key(AppAction::ScrollDown), key(AppAction::FocusPanel),
+                    key(AppAction::SortByIo), tabs_hint, key(AppAction::Quit)
+                ),
+            },
+            (TabIndex::Fleet, _) => format!(
+                "{} | {}{}: Select | {}: Show host | {} | {}: Quit",
+                current_tab_name, key(AppAction::ScrollUp), key(AppAction::ScrollDown), key(AppAction::FocusPanel),
+                tabs_hint, key(AppAction::Quit)
+            ),
+            (TabIndex::Network, _) => format!(
+                "{} | {} | {}: Refresh | {}: Quit",
+                current_tab_name, tabs_hint, key(AppAction::Refresh), key(AppAction::Quit)
+            ),
+            (TabIndex::Settings, _) => format!(
+                "{} | {}{}: Select | {}: Change | {}{}: Previous / next choice | {}: Save | {} | {}: Quit",
+                current_tab_name, key(AppAction::ScrollUp), key(AppAction::ScrollDown), key(AppAction::FocusPanel),
+                key(AppAction::PrevPanel), key(AppAction::NextPanel), key(AppAction::SaveSettings), tabs_hint,
+                key(AppAction::Quit)
+            ),
+            (TabIndex::Help, _) => format!("{} | {} | {}: Quit", current_tab_name, tabs_hint, key(AppAction::Quit)),
         };
 
-        let full_status = format!("Current: {} | {}", current_tab_name, status_text);
+        // Show a partially typed multi-key binding, like vim's showcmd
+        let mut full_status = match self.keymap.pending() {
+            Some(pending) => format!("Current: {} | Keys: {}", status_text, pending),
+            None => format!("Current: {}", status_text),
+        };
+        if self.paused {
+            full_status = format!("PAUSED ({}: resume) | {}", key(AppAction::TogglePause), full_status);
+        }
         let status = Paragraph::new(full_status)
             .style(Style::default().fg(Color::Gray));
 
         f.render_widget(status, area);
     }
 
-    pub fn handle_event(&mut self, event: Event) -> Result<bool> {
-        if should_quit(&event) {
-            return Ok(true); // Signal to quit
+    /// Apply an input event; anything that needs the collector or the OS is
+    /// returned for the main loop to carry out
+    pub fn handle_event(&mut self, event: Event) -> Result<Option<Command>> {
+        // A pending confirmation takes the next key press
+        if self.confirm.is_some() {
+            if let Event::Key(key_event) = event {
+                let confirmed = matches!(key_event.code, KeyCode::Char('y') | KeyCode::Char('Y'));
+                if let Some((_, command)) = self.confirm.take() {
+                    return Ok(confirmed.then_some(command));
+                }
+            }
+            return Ok(None);
         }
 
-        if let Event::Key(key_event) = event {
-            if let Some(action) = handle_key_event(key_event) {
-                match action {
-                    AppAction::Quit => return Ok(true),
-                    AppAction::NextTab => self.next_tab(),
-                    AppAction::PrevTab => self.prev_tab(),
-                    AppAction::GoToTab(index) => self.go_to_tab(index),
-                    AppAction::ScrollUp => self.scroll_up(),
-                    AppAction::ScrollDown => self.scroll_down(),
-                    AppAction::Refresh => {
-                        // Refresh will be handled by the main loop
+        // So does the value being typed on the Settings tab
+        if self.settings_editor.is_editing() {
+            if let Event::Key(key_event) = event {
+                return Ok(match self.settings_editor.handle_key(key_event) {
+                    InputOutcome::Submit(input) => self.submit_setting_input(&input),
+                    InputOutcome::Pending | InputOutcome::Cancelled => None,
+                });
+            }
+            return Ok(None);
+        }
+
+        // An open palette takes all keyboard input
+        if let Some(palette) = &mut self.palette {
+            if let Event::Key(key_event) = event {
+                match palette.handle_key(key_event) {
+                    PaletteOutcome::Pending => {}
+                    PaletteOutcome::Cancelled => self.palette = None,
+                    PaletteOutcome::Run(action) => {
+                        self.palette = None;
+                        return Ok(self.perform(action));
                     }
-                    AppAction::Help => {
-                        self.current_tab = TabIndex::Help;
+                }
+            }
+            return Ok(None);
+        }
+
+        if let Event::Mouse(mouse_event) = event {
+            return Ok(self.handle_mouse_event(mouse_event));
+        }
+
+        if let Event::Key(key_event) = event {
+            if let Some(action) = self.keymap.handle_key(key_event) {
+                return Ok(self.perform(action));
+            }
+        }
+
+        Ok(None) // Continue running
+    }
+
+    /// Carry out an action from a key binding or the palette
+    fn perform(&mut self, action: AppAction) -> Option<Command> {
+        match action {
+            AppAction::Quit => return Some(Command::Quit),
+            AppAction::NextPanel if self.current_tab == TabIndex::Settings => return self.step_selected_setting(true),
+            AppAction::PrevPanel if self.current_tab == TabIndex::Settings => return self.step_selected_setting(false),
+            AppAction::NextPanel => self.select_next_panel(),
+            AppAction::PrevPanel => self.select_prev_panel(),
+            AppAction::FocusPanel if self.current_tab == TabIndex::Fleet => return self.switch_to_selected_host(),
+            AppAction::FocusPanel if self.current_tab == TabIndex::Settings => return self.change_selected_setting(),
+            AppAction::FocusPanel => self.focus_selected_panel(),
+            AppAction::Back => {
+                let closed_cgroup = self.current_tab == TabIndex::Cgroups && self.open_cgroup.take().is_some();
+                if self.focused_panel.take().is_none() && !closed_cgroup {
+                    return Some(Command::Quit);
+                }
+            }
+            AppAction::NextTab => self.next_tab(),
+            AppAction::PrevTab => self.prev_tab(),
+            AppAction::GoToTab(index) => self.go_to_tab(index),
+            AppAction::ScrollUp => self.scroll_up(),
+            AppAction::ScrollDown => self.scroll_down(),
+            AppAction::ScrollTop => self.scroll_to(0),
+            AppAction::ScrollBottom => self.scroll_to(usize::MAX),
+            AppAction::SortBy(sort) if self.current_tab == TabIndex::Cgroups && self.open_cgroup.is_none() => {
+                self.sort_cgroups_by(match sort {
+                    ProcessSort::Cpu => CgroupSort::Cpu,
+                    ProcessSort::Memory => CgroupSort::Memory,
+                    ProcessSort::Pid => CgroupSort::Pids,
+                    ProcessSort::Name => CgroupSort::Name,
+                })
+            }
+            AppAction::SortBy(sort) => self.sort_processes_by(sort),
+            AppAction::SortByIo => self.sort_cgroups_by(CgroupSort::Io),
+            AppAction::KillProcess => self.confirm_kill_selected(),
+            AppAction::CommandPalette => self.palette = Some(CommandPalette::new()),
+            AppAction::Refresh => return Some(Command::Refresh),
+            AppAction::TogglePause => {
+                self.paused = !self.paused;
+                return Some(Command::SetPaused(self.paused));
+            }
+            AppAction::FasterRefresh => return self.scale_intervals(0.5),
+            AppAction::SlowerRefresh => return self.scale_intervals(2.0),
+            AppAction::ToggleLoadPerCore => {
+                let display = &mut self.settings.display;
+                display.normalize_load = !display.normalize_load;
+            }
+            AppAction::ToggleContainerView => self.toggle_container_view(),
+            AppAction::SaveSettings => self.confirm_save_settings(),
+            AppAction::Help => {
+                self.current_tab = TabIndex::Help;
+            }
+        }
+
+        None
+    }
+
+    fn scale_intervals(&mut self, factor: f64) -> Option<Command> {
+        // A remote host samples on the agent's schedule, which this dashboard
+        // cannot change
+        if self.hosts.get(self.active_host).is_some_and(|host| host.remote) {
+            self.show_message("Changing refresh intervals is not supported for remote hosts; set them in the agent's config");
+            return None;
+        }
+
+        let intervals = self.settings.system.intervals.scaled(factor);
+        self.show_message(format!(
+            "Refresh intervals: CPU {} ms, memory {} ms, processes {} ms",
+            intervals.cpu_ms, intervals.memory_ms, intervals.processes_ms
+        ));
+        self.settings.system.intervals = intervals;
+        Some(Command::SetSchedule(
+            self.settings
+                .system
+                .intervals
+                .schedule(self.settings.system.enable_process_monitoring),
+        ))
+    }
+
+    /// Our cgroup's usage when the gauges should show it instead of host totals
+    fn container_view<'a>(&self, snapshot: &'a SystemSnapshot) -> Option<&'a ContainerUsage> {
+        let container = snapshot.container.as_ref()?;
+        match self.settings.display.usage_view {
+            UsageView::Auto => container.has_limits().then_some(container),
+            UsageView::Host => None,
+            UsageView::Container => Some(container),
+        }
+    }
+
+    fn toggle_container_view(&mut self) {
+        let view = &mut self.settings.display.usage_view;
+        *view = match *view {
+            UsageView::Host => UsageView::Container,
+            UsageView::Auto | UsageView::Container => UsageView::Host,
+        };
+        let shown = match *view {
+            UsageView::Host => "host totals",
+            _ => "container usage against cgroup limits (host totals outside cgroup v2)",
+        };
+        self.show_message(format!("Gauges show {}", shown));
+    }
+
+    /// Ask before terminating the process selected in the Processes tab
+    fn confirm_kill_selected(&mut self) {
+        let Some(pid) = self.selected_pid else {
+            self.show_message("No process selected — pick one in the Processes tab with the arrow keys first");
+            return;
+        };
+        let name = self.selected_process_name.clone().unwrap_or_else(|| "?".to_string());
+        self.confirm = Some((
+            format!("Send SIGTERM to {} (PID {})?", name, pid),
+            Command::KillProcess { pid, name },
+        ));
+    }
+
+    /// Flip a toggle, step a choice, or open the input for a number or text
+    fn change_selected_setting(&mut self) -> Option<Command> {
+        let field = self.settings_editor.selected_field(&self.settings)?;
+        match field.kind {
+            FieldKind::Number | FieldKind::Text => {
+                self.settings_editor.start_input(&field);
+                None
+            }
+            FieldKind::ReadOnly => {
+                self.show_message(format!("{} can only be changed in the config file", field.key));
+                None
+            }
+            FieldKind::Toggle | FieldKind::Choice(_) => self.step_selected_setting(true),
+        }
+    }
+
+    fn step_selected_setting(&mut self, forward: bool) -> Option<Command> {
+        let field = self.settings_editor.selected_field(&self.settings)?;
+        let value = field.step(forward)?;
+        self.apply_setting(&field.key, value)
+    }
+
+    /// Apply the typed value; on a mistake the input stays open to fix it
+    fn submit_setting_input(&mut self, input: &str) -> Option<Command> {
+        let field = self.settings_editor.selected_field(&self.settings)?;
+        let value = match field.parse_input(input) {
+            Ok(value) => value,
+            Err(message) => {
+                self.show_message(message);
+                return None;
+            }
+        };
+        if value == field.value {
+            self.settings_editor.finish_input();
+            return None;
+        }
+        let command = self.apply_setting(&field.key, value);
+        if command.is_some() {
+            self.settings_editor.finish_input();
+        }
+        command
+    }
+
+    /// Check one changed setting as a config file would be and hand the
+    /// result to the main loop to apply; a rejected value only gets a message
+    fn apply_setting(&mut self, key: &str, value: toml::Value) -> Option<Command> {
+        let shown = value.to_string();
+        match self.settings.with_value(key, value) {
+            Ok(settings) => {
+                self.settings_editor.mark_changed(key);
+                let mut message = format!(
+                    "{} = {}, not saved yet ({}: save to the config file)",
+                    key, shown, self.keymap.hint(&AppAction::SaveSettings)
+                );
+                if key.starts_with("agent.") || key.starts_with("fleet.") {
+                    message.push_str("; takes effect after a restart");
+                }
+                self.show_message(message);
+                Some(Command::ApplySettings(Box::new(settings)))
+            }
+            Err(problems) => {
+                let more = match problems.len() {
+                    0 | 1 => String::new(),
+                    count => format!(" (and {} more)", count - 1),
+                };
+                if let Some(problem) = problems.first() {
+                    self.show_message(format!("Not applied: {}: {}{}", problem.key, problem.message, more));
+                }
+                None
+            }
+        }
+    }
+
+    /// Ask before writing the Settings tab's changes to the config file
+    fn confirm_save_settings(&mut self) {
+        let changed = self.settings_editor.changed().to_vec();
+        let question = match changed.as_slice() {
+            [] => {
+                self.show_message("No unsaved changes — change settings on the Settings tab first");
+                return;
+            }
+            [key] => format!("Save {} to the config file?", key),
+            keys => format!("Save {} changed settings to the config file?", keys.len()),
+        };
+        self.confirm = Some((question, Command::SaveSettings(changed)));
+    }
+
+    /// Choosing the current sort column again flips its direction
+    fn sort_processes_by(&mut self, sort: ProcessSort) {
+        if self.process_sort == sort {
+            self.sort_descending = !self.sort_descending;
+        } else {
+            self.process_sort = sort;
+            self.sort_descending = sort.descending_by_default();
+        }
+        self.process_scroll_offset = 0;
+    }
+
+    fn sort_cgroups_by(&mut self, sort: CgroupSort) {
+        if self.cgroup_sort == sort {
+            self.cgroup_sort_descending = !self.cgroup_sort_descending;
+        } else {
+            self.cgroup_sort = sort;
+            self.cgroup_sort_descending = sort.descending_by_default();
+        }
+        self.cgroup_scroll_offset = 0;
+    }
+
+    /// Move the cgroup cursor to `index` in last frame's order (clamped) and
+    /// scroll just enough to keep it on screen
+    fn select_cgroup_index(&mut self, index: usize) {
+        let Some(last) = self.cgroup_order.len().checked_sub(1) else {
+            return;
+        };
+        let index = index.min(last);
+        self.selected_cgroup = Some(self.cgroup_order[index].clone());
+
+        if index < self.cgroup_scroll_offset {
+            self.cgroup_scroll_offset = index;
+        } else if index >= self.cgroup_scroll_offset + self.cgroup_rows {
+            self.cgroup_scroll_offset = index + 1 - self.cgroup_rows.max(1);
+        }
+    }
+
+    /// Move the process cursor to `index` in last frame's order (clamped)
+    /// and scroll just enough to keep it on screen
+    fn select_process_index(&mut self, index: usize) {
+        let Some(last) = self.process_order.len().checked_sub(1) else {
+            return;
+        };
+        let index = index.min(last);
+        self.selected_pid = Some(self.process_order[index]);
+
+        if index < self.process_scroll_offset {
+            self.process_scroll_offset = index;
+        } else if index >= self.process_scroll_offset + self.process_rows {
+            self.process_scroll_offset = index + 1 - self.process_rows.max(1);
+        }
+    }
+
+    fn selected_process_index(&self) -> Option<usize> {
+        let selected = self.selected_pid?;
+        self.process_order.iter().position(|pid| *pid == selected)
+    }
+
+    fn selected_cgroup_index(&self) -> Option<usize> {
+        let selected = self.selected_cgroup.as_ref()?;
+        self.cgroup_order.iter().position(|path| path == selected)
+    }
+
+    fn open_selected_cgroup(&mut self) {
+        match &self.selected_cgroup {
+            Some(path) => {
+                self.open_cgroup = Some(path.clone());
+                self.process_scroll_offset = 0;
+            }
+            None => self.show_message("No cgroup selected — move the cursor with the arrow keys first"),
+        }
+    }
+
+    fn select_cgroup_at(&mut self, column: u16, row: u16) {
+        let first_row = self.cgroup_table_area.y + TABLE_FIRST_ROW_OFFSET;
+        if !contains(self.cgroup_table_area, column, row) || row < first_row {
+            return;
+        }
+
+        let index = self.cgroup_scroll_offset + (row - first_row) as usize;
+        if let Some(path) = self.cgroup_order.get(index).cloned() {
+            // Clicking the already selected group opens it
+            if self.selected_cgroup.as_ref() == Some(&path) {
+                self.open_selected_cgroup();
+            }
+            self.selected_cgroup = Some(path);
+        }
+    }
+
+    /// Move the Fleet cursor to `index` (clamped) and scroll just enough to
+    /// keep it on screen
+    fn select_host_index(&mut self, index: usize) {
+        let Some(last) = self.hosts.len().checked_sub(1) else {
+            return;
+        };
+        self.selected_host = index.min(last);
+
+        if self.selected_host < self.fleet_scroll_offset {
+            self.fleet_scroll_offset = self.selected_host;
+        } else if self.selected_host >= self.fleet_scroll_offset + self.fleet_rows {
+            self.fleet_scroll_offset = self.selected_host + 1 - self.fleet_rows.max(1);
+        }
+    }
+
+    /// Show the host under the Fleet cursor on every tab
+    fn switch_to_selected_host(&mut self) -> Option<Command> {
+        if self.selected_host >= self.hosts.len() {
+            return None;
+        }
+
+        // Selections from the previous host mean nothing on this one
+        self.current_tab = TabIndex::Overview;
+        self.focused_panel = None;
+        self.selected_pid = None;
+        self.selected_disk = None;
+        self.selected_cgroup = None;
+        self.open_cgroup = None;
+        self.process_scroll_offset = 0;
+        self.disk_scroll_offset = 0;
+        self.cgroup_scroll_offset = 0;
+        // The main loop resumes the host being left
+        self.paused = false;
+        Some(Command::SwitchHost(self.selected_host))
+    }
+
+    fn select_host_at(&mut self, column: u16, row: u16) -> Option<Command> {
+        let first_row = self.fleet_table_area.y + TABLE_FIRST_ROW_OFFSET;
+        if !contains(self.fleet_table_area, column, row) || row < first_row {
+            return None;
+        }
+
+        let index = self.fleet_scroll_offset + (row - first_row) as usize;
+        if index >= self.hosts.len() {
+            return None;
+        }
+        // Clicking the already selected host switches to it
+        if index == self.selected_host {
+            return self.switch_to_selected_host();
+        }
+        self.selected_host = index;
+        None
+    }
+
+    fn handle_mouse_event(&mut self, mouse_event: MouseEvent) -> Option<Command> {
+        let (column, row) = (mouse_event.column, mouse_event.row);
+
+        match mouse_event.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                if let Some(index) = self.tab_areas.iter().position(|area| contains(*area, column, row)) {
+                    self.go_to_tab(index);
+                    return None;
+                }
+
+                match self.current_tab.clone() {
+                    TabIndex::Overview if !self.select_disk_at(column, row) => {
+                        self.select_panel_at(column, row);
                     }
+                    TabIndex::Processes => self.select_process_at(column, row),
+                    TabIndex::Cgroups if self.open_cgroup.is_some() => self.select_process_at(column, row),
+                    TabIndex::Cgroups => self.select_cgroup_at(column, row),
+                    TabIndex::Fleet => return self.select_host_at(column, row),
+                    TabIndex::Settings => return self.select_setting_at(column, row),
+                    _ => {}
                 }
             }
+            MouseEventKind::ScrollUp => self.scroll_at(column, row, false),
+            MouseEventKind::ScrollDown => self.scroll_at(column, row, true),
+            _ => {}
+        }
+        None
+    }
+
+    fn scroll_at(&mut self, column: u16, row: u16, down: bool) {
+        if self.current_tab == TabIndex::Settings {
+            let selected = self.settings_editor.selected();
+            let index = if down { selected + MOUSE_SCROLL_LINES } else { selected.saturating_sub(MOUSE_SCROLL_LINES) };
+            self.settings_editor.select(index, &self.settings);
+            return;
         }
 
-        Ok(false) // Continue running
+        let offset = match self.current_tab {
+            TabIndex::Processes if contains(self.process_list_area, column, row) => {
+                &mut self.process_scroll_offset
+            }
+            TabIndex::Cgroups if self.open_cgroup.is_some() && contains(self.process_list_area, column, row) => {
+                &mut self.process_scroll_offset
+            }
+            TabIndex::Cgroups if contains(self.cgroup_table_area, column, row) => &mut self.cgroup_scroll_offset,
+            TabIndex::Fleet if contains(self.fleet_table_area, column, row) => &mut self.fleet_scroll_offset,
+            TabIndex::Overview
+                if self.focused_panel.is_none() && contains(self.disk_table_area, column, row) =>
+            {
+                &mut self.disk_scroll_offset
+            }
+            _ => return,
+        };
+
+        *offset = if down {
+            *offset + MOUSE_SCROLL_LINES
+        } else {
+            offset.saturating_sub(MOUSE_SCROLL_LINES)
+        };
+    }
+
+    fn select_setting_at(&mut self, column: u16, row: u16) -> Option<Command> {
+        let index = self.settings_editor.field_at(column, row)?;
+        // Clicking the already selected setting changes it
+        if index == self.settings_editor.selected() {
+            return self.change_selected_setting();
+        }
+        self.settings_editor.select(index, &self.settings);
+        None
+    }
+
+    fn select_process_at(&mut self, column: u16, row: u16) {
+        // Skip the top border of the list
+        if contains(self.process_list_area, column, row) && row > self.process_list_area.y {
+            let index = self.process_scroll_offset + (row - self.process_list_area.y - 1) as usize;
+            if let Some(pid) = self.process_order.get(index) {
+                self.selected_pid = Some(*pid);
+            }
+        }
+    }
+
+    /// Select the disk row under the cursor; returns whether a row was hit
+    fn select_disk_at(&mut self, column: u16, row: u16) -> bool {
+        let first_row = self.disk_table_area.y + TABLE_FIRST_ROW_OFFSET;
+        if self.focused_panel.is_some() || !contains(self.disk_table_area, column, row) || row < first_row {
+            return false;
+        }
+
+        match self.visible_disks.get((row - first_row) as usize) {
+            Some(mount_point) => {
+                self.selected_disk = Some(mount_point.clone());
+                self.selected_panel = Panel::Disks;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn select_next_panel(&mut self) {
+        if self.current_tab == TabIndex::Overview && self.focused_panel.is_none() {
+            self.selected_panel = self.selected_panel.next();
+        }
+    }
+
+    fn select_prev_panel(&mut self) {
+        if self.current_tab == TabIndex::Overview && self.focused_panel.is_none() {
+            self.selected_panel = self.selected_panel.prev();
+        }
+    }
+
+    fn focus_selected_panel(&mut self) {
+        match self.current_tab {
+            TabIndex::Overview => self.focused_panel = Some(self.selected_panel),
+            TabIndex::Cgroups if self.open_cgroup.is_none() => self.open_selected_cgroup(),
+            _ => {}
+        }
+    }
+
+    fn select_panel_at(&mut self, column: u16, row: u16) {
+        if self.current_tab != TabIndex::Overview {
+            return;
+        }
+
+        let clicked = self
+            .panel_areas
+            .iter()
+            .find(|(_, area)| contains(*area, column, row))
+            .map(|(panel, _)| *panel);
+
+        if let Some(panel) = clicked {
+            // Clicking the already selected panel maximizes it
+            if panel == self.selected_panel {
+                self.focused_panel = Some(panel);
+            }
+            self.selected_panel = panel;
+        }
     }
 
     fn next_tab(&mut self) {
         let current = self.current_tab.clone() as usize;
-        let next = (current + 1) % 4; // We have 4 tabs
+        let next = (current + 1) % TabIndex::ALL.len();
         self.current_tab = TabIndex::from(next);
         self.process_scroll_offset = 0; // Reset scroll when switching tabs
     }
 
     fn prev_tab(&mut self) {
         let current = self.current_tab.clone() as usize;
-        let prev = if current == 0 { 3 } else { current - 1 };
+        let prev = if current == 0 { TabIndex::ALL.len() - 1 } else { current - 1 };
         self.current_tab = TabIndex::from(prev);
         self.process_scroll_offset = 0; // Reset scroll when switching tabs
     }
 
     fn go_to_tab(&mut self, index: usize) {
-        if index < 4 {
+        if index < TabIndex::ALL.len() {
             self.current_tab = TabIndex::from(index);
             self.process_scroll_offset = 0; // Reset scroll when switching tabs
         }
     }
 
+    /// Jump the current tab's table to an offset; clamped when drawn
+    fn scroll_to(&mut self, offset: usize) {
+        match self.current_tab {
+            TabIndex::Processes => self.select_process_index(offset),
+            TabIndex::Cgroups if self.open_cgroup.is_some() => self.select_process_index(offset),
+            TabIndex::Cgroups => self.select_cgroup_index(offset),
+            TabIndex::Fleet => self.select_host_index(offset),
+            TabIndex::Settings => self.settings_editor.select(offset, &self.settings),
+            TabIndex::Overview if self.focused_panel.is_none() => self.disk_scroll_offset = offset,
+            _ => {}
+        }
+    }
+
     fn scroll_up(&mut self) {
-        if self.current_tab == TabIndex::Processes {
-            self.process_scroll_offset = self.process_scroll_offset.saturating_sub(1);
+        match self.current_tab {
+            TabIndex::Cgroups if self.open_cgroup.is_none() => {
+                let index = self.selected_cgroup_index().map_or(0, |index| index.saturating_sub(1));
+                self.select_cgroup_index(index);
+            }
+            TabIndex::Processes | TabIndex::Cgroups => {
+                let index = self.selected_process_index().map_or(self.process_scroll_offset, |index| index.saturating_sub(1));
+                self.select_process_index(index);
+            }
+            TabIndex::Fleet => self.select_host_index(self.selected_host.saturating_sub(1)),
+            TabIndex::Settings => {
+                let index = self.settings_editor.selected().saturating_sub(1);
+                self.settings_editor.select(index, &self.settings);
+            }
+            _ => {}
         }
     }
 
     fn scroll_down(&mut self) {
-        if self.current_tab == TabIndex::Processes {
-            self.process_scroll_offset += 1;
+        match self.current_tab {
+            TabIndex::Cgroups if self.open_cgroup.is_none() => {
+                let index = self.selected_cgroup_index().map_or(0, |index| index + 1);
+                self.select_cgroup_index(index);
+            }
+            TabIndex::Processes | TabIndex::Cgroups => {
+                let index = self.selected_process_index().map_or(self.process_scroll_offset, |index| index + 1);
+                self.select_process_index(index);
+            }
+            TabIndex::Fleet => self.select_host_index(self.selected_host + 1),
+            TabIndex::Settings => {
+                let index = self.settings_editor.selected() + 1;
+                self.settings_editor.select(index, &self.settings);
+            }
+            _ => {}
         }
     }
-}
\ No newline at end of file
+}
+
+fn contains(area: Rect, column: u16, row: u16) -> bool {
+    column >= area.x && column < area.x + area.width && row >= area.y && row < area.y + area.height
+}
+
+/// Restyle the border cells of `area` so the selected panel stands out
+/// without redrawing the widget that owns it
+fn highlight_border(f: &mut Frame, area: Rect) {
+    if area.width < 2 || area.height < 2 {
+        return;
+    }
+
+    let style = Style::default().fg(Color::LightYellow).add_modifier(Modifier::BOLD);
+    let buf = f.buffer_mut();
+    buf.set_style(Rect { height: 1, ..area }, style);
+    buf.set_style(Rect { y: area.y + area.height - 1, height: 1, ..area }, style);
+    buf.set_style(Rect { width: 1, ..area }, style);
+    buf.set_style(Rect { x: area.x + area.width - 1, width: 1, ..area }, style);
+}
@@ -1,43 +1,129 @@
-use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers, poll};
+use crossterm::event::{Event, EventStream, KeyCode, KeyEvent, KeyEventKind, MouseEventKind};
+use futures::future::select_all;
+use futures::StreamExt;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, watch};
+
+use crate::config::Settings;
+use crate::system::{CollectorKind, ProcessSort, SystemSnapshot};
+
+/// Everything the main loop reacts to, merged from the terminal and the collector
+#[derive(Debug, Clone)]
+pub enum AppEvent {
+    /// Keyboard or mouse input
+    Input(Event),
+    /// The terminal was resized; the next draw picks up the new size
+    Resize,
+    /// A host's collector published fresh system data (or its link changed);
+    /// `host` indexes the receivers the handler was created with
+    Snapshot { host: usize, snapshot: Arc<SystemSnapshot> },
+    /// The config file was saved and should be read again
+    ConfigChanged,
+    /// An outcome for the status bar: background errors, which logging
+    /// would draw over the screen, and results of spawned commands
+    Message(String),
+}
 
-#[derive(Debug)]
 pub struct EventHandler {
+    events: EventStream,
+    // One per host, with whether its sender is still alive
+    snapshots: Vec<(watch::Receiver<Arc<SystemSnapshot>>, bool)>,
+    config_changes: Option<mpsc::UnboundedReceiver<()>>,
+    // Outcomes of work spawned by the main loop; the sender is handed out
+    messages: (mpsc::UnboundedSender<String>, mpsc::UnboundedReceiver<String>),
     last_key_time: Option<Instant>,
     key_debounce_ms: u64,
 }
 
 impl EventHandler {
-    pub fn new() -> Self {
+    pub fn new(snapshots: Vec<watch::Receiver<Arc<SystemSnapshot>>>) -> Self {
         Self {
+            events: EventStream::new(),
+            snapshots: snapshots.into_iter().map(|receiver| (receiver, true)).collect(),
+            config_changes: None,
+            messages: mpsc::unbounded_channel(),
             last_key_time: None,
             key_debounce_ms: 150, // 150ms debounce for tab switching
         }
     }
 
-    pub async fn next_event(&mut self) -> Option<Event> {
-        // Use a longer timeout to prevent rapid key repeats
-        if poll(Duration::from_millis(100)).unwrap_or(false) {
-            match event::read() {
-                Ok(event) => {
-                    // Apply debouncing for certain keys
-                    if let Event::Key(key_event) = &event {
-                        if self.should_debounce_key(key_event) {
-                            let now = Instant::now();
-                            if let Some(last_time) = self.last_key_time {
-                                if now.duration_since(last_time).as_millis() < self.key_debounce_ms as u128 {
-                                    return None; // Ignore this key press (too soon)
-                                }
-                            }
-                            self.last_key_time = Some(now);
+    /// Also report changes to the config file, from `config::watcher::watch`
+    pub fn watch_config(&mut self, changes: mpsc::UnboundedReceiver<()>) {
+        self.config_changes = Some(changes);
+    }
+
+    /// A sender whose messages come back as `AppEvent::Message`, for tasks
+    /// spawned off the main loop
+    pub fn messages(&self) -> mpsc::UnboundedSender<String> {
+        self.messages.0.clone()
+    }
+
+    /// Wait for the next input, resize, snapshot or config change without blocking the runtime.
+    /// Returns None once the terminal input stream has closed.
+    pub async fn next_event(&mut self) -> Option<AppEvent> {
+        loop {
+            tokio::select! {
+                maybe_event = self.events.next() => match maybe_event {
+                    Some(Ok(Event::Resize(_, _))) => return Some(AppEvent::Resize),
+                    Some(Ok(event)) => {
+                        if self.should_forward(&event) {
+                            return Some(AppEvent::Input(event));
+                        }
+                    }
+                    Some(Err(err)) => return Some(AppEvent::Message(format!("Failed to read terminal event: {}", err))),
+                    None => return None,
+                },
+                (host, changed) = next_change(&mut self.snapshots) => {
+                    let (receiver, running) = &mut self.snapshots[host];
+                    match changed {
+                        Ok(()) => {
+                            let snapshot = receiver.borrow_and_update().clone();
+                            return Some(AppEvent::Snapshot { host, snapshot });
+                        }
+                        // Collector is gone; keep serving input with the last snapshot
+                        Err(_) => {
+                            *running = false;
+                            return Some(AppEvent::Message(
+                                "System collector stopped; data will no longer update".to_string(),
+                            ));
+                        }
+                    }
+                }
+                changed = next_config_change(&mut self.config_changes) => match changed {
+                    Some(()) => return Some(AppEvent::ConfigChanged),
+                    None => self.config_changes = None,
+                },
+                // Never closes, since the handler keeps a sender
+                Some(message) = self.messages.1.recv() => return Some(AppEvent::Message(message)),
+            }
+        }
+    }
+
+    /// Drop input that cannot change what is on screen: key releases and
+    /// repeats on platforms that report them, bare mouse motion, and tab
+    /// keys arriving faster than the debounce window
+    fn should_forward(&mut self, event: &Event) -> bool {
+        match event {
+            Event::Key(key_event) => {
+                if key_event.kind == KeyEventKind::Release {
+                    return false;
+                }
+
+                if self.should_debounce_key(key_event) {
+                    let now = Instant::now();
+                    if let Some(last_time) = self.last_key_time {
+                        if now.duration_since(last_time).as_millis() < self.key_debounce_ms as u128 {
+                            return false; // Ignore this key press (too soon)
                         }
                     }
-                    Some(event)
+                    self.last_key_time = Some(now);
                 }
-                Err(_) => None,
+                true
             }
-        } else {
-            None
+            Event::Mouse(mouse_event) => !matches!(mouse_event.kind, MouseEventKind::Moved),
+            Event::FocusGained | Event::FocusLost => false,
+            _ => true,
         }
     }
 
@@ -50,51 +136,27 @@ impl EventHandler {
     }
 }
 
-// Helper functions for handling specific events
-pub fn should_quit(event: &Event) -> bool {
-    matches!(event,
-        Event::Key(KeyEvent {
-            code: KeyCode::Char('q'),
-            modifiers: KeyModifiers::NONE,
-            ..
-        }) | Event::Key(KeyEvent {
-            code: KeyCode::Char('c'),
-            modifiers: KeyModifiers::CONTROL,
-            ..
-        }) | Event::Key(KeyEvent {
-            code: KeyCode::Esc,
-            modifiers: KeyModifiers::NONE,
-            ..
-        })
-    )
+/// Wait for the first receiver with news; never resolves once every sender is gone
+async fn next_change(
+    snapshots: &mut [(watch::Receiver<Arc<SystemSnapshot>>, bool)],
+) -> (usize, Result<(), watch::error::RecvError>) {
+    let pending: Vec<_> = snapshots
+        .iter_mut()
+        .enumerate()
+        .filter(|(_, (_, running))| *running)
+        .map(|(host, (receiver, _))| Box::pin(async move { (host, receiver.changed().await) }))
+        .collect();
+    if pending.is_empty() {
+        return std::future::pending().await;
+    }
+    select_all(pending).await.0
 }
 
-pub fn handle_key_event(event: KeyEvent) -> Option<AppAction> {
-    match (event.code, event.modifiers) {
-        // Quit commands
-        (KeyCode::Char('q'), KeyModifiers::NONE) => Some(AppAction::Quit),
-        (KeyCode::Char('c'), KeyModifiers::CONTROL) => Some(AppAction::Quit),
-        (KeyCode::Esc, KeyModifiers::NONE) => Some(AppAction::Quit),
-        
-        // Tab navigation - only on key press, not release
-        (KeyCode::Tab, KeyModifiers::NONE) => Some(AppAction::NextTab),
-        (KeyCode::BackTab, KeyModifiers::SHIFT) => Some(AppAction::PrevTab),
-        
-        // Alternative navigation with numbers
-        (KeyCode::Char('1'), KeyModifiers::NONE) => Some(AppAction::GoToTab(0)),
-        (KeyCode::Char('2'), KeyModifiers::NONE) => Some(AppAction::GoToTab(1)),
-        (KeyCode::Char('3'), KeyModifiers::NONE) => Some(AppAction::GoToTab(2)),
-        (KeyCode::Char('4'), KeyModifiers::NONE) => Some(AppAction::GoToTab(3)),
-        
-        // Arrow key navigation (no debouncing for smoother scrolling)
-        (KeyCode::Up, KeyModifiers::NONE) => Some(AppAction::ScrollUp),
-        (KeyCode::Down, KeyModifiers::NONE) => Some(AppAction::ScrollDown),
-        
-        // Other commands
-        (KeyCode::Char('r'), KeyModifiers::NONE) => Some(AppAction::Refresh),
-        (KeyCode::Char('h'), KeyModifiers::NONE) => Some(AppAction::Help),
-        
-        _ => None,
+/// Wait for the config watcher; never resolves when nothing is watched
+async fn next_config_change(changes: &mut Option<mpsc::UnboundedReceiver<()>>) -> Option<()> {
+    match changes {
+        Some(changes) => changes.recv().await,
+        None => std::future::pending().await,
     }
 }
 
@@ -106,6 +168,147 @@ pub enum AppAction {
     GoToTab(usize),
     ScrollUp,
     ScrollDown,
+    ScrollTop,
+    ScrollBottom,
+    NextPanel,
+    PrevPanel,
+    FocusPanel,
+    Back,
+    SortBy(ProcessSort),
+    SortByIo,
+    KillProcess,
+    CommandPalette,
     Refresh,
+    TogglePause,
+    FasterRefresh,
+    SlowerRefresh,
+    ToggleLoadPerCore,
+    ToggleContainerView,
+    SaveSettings,
     Help,
-}
\ No newline at end of file
+}
+
+/// Work the dashboard cannot do itself, handed back to the main loop
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Quit,
+    /// Sample every collector now (also steps a paused collector)
+    Refresh,
+    /// Stop or restart sampling; the UI stays usable on the frozen snapshot
+    SetPaused(bool),
+    /// New per-collector intervals
+    SetSchedule(Vec<(CollectorKind, Duration)>),
+    /// Send SIGTERM to a process
+    KillProcess { pid: u32, name: String },
+    /// Show another host from the Fleet tab, by its position in the fleet
+    SwitchHost(usize),
+    /// Settings changed on the Settings tab, already validated
+    ApplySettings(Box<Settings>),
+    /// Write these dotted keys, as currently set, into the config file
+    SaveSettings(Vec<String>),
+}
+
+impl AppAction {
+    /// Every action that can be bound to keys, in the order the Help tab lists them
+    pub const ALL: [AppAction; 33] = [
+        AppAction::NextTab,
+        AppAction::PrevTab,
+        AppAction::GoToTab(0),
+        AppAction::GoToTab(1),
+        AppAction::GoToTab(2),
+        AppAction::GoToTab(3),
+        AppAction::GoToTab(4),
+        AppAction::GoToTab(5),
+        AppAction::GoToTab(6),
+        AppAction::ScrollUp,
+        AppAction::ScrollDown,
+        AppAction::ScrollTop,
+        AppAction::ScrollBottom,
+        AppAction::NextPanel,
+        AppAction::PrevPanel,
+        AppAction::FocusPanel,
+        AppAction::Back,
+        AppAction::SortBy(ProcessSort::Cpu),
+        AppAction::SortBy(ProcessSort::Memory),
+        AppAction::SortBy(ProcessSort::Pid),
+        AppAction::SortBy(ProcessSort::Name),
+        AppAction::SortByIo,
+        AppAction::KillProcess,
+        AppAction::CommandPalette,
+        AppAction::Refresh,
+        AppAction::TogglePause,
+        AppAction::FasterRefresh,
+        AppAction::SlowerRefresh,
+        AppAction::ToggleLoadPerCore,
+        AppAction::ToggleContainerView,
+        AppAction::SaveSettings,
+        AppAction::Help,
+        AppAction::Quit,
+    ];
+
+    /// The name used for this action in the `[keybindings]` config section
+    pub fn name(&self) -> String {
+        match self {
+            AppAction::Quit => "quit".to_string(),
+            AppAction::NextTab => "next_tab".to_string(),
+            AppAction::PrevTab => "prev_tab".to_string(),
+            AppAction::GoToTab(index) => format!("tab_{}", index + 1),
+            AppAction::ScrollUp => "scroll_up".to_string(),
+            AppAction::ScrollDown => "scroll_down".to_string(),
+            AppAction::ScrollTop => "scroll_top".to_string(),
+            AppAction::ScrollBottom => "scroll_bottom".to_string(),
+            AppAction::NextPanel => "next_panel".to_string(),
+            AppAction::PrevPanel => "prev_panel".to_string(),
+            AppAction::FocusPanel => "focus_panel".to_string(),
+            AppAction::Back => "back".to_string(),
+            AppAction::SortBy(sort) => format!("sort_{}", sort.name().to_lowercase()),
+            AppAction::SortByIo => "sort_io".to_string(),
+            AppAction::KillProcess => "kill_process".to_string(),
+            AppAction::CommandPalette => "command_palette".to_string(),
+            AppAction::Refresh => "refresh".to_string(),
+            AppAction::TogglePause => "toggle_pause".to_string(),
+            AppAction::FasterRefresh => "faster_refresh".to_string(),
+            AppAction::SlowerRefresh => "slower_refresh".to_string(),
+            AppAction::ToggleLoadPerCore => "toggle_load_per_core".to_string(),
+            AppAction::ToggleContainerView => "toggle_container_view".to_string(),
+            AppAction::SaveSettings => "save_settings".to_string(),
+            AppAction::Help => "help".to_string(),
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<AppAction> {
+        AppAction::ALL.into_iter().find(|action| action.name() == name)
+    }
+
+    pub fn description(&self) -> String {
+        match self {
+            AppAction::Quit => "Quit application".to_string(),
+            AppAction::NextTab => "Next tab".to_string(),
+            AppAction::PrevTab => "Previous tab".to_string(),
+            AppAction::GoToTab(index) => {
+                let names = ["Overview", "Processes", "Network", "Cgroups", "Fleet", "Settings", "Help"];
+                format!("Go to {} tab", names.get(*index).unwrap_or(&"?"))
+            }
+            AppAction::ScrollUp => "Scroll up".to_string(),
+            AppAction::ScrollDown => "Scroll down".to_string(),
+            AppAction::ScrollTop => "Scroll to top".to_string(),
+            AppAction::ScrollBottom => "Scroll to bottom".to_string(),
+            AppAction::NextPanel => "Select next panel (Overview)".to_string(),
+            AppAction::PrevPanel => "Select previous panel (Overview)".to_string(),
+            AppAction::FocusPanel => "Maximize selected panel / open selected cgroup or host".to_string(),
+            AppAction::Back => "Leave maximized panel or cgroup, or quit".to_string(),
+            AppAction::SortBy(sort) => format!("Sort processes or cgroups by {} (again to reverse)", sort.name()),
+            AppAction::SortByIo => "Sort cgroups by IO (again to reverse)".to_string(),
+            AppAction::KillProcess => "Terminate selected process (asks first)".to_string(),
+            AppAction::CommandPalette => "Open command palette".to_string(),
+            AppAction::TogglePause => "Pause / resume sampling".to_string(),
+            AppAction::FasterRefresh => "Halve refresh intervals".to_string(),
+            AppAction::SlowerRefresh => "Double refresh intervals".to_string(),
+            AppAction::ToggleLoadPerCore => "Show load average raw / per core".to_string(),
+            AppAction::ToggleContainerView => "Gauges: host totals / container limits".to_string(),
+            AppAction::SaveSettings => "Save Settings tab changes to the config file (asks first)".to_string(),
+            AppAction::Refresh => "Force refresh (steps while paused)".to_string(),
+            AppAction::Help => "Show this help".to_string(),
+        }
+    }
+}
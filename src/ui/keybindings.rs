@@ -0,0 +1,174 @@
+use anyhow::{anyhow, Result};
+use crossterm::event::KeyEvent;
+
+use crate::config::keys::{self, format_sequence, KeyChord, KeySequence};
+use crate::config::settings::KeybindingSettings;
+use super::events::AppAction;
+
+/// Resolved key bindings: key sequences to actions, plus the chords typed so
+/// far towards a multi-key sequence
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: Vec<(KeySequence, AppAction)>,
+    pending: KeySequence,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::from_settings(&KeybindingSettings::default())
+            .expect("built-in default keymap is valid")
+    }
+}
+
+impl Keymap {
+    /// Build the keymap for a preset plus per-action overrides, as checked
+    /// by `keys::resolve`
+    pub fn from_settings(settings: &KeybindingSettings) -> Result<Self> {
+        let bindings = keys::resolve(settings)?
+            .into_iter()
+            .map(|(sequence, name)| {
+                let action = AppAction::from_name(&name)
+                    .ok_or_else(|| anyhow!("keybindings.bindings.{}: unknown action \"{}\"", name, name))?;
+                Ok((sequence, action))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self {
+            bindings,
+            pending: Vec::new(),
+        })
+    }
+
+    /// Feed a key press; returns the action once a full sequence has been typed
+    pub fn handle_key(&mut self, event: KeyEvent) -> Option<AppAction> {
+        self.pending.push(KeyChord::from(event));
+
+        if let Some((_, action)) = self.bindings.iter().find(|(sequence, _)| *sequence == self.pending) {
+            let action = action.clone();
+            self.pending.clear();
+            return Some(action);
+        }
+
+        let is_prefix = self
+            .bindings
+            .iter()
+            .any(|(sequence, _)| sequence.len() > self.pending.len() && sequence.starts_with(&self.pending));
+        if is_prefix {
+            return None;
+        }
+
+        // Not going anywhere: drop what was pending and try this key on its own
+        if self.pending.len() > 1 {
+            self.pending.clear();
+            return self.handle_key(event);
+        }
+        self.pending.clear();
+        None
+    }
+
+    /// Chords typed so far towards a multi-key binding, for the status bar
+    pub fn pending(&self) -> Option<String> {
+        if self.pending.is_empty() {
+            None
+        } else {
+            Some(format_sequence(&self.pending))
+        }
+    }
+
+    /// Display strings for every sequence bound to `action`
+    pub fn keys_for(&self, action: &AppAction) -> Vec<String> {
+        self.bindings
+            .iter()
+            .filter(|(_, bound)| bound == action)
+            .map(|(sequence, _)| format_sequence(sequence))
+            .collect()
+    }
+
+    /// The first key bound to `action`, for compact hints
+    pub fn hint(&self, action: &AppAction) -> String {
+        self.keys_for(action).into_iter().next().unwrap_or_else(|| "unbound".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::settings::KeymapPreset;
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    fn char_key(c: char) -> KeyEvent {
+        key(KeyCode::Char(c), KeyModifiers::NONE)
+    }
+
+    fn keymap(preset: KeymapPreset, bindings: &[(&str, &[&str])]) -> Result<Keymap> {
+        Keymap::from_settings(&KeybindingSettings {
+            preset,
+            bindings: bindings
+                .iter()
+                .map(|(action, keys)| (action.to_string(), keys.iter().map(|key| key.to_string()).collect()))
+                .collect(),
+        })
+    }
+
+    #[test]
+    fn every_preset_builds() {
+        for preset in KeymapPreset::ALL {
+            let keymap = keymap(preset, &[]).unwrap_or_else(|err| panic!("{:?}: {:#}", preset, err));
+            for action in AppAction::ALL {
+                assert!(!keymap.keys_for(&action).is_empty(), "{:?} leaves {:?} unbound", preset, action);
+            }
+        }
+    }
+
+    #[test]
+    fn every_action_name_is_an_action() {
+        let names = keys::action_names();
+        assert_eq!(names.len(), AppAction::ALL.len());
+        for name in names {
+            assert!(AppAction::from_name(name).is_some(), "{} is not an action", name);
+        }
+    }
+
+    #[test]
+    fn overrides_replace_a_preset_action_keys() {
+        let overridden = keymap(KeymapPreset::Default, &[("scroll_down", &["j", "ctrl+n"])]).unwrap();
+        assert_eq!(overridden.keys_for(&AppAction::ScrollDown), ["j", "Ctrl+N"]);
+        assert!(keymap(KeymapPreset::Default, &[("launch", &["x"])]).is_err());
+    }
+
+    #[test]
+    fn multi_key_sequences_wait_for_the_rest() {
+        let mut keymap = keymap(KeymapPreset::Vim, &[]).unwrap();
+        assert_eq!(keymap.handle_key(char_key('g')), None);
+        assert_eq!(keymap.pending().as_deref(), Some("g"));
+        assert_eq!(keymap.handle_key(char_key('g')), Some(AppAction::ScrollTop));
+        assert_eq!(keymap.pending(), None);
+
+        assert_eq!(keymap.handle_key(char_key('g')), None);
+        assert_eq!(keymap.handle_key(key(KeyCode::Char('T'), KeyModifiers::SHIFT)), Some(AppAction::PrevTab));
+    }
+
+    #[test]
+    fn dead_prefix_falls_back_to_the_single_key() {
+        let mut keymap = keymap(KeymapPreset::Vim, &[]).unwrap();
+        assert_eq!(keymap.handle_key(char_key('g')), None);
+        assert_eq!(keymap.handle_key(char_key('j')), Some(AppAction::ScrollDown));
+        assert_eq!(keymap.pending(), None);
+
+        // An unbound key after the prefix just clears it
+        assert_eq!(keymap.handle_key(char_key('g')), None);
+        assert_eq!(keymap.handle_key(char_key('z')), None);
+        assert_eq!(keymap.pending(), None);
+    }
+
+    #[test]
+    fn emacs_chords_chain() {
+        let mut keymap = keymap(KeymapPreset::Emacs, &[]).unwrap();
+        assert_eq!(keymap.handle_key(key(KeyCode::Char('x'), KeyModifiers::CONTROL)), None);
+        assert_eq!(keymap.handle_key(key(KeyCode::Char('c'), KeyModifiers::CONTROL)), Some(AppAction::Quit));
+    }
+}
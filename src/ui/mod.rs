@@ -1,6 +1,12 @@
 pub mod dashboard;
 pub mod events;
+pub mod keybindings;
+pub mod palette;
+pub mod settings_editor;
+pub mod state;
 pub mod widgets;
 
 pub use dashboard::Dashboard;
-pub use events::EventHandler;
\ No newline at end of file
+pub use events::{AppEvent, Command, EventHandler};
+pub use keybindings::Keymap;
+pub use state::UiState;
\ No newline at end of file
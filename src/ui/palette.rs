@@ -0,0 +1,272 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, StatefulWidget, Widget},
+};
+
+use super::events::AppAction;
+use super::keybindings::Keymap;
+
+/// What the palette wants the dashboard to do after a key press
+#[derive(Debug, Clone, PartialEq)]
+pub enum PaletteOutcome {
+    /// Keep the palette open
+    Pending,
+    /// Close without running anything
+    Cancelled,
+    /// Close and run this action
+    Run(AppAction),
+}
+
+/// Fuzzy-searchable overlay listing every action with its current key binding
+#[derive(Debug, Clone, Default)]
+pub struct CommandPalette {
+    query: String,
+    selected: usize,
+}
+
+/// Score `candidate` against `query` as an in-order subsequence match.
+/// Consecutive matches and matches at the start of a word score higher;
+/// returns None when some query character is missing.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i32> {
+    let candidate: Vec<char> = candidate.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous_match: Option<usize> = None;
+
+    for wanted in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let found = (position..candidate.len()).find(|&i| candidate[i] == wanted)?;
+
+        score += 1;
+        if previous_match.is_some_and(|previous| previous + 1 == found) {
+            score += 5;
+        }
+        if found == 0 || matches!(candidate[found - 1], ' ' | '_' | '(') {
+            score += 3;
+        }
+
+        previous_match = Some(found);
+        position = found + 1;
+    }
+
+    Some(score)
+}
+
+impl CommandPalette {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Actions matching the current query, best first
+    pub fn matches(&self) -> Vec<AppAction> {
+        let mut scored: Vec<(i32, usize, AppAction)> = AppAction::ALL
+            .into_iter()
+            .filter(|action| *action != AppAction::CommandPalette)
+            .enumerate()
+            .filter_map(|(order, action)| {
+                // Search the description and the config name, whichever matches better
+                let score = fuzzy_score(&self.query, &action.description())
+                    .max(fuzzy_score(&self.query, &action.name()))?;
+                Some((score, order, action))
+            })
+            .collect();
+
+        scored.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+        scored.into_iter().map(|(_, _, action)| action).collect()
+    }
+
+    pub fn handle_key(&mut self, event: KeyEvent) -> PaletteOutcome {
+        let matches = self.matches();
+        let ctrl = event.modifiers.contains(KeyModifiers::CONTROL);
+
+        match event.code {
+            KeyCode::Esc => return PaletteOutcome::Cancelled,
+            KeyCode::Char('g') if ctrl => return PaletteOutcome::Cancelled,
+            KeyCode::Enter => {
+                return match matches.get(self.selected) {
+                    Some(action) => PaletteOutcome::Run(action.clone()),
+                    None => PaletteOutcome::Cancelled,
+                };
+            }
+            KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Char('p') if ctrl => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down | KeyCode::Tab => {
+                self.selected = (self.selected + 1).min(matches.len().saturating_sub(1));
+            }
+            KeyCode::Char('n') if ctrl => {
+                self.selected = (self.selected + 1).min(matches.len().saturating_sub(1));
+            }
+            KeyCode::Backspace => {
+                self.query.pop();
+                self.selected = 0;
+            }
+            KeyCode::Char('u') if ctrl => {
+                self.query.clear();
+                self.selected = 0;
+            }
+            KeyCode::Char(c) if !ctrl && !event.modifiers.contains(KeyModifiers::ALT) => {
+                self.query.push(c);
+                self.selected = 0;
+            }
+            _ => {}
+        }
+
+        PaletteOutcome::Pending
+    }
+
+    pub fn render(&self, keymap: &Keymap, area: Rect, buf: &mut Buffer) {
+        let width = area.width.saturating_sub(4).min(80);
+        let height = area.height.saturating_sub(4).min(20);
+        let popup = Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 3,
+            width,
+            height,
+        };
+
+        Clear.render(popup, buf);
+        let block = Block::default()
+            .title(" Command Palette ")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan));
+        let inner = block.inner(popup);
+        block.render(popup, buf);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(2), Constraint::Min(0)])
+            .split(inner);
+
+        Paragraph::new(vec![
+            Line::from(vec![
+                Span::styled("> ", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+                Span::raw(self.query.clone()),
+                Span::styled("█", Style::default().fg(Color::Gray)),
+            ]),
+            Line::from(Span::styled(
+                "↑↓ select · Enter run · Esc close",
+                Style::default().fg(Color::DarkGray),
+            )),
+        ])
+        .render(chunks[0], buf);
+
+        let key_width = 18;
+        let label_width = (chunks[1].width as usize).saturating_sub(key_width + 3);
+        let items: Vec<ListItem> = self
+            .matches()
+            .iter()
+            .map(|action| {
+                let keys = keymap.keys_for(action).join(" / ");
+                ListItem::new(Line::from(vec![
+                    Span::raw(format!(" {:<width$}", action.description(), width = label_width)),
+                    Span::styled(format!("{:>width$} ", keys, width = key_width), Style::default().fg(Color::Green)),
+                ]))
+            })
+            .collect();
+
+        let mut state = ListState::default().with_selected(Some(self.selected));
+        StatefulWidget::render(
+            List::new(items).highlight_style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD)),
+            chunks[1],
+            buf,
+            &mut state,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(palette: &mut CommandPalette, code: KeyCode) -> PaletteOutcome {
+        palette.handle_key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    fn type_query(palette: &mut CommandPalette, query: &str) {
+        for c in query.chars() {
+            press(palette, KeyCode::Char(c));
+        }
+    }
+
+    #[test]
+    fn matches_in_order_subsequences_ignoring_case_and_spaces() {
+        assert!(fuzzy_score("qt", "Quit application").is_some());
+        assert!(fuzzy_score("Q U I T", "quit").is_some());
+        assert_eq!(fuzzy_score("", "quit"), Some(0));
+    }
+
+    #[test]
+    fn missing_or_out_of_order_characters_do_not_match() {
+        assert_eq!(fuzzy_score("quiz", "quit"), None);
+        assert_eq!(fuzzy_score("tiuq", "quit"), None);
+        assert_eq!(fuzzy_score("quits", "quit"), None);
+    }
+
+    #[test]
+    fn word_starts_score_higher() {
+        // One point per character, three more at a word start
+        assert_eq!(fuzzy_score("t", "at"), Some(1));
+        assert_eq!(fuzzy_score("t", "a tab"), Some(4));
+        assert_eq!(fuzzy_score("t", "next_tab"), Some(1));
+        assert_eq!(fuzzy_score("t", "(tab"), Some(4));
+        assert_eq!(fuzzy_score("t", "tab"), Some(4));
+    }
+
+    #[test]
+    fn consecutive_characters_score_higher() {
+        assert_eq!(fuzzy_score("ab", "xaxb"), Some(2));
+        assert_eq!(fuzzy_score("ab", "xab"), Some(7));
+        assert!(fuzzy_score("sort", "sort by cpu") > fuzzy_score("sort", "scroll to top"));
+    }
+
+    #[test]
+    fn empty_query_lists_every_action_but_the_palette() {
+        let matches = CommandPalette::new().matches();
+        assert_eq!(matches.len(), AppAction::ALL.len() - 1);
+        assert!(!matches.contains(&AppAction::CommandPalette));
+        assert_eq!(matches[0], AppAction::ALL[0]);
+    }
+
+    #[test]
+    fn best_match_comes_first_and_runs_on_enter() {
+        let mut palette = CommandPalette::new();
+        type_query(&mut palette, "pause");
+        assert_eq!(palette.matches()[0], AppAction::TogglePause);
+        assert_eq!(press(&mut palette, KeyCode::Enter), PaletteOutcome::Run(AppAction::TogglePause));
+    }
+
+    #[test]
+    fn selection_stays_within_the_matches() {
+        let mut palette = CommandPalette::new();
+        type_query(&mut palette, "tab");
+        let count = palette.matches().len();
+        assert!(count > 1);
+
+        press(&mut palette, KeyCode::Up);
+        assert_eq!(palette.selected, 0);
+        for _ in 0..count + 5 {
+            press(&mut palette, KeyCode::Down);
+        }
+        assert_eq!(palette.selected, count - 1);
+        let last = palette.matches()[count - 1].clone();
+        assert_eq!(press(&mut palette, KeyCode::Enter), PaletteOutcome::Run(last));
+
+        // Editing the query starts over at the best match
+        press(&mut palette, KeyCode::Backspace);
+        assert_eq!(palette.selected, 0);
+    }
+
+    #[test]
+    fn nothing_runs_without_a_match() {
+        let mut palette = CommandPalette::new();
+        type_query(&mut palette, "zzzz");
+        assert!(palette.matches().is_empty());
+        press(&mut palette, KeyCode::Down);
+        assert_eq!(palette.selected, 0);
+        assert_eq!(press(&mut palette, KeyCode::Enter), PaletteOutcome::Cancelled);
+        assert_eq!(press(&mut palette, KeyCode::Esc), PaletteOutcome::Cancelled);
+    }
+}
@@ -0,0 +1,446 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Cell, Row, StatefulWidget, Table, TableState},
+};
+use serde::Serialize;
+use toml::Value;
+
+use crate::config::settings::{KeymapPreset, UsageView};
+use crate::config::Settings;
+use super::widgets::TABLE_FIRST_ROW_OFFSET;
+
+/// How a field on the Settings tab is changed
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldKind {
+    /// Enter flips it
+    Toggle,
+    /// Enter opens an input for a new number
+    Number,
+    /// Enter opens an input for new text
+    Text,
+    /// Enter steps through the allowed values
+    Choice(Vec<String>),
+    /// Lists, tables and the layout version; edit those in the file
+    ReadOnly,
+}
+
+/// One row of the Settings tab
+#[derive(Debug, Clone, PartialEq)]
+pub struct Field {
+    /// Dotted key, e.g. `system.intervals.cpu_ms`
+    pub key: String,
+    pub value: Value,
+    pub kind: FieldKind,
+}
+
+impl Field {
+    /// Top-level table the field belongs to; empty for `version`
+    pub fn section(&self) -> &str {
+        self.key.split_once('.').map_or("", |(section, _)| section)
+    }
+
+    /// The key within its section
+    pub fn label(&self) -> &str {
+        self.key.split_once('.').map_or(self.key.as_str(), |(_, rest)| rest)
+    }
+
+    /// The value as shown in the table
+    pub fn display(&self) -> String {
+        match &self.value {
+            Value::String(text) => text.clone(),
+            Value::Array(items) if items.is_empty() => "(none)".to_string(),
+            Value::Array(items) if items.iter().all(Value::is_table) => format!("{} entries", items.len()),
+            Value::Table(table) if table.is_empty() => "(none)".to_string(),
+            value => value.to_string(),
+        }
+    }
+
+    fn hint(&self) -> String {
+        match &self.kind {
+            FieldKind::Toggle => "true / false".to_string(),
+            FieldKind::Number => "number".to_string(),
+            FieldKind::Text => "text".to_string(),
+            FieldKind::Choice(choices) => choices.join(" / "),
+            FieldKind::ReadOnly => "edit in the config file".to_string(),
+        }
+    }
+
+    /// The value after one step of a toggle or choice, `forward` or back
+    pub fn step(&self, forward: bool) -> Option<Value> {
+        match (&self.kind, &self.value) {
+            (FieldKind::Toggle, Value::Boolean(on)) => Some(Value::Boolean(!on)),
+            (FieldKind::Choice(choices), Value::String(current)) => {
+                let index = choices.iter().position(|choice| choice == current).unwrap_or(0);
+                let next = if forward {
+                    (index + 1) % choices.len()
+                } else {
+                    (index + choices.len() - 1) % choices.len()
+                };
+                Some(Value::String(choices[next].clone()))
+            }
+            _ => None,
+        }
+    }
+
+    /// The value typed into the input; range checks are left to validation
+    pub fn parse_input(&self, input: &str) -> Result<Value, String> {
+        let input = input.trim();
+        match self.kind {
+            FieldKind::Number => input
+                .parse::<i64>()
+                .map(Value::Integer)
+                .or_else(|_| input.parse::<f64>().map(Value::Float))
+                .map_err(|_| format!("{}: \"{}\" is not a number", self.key, input)),
+            _ => Ok(Value::String(input.to_string())),
+        }
+    }
+}
+
+/// Every setting, in the order the structs declare them, nested tables
+/// flattened into dotted keys
+pub fn fields(settings: &Settings) -> Vec<Field> {
+    let mut fields = Vec::new();
+    if let Ok(table) = settings.to_table() {
+        collect_fields(&table, "", &mut fields);
+    }
+    fields
+}
+
+fn collect_fields(table: &toml::Table, prefix: &str, fields: &mut Vec<Field>) {
+    for (name, value) in table {
+        let key = if prefix.is_empty() { name.clone() } else { format!("{}.{}", prefix, name) };
+        match value {
+            // Maps with arbitrary keys, like [keybindings.bindings], still get a row when empty
+            Value::Table(nested) if !nested.is_empty() => collect_fields(nested, &key, fields),
+            _ => {
+                let kind = field_kind(&key, value);
+                fields.push(Field { key, value: value.clone(), kind });
+            }
+        }
+    }
+}
+
+fn field_kind(key: &str, value: &Value) -> FieldKind {
+    if let Some(choices) = choices(key) {
+        return FieldKind::Choice(choices);
+    }
+    match value {
+        _ if key == "version" => FieldKind::ReadOnly,
+        Value::Boolean(_) => FieldKind::Toggle,
+        Value::Integer(_) | Value::Float(_) => FieldKind::Number,
+        Value::String(_) => FieldKind::Text,
+        _ => FieldKind::ReadOnly,
+    }
+}
+
+/// Allowed values of the enum-typed settings, as written in the file
+fn choices(key: &str) -> Option<Vec<String>> {
+    match key {
+        "display.usage_view" => Some(variant_names(&UsageView::ALL)),
+        "keybindings.preset" => Some(variant_names(&KeymapPreset::ALL)),
+        _ => None,
+    }
+}
+
+fn variant_names<T: Serialize>(variants: &[T]) -> Vec<String> {
+    variants
+        .iter()
+        .filter_map(|variant| Value::try_from(variant).ok())
+        .filter_map(|value| value.as_str().map(str::to_string))
+        .collect()
+}
+
+/// What the editor wants the dashboard to do after a key press in the input
+#[derive(Debug, Clone, PartialEq)]
+pub enum InputOutcome {
+    /// Keep typing
+    Pending,
+    /// Close the input without changing anything
+    Cancelled,
+    /// Try this text as the field's new value; the input stays open until
+    /// the dashboard accepts it
+    Submit(String),
+}
+
+/// Cursor, input and unsaved changes of the Settings tab
+#[derive(Debug, Clone, Default)]
+pub struct SettingsEditor {
+    table_state: TableState,
+    /// Text typed for the selected field, while its input is open
+    input: Option<String>,
+    /// Keys changed since the last save, in the order they were first changed
+    changed: Vec<String>,
+    area: Rect,
+}
+
+impl SettingsEditor {
+    pub fn selected(&self) -> usize {
+        self.table_state.selected().unwrap_or(0)
+    }
+
+    /// The field under the cursor
+    pub fn selected_field(&self, settings: &Settings) -> Option<Field> {
+        fields(settings).into_iter().nth(self.selected())
+    }
+
+    /// Move the cursor to `index`, clamped to the fields there are
+    pub fn select(&mut self, index: usize, settings: &Settings) {
+        let last = fields(settings).len().saturating_sub(1);
+        self.table_state.select(Some(index.min(last)));
+    }
+
+    pub fn is_editing(&self) -> bool {
+        self.input.is_some()
+    }
+
+    /// Open the input for the selected field, starting from its current value
+    pub fn start_input(&mut self, field: &Field) {
+        self.input = Some(match &field.value {
+            Value::String(text) => text.clone(),
+            value => value.to_string(),
+        });
+    }
+
+    pub fn finish_input(&mut self) {
+        self.input = None;
+    }
+
+    pub fn handle_key(&mut self, event: KeyEvent) -> InputOutcome {
+        let Some(input) = &mut self.input else {
+            return InputOutcome::Cancelled;
+        };
+        let ctrl = event.modifiers.contains(KeyModifiers::CONTROL);
+
+        match event.code {
+            KeyCode::Esc => {
+                self.input = None;
+                return InputOutcome::Cancelled;
+            }
+            KeyCode::Char('g') if ctrl => {
+                self.input = None;
+                return InputOutcome::Cancelled;
+            }
+            KeyCode::Enter => return InputOutcome::Submit(input.clone()),
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Char('u') if ctrl => input.clear(),
+            KeyCode::Char(c) if !ctrl && !event.modifiers.contains(KeyModifiers::ALT) => input.push(c),
+            _ => {}
+        }
+
+        InputOutcome::Pending
+    }
+
+    /// Remember that `key` differs from the file
+    pub fn mark_changed(&mut self, key: &str) {
+        if !self.changed.iter().any(|changed| changed == key) {
+            self.changed.push(key.to_string());
+        }
+    }
+
+    pub fn changed(&self) -> &[String] {
+        &self.changed
+    }
+
+    /// Forget the unsaved changes once they are in the file
+    pub fn clear_changes(&mut self) {
+        self.changed.clear();
+    }
+
+    /// The field index under a mouse click, if it hit a row
+    pub fn field_at(&self, column: u16, row: u16) -> Option<usize> {
+        let area = self.area;
+        let first_row = area.y + TABLE_FIRST_ROW_OFFSET;
+        let inside = column >= area.x && column < area.x + area.width && row < area.y + area.height.saturating_sub(1);
+        (inside && row >= first_row).then(|| self.table_state.offset() + (row - first_row) as usize)
+    }
+
+    pub fn render(&mut self, settings: &Settings, area: Rect, buf: &mut Buffer) {
+        self.area = area;
+        let fields = fields(settings);
+        let selected = self.selected().min(fields.len().saturating_sub(1));
+        self.table_state.select(Some(selected));
+
+        let mut previous_section = None;
+        let rows: Vec<Row> = fields
+            .iter()
+            .enumerate()
+            .map(|(index, field)| {
+                // Name each section once, on its first row
+                let section = field.section();
+                let section_cell = if previous_section == Some(section) { "" } else { section };
+                previous_section = Some(section);
+
+                let changed = self.changed.contains(&field.key);
+                let value_cell = match &self.input {
+                    Some(input) if index == selected => Cell::from(format!("{}█", input))
+                        .style(Style::default().fg(Color::Black).bg(Color::Yellow)),
+                    _ => {
+                        let marker = if changed { " *" } else { "" };
+                        let color = match field.kind {
+                            FieldKind::ReadOnly => Color::DarkGray,
+                            _ if changed => Color::Yellow,
+                            _ => Color::Green,
+                        };
+                        Cell::from(format!("{}{}", field.display(), marker)).style(Style::default().fg(color))
+                    }
+                };
+
+                Row::new(vec![
+                    Cell::from(section_cell.to_string())
+                        .style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+                    Cell::from(field.label().to_string()),
+                    value_cell,
+                    Cell::from(field.hint()).style(Style::default().fg(Color::DarkGray)),
+                ])
+            })
+            .collect();
+
+        let title = match self.changed.len() {
+            0 => " Settings ".to_string(),
+            1 => " Settings (1 unsaved change) ".to_string(),
+            count => format!(" Settings ({} unsaved changes) ", count),
+        };
+
+        let table = Table::new(
+            rows,
+            &[
+                Constraint::Length(12), // Section
+                Constraint::Min(28),    // Key
+                Constraint::Min(24),    // Value
+                Constraint::Min(20),    // How to change it
+            ],
+        )
+        .header(
+            Row::new(vec!["Section", "Setting", "Value", "Change with"])
+                .style(Style::default().add_modifier(Modifier::BOLD))
+                .bottom_margin(1),
+        )
+        .highlight_style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD))
+        .block(
+            Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Cyan)),
+        );
+
+        StatefulWidget::render(table, area, buf, &mut self.table_state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use toml::Table;
+
+    fn field(key: &str, settings: &Settings) -> Field {
+        fields(settings).into_iter().find(|field| field.key == key).unwrap()
+    }
+
+    fn choice(value: &str, choices: &[&str]) -> Field {
+        Field {
+            key: "display.usage_view".to_string(),
+            value: Value::from(value),
+            kind: FieldKind::Choice(choices.iter().map(|choice| choice.to_string()).collect()),
+        }
+    }
+
+    #[test]
+    fn fields_flatten_settings_into_dotted_keys() {
+        let settings = Settings::default();
+        let fields = fields(&settings);
+        assert_eq!(fields[0].key, "version");
+        assert_eq!(fields[0].kind, FieldKind::ReadOnly);
+        assert!(fields.iter().all(|field| !field.value.is_table() || field.display() == "(none)"));
+
+        assert_eq!(field("system.intervals.cpu_ms", &settings).kind, FieldKind::Number);
+        assert_eq!(field("dashboard.title", &settings).kind, FieldKind::Text);
+        assert_eq!(field("display.show_cpu_graph", &settings).kind, FieldKind::Toggle);
+        assert_eq!(
+            field("display.usage_view", &settings).kind,
+            FieldKind::Choice(vec!["auto".to_string(), "host".to_string(), "container".to_string()])
+        );
+    }
+
+    #[test]
+    fn collect_fields_keeps_empty_tables_as_rows() {
+        let table: Table = "top = 1\n[outer]\nflag = true\n[outer.inner]\nname = \"x\"\n[outer.empty]\n"
+            .parse()
+            .unwrap();
+        let mut fields = Vec::new();
+        collect_fields(&table, "", &mut fields);
+
+        let keys: Vec<&str> = fields.iter().map(|field| field.key.as_str()).collect();
+        assert_eq!(keys, ["top", "outer.flag", "outer.inner.name", "outer.empty"]);
+        assert_eq!(fields[1].section(), "outer");
+        assert_eq!(fields[2].label(), "inner.name");
+        assert_eq!(fields[3].kind, FieldKind::ReadOnly);
+        assert_eq!(fields[3].display(), "(none)");
+    }
+
+    #[test]
+    fn choices_wrap_around_both_ways() {
+        let choices = ["auto", "host", "container"];
+        assert_eq!(choice("auto", &choices).step(true), Some(Value::from("host")));
+        assert_eq!(choice("container", &choices).step(true), Some(Value::from("auto")));
+        assert_eq!(choice("auto", &choices).step(false), Some(Value::from("container")));
+        // An unknown value steps from the first choice
+        assert_eq!(choice("other", &choices).step(true), Some(Value::from("host")));
+    }
+
+    #[test]
+    fn toggles_flip_and_other_kinds_do_not_step() {
+        let settings = Settings::default();
+        let toggle = field("display.show_cpu_graph", &settings);
+        assert_eq!(toggle.step(true), Some(Value::Boolean(!toggle.value.as_bool().unwrap())));
+        assert_eq!(field("system.intervals.cpu_ms", &settings).step(true), None);
+        assert_eq!(field("dashboard.title", &settings).step(false), None);
+    }
+
+    #[test]
+    fn number_input_is_an_integer_or_a_float() {
+        let settings = Settings::default();
+        let number = field("system.intervals.cpu_ms", &settings);
+        assert_eq!(number.parse_input(" 250 "), Ok(Value::Integer(250)));
+        assert_eq!(number.parse_input("-3"), Ok(Value::Integer(-3)));
+        assert_eq!(number.parse_input("2.5"), Ok(Value::Float(2.5)));
+        assert_eq!(
+            number.parse_input("fast"),
+            Err("system.intervals.cpu_ms: \"fast\" is not a number".to_string())
+        );
+
+        // Anything else is taken as text
+        let title = field("dashboard.title", &settings);
+        assert_eq!(title.parse_input(" 42 "), Ok(Value::from("42")));
+    }
+
+    #[test]
+    fn clicks_map_to_fields_below_the_header() {
+        let settings = Settings::default();
+        let area = Rect::new(2, 1, 80, 12);
+        let mut buf = Buffer::empty(Rect::new(0, 0, 90, 20));
+        let mut editor = SettingsEditor::default();
+        editor.render(&settings, area, &mut buf);
+
+        let first_row = area.y + TABLE_FIRST_ROW_OFFSET;
+        assert_eq!(editor.field_at(10, first_row), Some(0));
+        assert_eq!(editor.field_at(10, first_row + 2), Some(2));
+        // Header, bottom border and outside the table
+        assert_eq!(editor.field_at(10, first_row - 1), None);
+        assert_eq!(editor.field_at(10, area.y + area.height - 1), None);
+        assert_eq!(editor.field_at(1, first_row), None);
+        assert_eq!(editor.field_at(area.x + area.width, first_row), None);
+
+        // Scrolled to the last field, rows count from the table's offset
+        let last = fields(&settings).len() - 1;
+        editor.select(last, &settings);
+        editor.render(&settings, area, &mut buf);
+        let offset = editor.table_state.offset();
+        assert!(offset > 0);
+        assert_eq!(editor.field_at(10, first_row), Some(offset));
+    }
+}
@@ -0,0 +1,133 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::{env, fs, io};
+
+use crate::system::{CgroupSort, ProcessSort};
+use super::dashboard::{Panel, TabIndex};
+
+/// Where the dashboard was left, restored on the next start. Kept in its own
+/// file so the hand-edited config is never rewritten.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiState {
+    pub tab: TabIndex,
+    pub process_sort: ProcessSort,
+    pub process_sort_descending: bool,
+    pub cgroup_sort: CgroupSort,
+    pub cgroup_sort_descending: bool,
+    /// The Overview panel with the highlight
+    pub selected_panel: Panel,
+    /// The Overview panel shown maximized, if any
+    pub focused_panel: Option<Panel>,
+}
+
+impl Default for UiState {
+    fn default() -> Self {
+        Self {
+            tab: TabIndex::Overview,
+            process_sort: ProcessSort::Cpu,
+            process_sort_descending: true,
+            cgroup_sort: CgroupSort::Cpu,
+            cgroup_sort_descending: true,
+            selected_panel: Panel::CpuGauge,
+            focused_panel: None,
+        }
+    }
+}
+
+impl UiState {
+    /// `$XDG_STATE_HOME/system-monitor/state.toml`, falling back to
+    /// `~/.local/state` as the XDG spec does
+    pub fn default_path() -> Option<PathBuf> {
+        let non_empty = |name| env::var_os(name).filter(|value| !value.is_empty()).map(PathBuf::from);
+        non_empty("XDG_STATE_HOME")
+            .or_else(|| non_empty("HOME").map(|home| home.join(".local").join("state")))
+            .map(|dir| dir.join("system-monitor").join("state.toml"))
+    }
+
+    /// The saved state, or None before the first save
+    pub fn load(path: &Path) -> Result<Option<UiState>> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err).with_context(|| format!("Failed to read {}", path.display())),
+        };
+        let state = toml::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))?;
+        Ok(Some(state))
+    }
+
+    /// Write through a temporary file, so a crash mid-write leaves the old state
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        let content = toml::to_string_pretty(self).context("Failed to serialize UI state")?;
+        let temporary = path.with_extension("toml.tmp");
+        fs::write(&temporary, content).with_context(|| format!("Failed to write {}", temporary.display()))?;
+        fs::rename(&temporary, path).with_context(|| format!("Failed to replace {}", path.display()))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch_file(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("system-monitor-state-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn saved_state_loads_back() {
+        let path = scratch_file("round-trip.toml");
+        let state = UiState {
+            tab: TabIndex::Cgroups,
+            process_sort: ProcessSort::Memory,
+            process_sort_descending: false,
+            cgroup_sort: CgroupSort::Io,
+            cgroup_sort_descending: false,
+            selected_panel: Panel::Disks,
+            focused_panel: Some(Panel::Pressure),
+        };
+        state.save(&path).unwrap();
+        assert_eq!(UiState::load(&path).unwrap(), Some(state));
+        assert!(!path.with_extension("toml.tmp").exists());
+    }
+
+    #[test]
+    fn save_creates_the_directory() {
+        let path = scratch_file("nested").join("deeper").join("state.toml");
+        UiState::default().save(&path).unwrap();
+        assert_eq!(UiState::load(&path).unwrap(), Some(UiState::default()));
+    }
+
+    #[test]
+    fn missing_file_is_not_an_error() {
+        assert_eq!(UiState::load(&scratch_file("missing.toml")).unwrap(), None);
+    }
+
+    #[test]
+    fn missing_keys_take_their_defaults() {
+        let path = scratch_file("partial.toml");
+        fs::write(&path, "tab = \"fleet\"\nprocess_sort = \"name\"\n").unwrap();
+        let state = UiState::load(&path).unwrap().unwrap();
+        assert_eq!(
+            state,
+            UiState { tab: TabIndex::Fleet, process_sort: ProcessSort::Name, ..UiState::default() }
+        );
+    }
+
+    #[test]
+    fn unknown_tab_is_an_error() {
+        let path = scratch_file("unknown-tab.toml");
+        fs::write(&path, "tab = \"graphs\"\n").unwrap();
+        let error = UiState::load(&path).unwrap_err();
+        assert!(error.to_string().starts_with("Failed to parse"), "{}", error);
+        assert!(format!("{:#}", error).contains("graphs"), "{:#}", error);
+    }
+}
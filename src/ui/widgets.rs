@@ -1,28 +1,117 @@
 use ratatui::{
     buffer::Buffer,
-    layout::{Constraint, Rect},
+    layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     symbols,
     text::{Line, Span},
     widgets::{
-        Axis, Block, Borders, Chart, Dataset, Gauge, List, ListItem, Paragraph, Row, 
+        Axis, Block, Borders, Cell, Chart, Dataset, Gauge, GraphType, LineGauge, List, ListItem, Paragraph, Row, 
         Table, Widget, Wrap,
     },
 };
-use crate::system::SystemMonitor;
+use crate::config::settings::AlertSettings;
+use crate::remote::HostStatus;
+use crate::system::{evaluate_alerts, sort_cgroups, AlertLevel, sort_processes, CgroupSort, CgroupStats, ContainerUsage, CpuBreakdown, LoadAverage, MemInfo, MemoryData, PressureData, ProcessInfo, ProcessSort, SystemSnapshot};
+use crate::utils::helpers::{format_bytes, format_duration};
+
+/// Rows of a bordered table with a header and header margin start this far
+/// below its top edge
+pub const TABLE_FIRST_ROW_OFFSET: u16 = 3;
+
+// Line colors for per-core series, cycled when there are more cores than colors
+const CORE_COLORS: &[Color] = &[
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Red,
+    Color::LightCyan,
+    Color::LightGreen,
+    Color::LightYellow,
+    Color::LightBlue,
+    Color::LightMagenta,
+    Color::LightRed,
+    Color::White,
+];
+
+// CPU states in stacking order, bottom up; idle fills the rest
+type CpuState = (&'static str, Color, fn(&CpuBreakdown) -> f32);
+const CPU_STATES: &[CpuState] = &[
+    ("user", Color::Green, |b| b.user),
+    ("nice", Color::Blue, |b| b.nice),
+    ("system", Color::Red, |b| b.system),
+    ("irq", Color::Magenta, |b| b.irq),
+    ("softirq", Color::LightMagenta, |b| b.softirq),
+    ("iowait", Color::Yellow, |b| b.iowait),
+    ("steal", Color::LightCyan, |b| b.steal),
+];
+
+/// Compute (min, avg, max) over a series, or None when it is empty
+fn series_stats(values: impl Iterator<Item = f64>) -> Option<(f64, f64, f64)> {
+    let mut count = 0usize;
+    let mut sum = 0.0;
+    let mut min = f64::MAX;
+    let mut max = f64::MIN;
+
+    for value in values {
+        count += 1;
+        sum += value;
+        min = min.min(value);
+        max = max.max(value);
+    }
+
+    if count == 0 {
+        None
+    } else {
+        Some((min, sum / count as f64, max))
+    }
+}
+
+fn stats_line(label: &str, current: f64, stats: Option<(f64, f64, f64)>, color: Color) -> Line<'static> {
+    let (min, avg, max) = stats.unwrap_or((current, current, current));
+    Line::from(vec![
+        Span::styled(format!("{}: ", label), Style::default().fg(color).add_modifier(Modifier::BOLD)),
+        Span::raw(format!("now {:.1}%", current)),
+        Span::styled("  min ", Style::default().fg(Color::Gray)),
+        Span::raw(format!("{:.1}%", min)),
+        Span::styled("  avg ", Style::default().fg(Color::Gray)),
+        Span::raw(format!("{:.1}%", avg)),
+        Span::styled("  max ", Style::default().fg(Color::Gray)),
+        Span::raw(format!("{:.1}%", max)),
+    ])
+}
+
+fn usage_color(percent: f32, warn: f32, crit: f32) -> Color {
+    if percent > crit {
+        Color::Red
+    } else if percent > warn {
+        Color::Yellow
+    } else {
+        Color::Green
+    }
+}
 
 pub struct CpuWidget;
 
 impl CpuWidget {
-    pub fn render(monitor: &SystemMonitor, area: Rect, buf: &mut Buffer) {
-        let cpu_usage = monitor.cpu_usage();
-        let cpu_count = monitor.cpu_count();
+    /// Overall CPU gauge; with `container`, usage of our cgroup against its limit
+    pub fn render(snapshot: &SystemSnapshot, container: Option<&ContainerUsage>, area: Rect, buf: &mut Buffer) {
+        let (cpu_usage, title) = match container {
+            Some(container) => (
+                container.cpu_usage,
+                match container.cpu_limit {
+                    Some(limit) => format!(" CPU Usage (container, {:.2} cores) ", limit),
+                    None => " CPU Usage (container, no limit) ".to_string(),
+                },
+            ),
+            None => (snapshot.cpu_usage, format!(" CPU Usage ({} cores) ", snapshot.cpu_count())),
+        };
         
         // Create CPU usage gauge
         let gauge = Gauge::default()
             .block(
                 Block::default()
-                    .title(format!(" CPU Usage ({} cores) ", cpu_count))
+                    .title(title)
                     .borders(Borders::ALL)
                     .border_style(Style::default().fg(Color::Cyan)),
             )
@@ -37,14 +126,14 @@ impl CpuWidget {
                     })
                     .add_modifier(Modifier::BOLD),
             )
-            .percent(cpu_usage as u16)
+            .percent(cpu_usage.clamp(0.0, 100.0) as u16)
             .label(format!("{:.1}%", cpu_usage));
 
         gauge.render(area, buf);
     }
 
-    pub fn render_history_chart(monitor: &SystemMonitor, area: Rect, buf: &mut Buffer) {
-        let history = monitor.cpu_history();
+    pub fn render_history_chart(snapshot: &SystemSnapshot, area: Rect, buf: &mut Buffer) {
+        let history = &snapshot.cpu_history;
         
         if history.is_empty() {
             return;
@@ -85,15 +174,267 @@ impl CpuWidget {
 
         chart.render(area, buf);
     }
+
+    /// Maximized CPU gauge: one gauge per core, split by CPU state where
+    /// `/proc/stat` is available
+    pub fn render_core_gauges(snapshot: &SystemSnapshot, area: Rect, buf: &mut Buffer) {
+        let cores = &snapshot.cpu_core_usage;
+        let block = Block::default()
+            .title(format!(" CPU Cores ({}) — {:.1}% overall ", cores.len(), snapshot.cpu_usage))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan));
+        let mut inner = block.inner(area);
+        block.render(area, buf);
+
+        if cores.is_empty() || inner.height < 2 {
+            return;
+        }
+
+        let breakdowns = snapshot
+            .cpu_history
+            .back()
+            .map(|cpu_data| cpu_data.per_core_breakdown.as_slice())
+            .unwrap_or_default();
+        if !breakdowns.is_empty() {
+            Paragraph::new(cpu_state_legend()).render(Rect { height: 1, ..inner }, buf);
+            inner.y += 1;
+            inner.height -= 1;
+        }
+
+        // Lay cores out in as many columns as needed to fit the height
+        let rows_available = inner.height as usize;
+        let columns = cores.len().div_ceil(rows_available).max(1);
+        let rows_per_column = cores.len().div_ceil(columns);
+        let column_areas = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(vec![Constraint::Ratio(1, columns as u32); columns])
+            .split(inner);
+
+        for (index, usage) in cores.iter().enumerate() {
+            let column = index / rows_per_column;
+            let row = index % rows_per_column;
+            let column_area = column_areas[column];
+            let gauge_area = Rect {
+                x: column_area.x,
+                y: column_area.y + row as u16,
+                width: column_area.width.saturating_sub(1),
+                height: 1,
+            };
+
+            match breakdowns.get(index) {
+                Some(breakdown) => {
+                    let label = format!("cpu{:<3} {:>5.1}% ", index, breakdown.busy());
+                    buf.set_string(gauge_area.x, gauge_area.y, &label, Style::default());
+                    let bar_area = Rect {
+                        x: gauge_area.x + label.len() as u16,
+                        width: gauge_area.width.saturating_sub(label.len() as u16),
+                        ..gauge_area
+                    };
+                    render_stacked_bar(breakdown, bar_area, buf);
+                }
+                None => LineGauge::default()
+                    .label(format!("cpu{:<3} {:>5.1}%", index, usage))
+                    .gauge_style(Style::default().fg(usage_color(*usage, 60.0, 80.0)))
+                    .line_set(symbols::line::THICK)
+                    .ratio((*usage as f64 / 100.0).clamp(0.0, 1.0))
+                    .render(gauge_area, buf),
+            }
+        }
+    }
+
+    /// Maximized CPU history: overall plus per-core lines with min/avg/max
+    pub fn render_detailed_chart(snapshot: &SystemSnapshot, area: Rect, buf: &mut Buffer) {
+        let history = &snapshot.cpu_history;
+        let block = Block::default()
+            .title(" CPU History (per core) ")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan));
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let has_breakdown = history.iter().any(|cpu_data| cpu_data.breakdown.is_some());
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(if has_breakdown {
+                vec![Constraint::Length(1), Constraint::Percentage(50), Constraint::Min(0)]
+            } else {
+                vec![Constraint::Length(1), Constraint::Min(0)]
+            })
+            .split(inner);
+
+        let stats = series_stats(history.iter().map(|cpu_data| cpu_data.usage as f64));
+        Paragraph::new(stats_line("Overall", snapshot.cpu_usage as f64, stats, Color::Cyan))
+            .render(chunks[0], buf);
+
+        if history.is_empty() {
+            return;
+        }
+        if has_breakdown {
+            Self::render_breakdown_chart(snapshot, chunks[2], buf);
+        }
+
+        let overall: Vec<(f64, f64)> = history
+            .iter()
+            .enumerate()
+            .map(|(i, cpu_data)| (i as f64, cpu_data.usage as f64))
+            .collect();
+
+        let core_count = history.iter().map(|cpu_data| cpu_data.per_core.len()).max().unwrap_or(0);
+        let per_core: Vec<Vec<(f64, f64)>> = (0..core_count)
+            .map(|core| {
+                history
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, cpu_data)| {
+                        cpu_data.per_core.get(core).map(|usage| (i as f64, *usage as f64))
+                    })
+                    .collect()
+            })
+            .collect();
+        let core_names: Vec<String> = (0..core_count).map(|core| format!("cpu{}", core)).collect();
+
+        let mut datasets: Vec<Dataset> = per_core
+            .iter()
+            .zip(core_names.iter())
+            .enumerate()
+            .map(|(core, (data, name))| {
+                Dataset::default()
+                    .name(name.as_str())
+                    .marker(symbols::Marker::Braille)
+                    .style(Style::default().fg(CORE_COLORS[core % CORE_COLORS.len()]))
+                    .data(data)
+            })
+            .collect();
+
+        // Overall usage last so it is drawn on top of the per-core lines
+        datasets.push(
+            Dataset::default()
+                .name("All")
+                .marker(symbols::Marker::Braille)
+                .style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
+                .data(&overall),
+        );
+
+        let chart = Chart::new(datasets)
+            .x_axis(
+                Axis::default()
+                    .title("Time")
+                    .style(Style::default().fg(Color::Gray))
+                    .bounds([0.0, overall.len().max(1) as f64]),
+            )
+            .y_axis(
+                Axis::default()
+                    .title("Usage %")
+                    .style(Style::default().fg(Color::Gray))
+                    .bounds([0.0, 100.0])
+                    .labels(vec![Span::raw("0"), Span::raw("50"), Span::raw("100")]),
+            );
+
+        chart.render(chunks[1], buf);
+    }
+
+    /// Overall CPU time stacked by state: each line is the running total of
+    /// the states below it, so the gap between two lines is that state's share
+    pub fn render_breakdown_chart(snapshot: &SystemSnapshot, area: Rect, buf: &mut Buffer) {
+        let history = &snapshot.cpu_history;
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Min(0)])
+            .split(area);
+        Paragraph::new(cpu_state_legend()).render(chunks[0], buf);
+
+        let series: Vec<Vec<(f64, f64)>> = (0..CPU_STATES.len())
+            .map(|depth| {
+                history
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, cpu_data)| {
+                        let breakdown = cpu_data.breakdown.as_ref()?;
+                        let stacked: f32 = CPU_STATES[..=depth].iter().map(|(_, _, share)| share(breakdown)).sum();
+                        Some((i as f64, stacked as f64))
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let datasets: Vec<Dataset> = CPU_STATES
+            .iter()
+            .zip(series.iter())
+            .map(|((_, color, _), data)| {
+                Dataset::default()
+                    .marker(symbols::Marker::Braille)
+                    .graph_type(GraphType::Line)
+                    .style(Style::default().fg(*color))
+                    .data(data)
+            })
+            .collect();
+
+        let chart = Chart::new(datasets)
+            .x_axis(
+                Axis::default()
+                    .style(Style::default().fg(Color::Gray))
+                    .bounds([0.0, history.len().max(1) as f64]),
+            )
+            .y_axis(
+                Axis::default()
+                    .title("Time by state %")
+                    .style(Style::default().fg(Color::Gray))
+                    .bounds([0.0, 100.0])
+                    .labels(vec![Span::raw("0"), Span::raw("50"), Span::raw("100")]),
+            );
+
+        chart.render(chunks[1], buf);
+    }
+}
+
+/// One line naming each CPU state in its stacking color
+fn cpu_state_legend() -> Line<'static> {
+    let mut spans = Vec::new();
+    for (name, color, _) in CPU_STATES {
+        spans.push(Span::styled("■ ", Style::default().fg(*color)));
+        spans.push(Span::raw(format!("{}  ", name)));
+    }
+    spans.push(Span::styled("· idle", Style::default().fg(Color::DarkGray)));
+    Line::from(spans)
+}
+
+/// Fill `area` (one row) with a bar split into CPU state segments
+fn render_stacked_bar(breakdown: &CpuBreakdown, area: Rect, buf: &mut Buffer) {
+    let width = area.width as f32;
+    let mut x = area.x;
+    let mut filled = 0.0;
+    for (_, color, share) in CPU_STATES {
+        filled += share(breakdown) / 100.0 * width;
+        let end = area.x + (filled.round() as u16).min(area.width);
+        while x < end {
+            buf.get_mut(x, area.y).set_symbol("█").set_fg(*color);
+            x += 1;
+        }
+    }
+    while x < area.x + area.width {
+        buf.get_mut(x, area.y).set_symbol("·").set_fg(Color::DarkGray);
+        x += 1;
+    }
 }
 
 pub struct MemoryWidget;
 
 impl MemoryWidget {
-    pub fn render(monitor: &SystemMonitor, area: Rect, buf: &mut Buffer) {
-        let used = monitor.memory_used();
-        let total = monitor.memory_total();
-        let usage_percent = monitor.memory_usage_percent();
+    /// Memory gauge; with `container`, our cgroup's memory against its limit
+    pub fn render(snapshot: &SystemSnapshot, container: Option<&ContainerUsage>, area: Rect, buf: &mut Buffer) {
+        let (used, total, usage_percent, title) = match container {
+            Some(container) => (
+                container.memory_current,
+                container.memory_limit(snapshot.memory_total),
+                container.memory_usage_percent(snapshot.memory_total),
+                if container.memory_max.is_some() {
+                    " Memory Usage (container) "
+                } else {
+                    " Memory Usage (container, no limit) "
+                },
+            ),
+            None => (snapshot.memory_used, snapshot.memory_total, snapshot.memory_usage_percent(), " Memory Usage "),
+        };
         
         let used_gb = used as f64 / 1_073_741_824.0; // Convert bytes to GB
         let total_gb = total as f64 / 1_073_741_824.0;
@@ -101,7 +442,7 @@ impl MemoryWidget {
         let gauge = Gauge::default()
             .block(
                 Block::default()
-                    .title(" Memory Usage ")
+                    .title(title)
                     .borders(Borders::ALL)
                     .border_style(Style::default().fg(Color::Magenta)),
             )
@@ -116,14 +457,44 @@ impl MemoryWidget {
                     })
                     .add_modifier(Modifier::BOLD),
             )
-            .percent(usage_percent as u16)
+            .percent(usage_percent.clamp(0.0, 100.0) as u16)
             .label(format!("{:.1}% ({:.1}/{:.1} GB)", usage_percent, used_gb, total_gb));
 
         gauge.render(area, buf);
     }
 
-    pub fn render_history_chart(monitor: &SystemMonitor, area: Rect, buf: &mut Buffer) {
-        let history = monitor.memory_history();
+    pub fn render_swap(snapshot: &SystemSnapshot, area: Rect, buf: &mut Buffer) {
+        let swap_percent = snapshot.swap_usage_percent();
+
+        let gauge = Gauge::default()
+            .block(
+                Block::default()
+                    .title(" Swap ")
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::LightRed)),
+            )
+            .gauge_style(
+                Style::default()
+                    .fg(usage_color(swap_percent, 25.0, 50.0))
+                    .add_modifier(Modifier::BOLD),
+            )
+            .percent(swap_percent.clamp(0.0, 100.0) as u16)
+            .label(if snapshot.swap_total > 0 {
+                format!(
+                    "{:.1}% ({:.1}/{:.1} GB)",
+                    swap_percent,
+                    snapshot.swap_used as f64 / 1_073_741_824.0,
+                    snapshot.swap_total as f64 / 1_073_741_824.0
+                )
+            } else {
+                "No swap".to_string()
+            });
+
+        gauge.render(area, buf);
+    }
+
+    pub fn render_history_chart(snapshot: &SystemSnapshot, area: Rect, buf: &mut Buffer) {
+        let history = &snapshot.memory_history;
         
         if history.is_empty() {
             return;
@@ -135,13 +506,28 @@ impl MemoryWidget {
             .map(|(i, mem_data)| (i as f64, mem_data.usage_percent as f64))
             .collect();
 
-        let dataset = Dataset::default()
+        let mut datasets = vec![Dataset::default()
             .name("Memory %")
             .marker(symbols::Marker::Braille)
             .style(Style::default().fg(Color::Magenta))
-            .data(&data);
+            .data(&data)];
 
-        let chart = Chart::new(vec![dataset])
+        let swap_data: Vec<(f64, f64)> = history
+            .iter()
+            .enumerate()
+            .map(|(i, mem_data)| (i as f64, mem_data.swap_usage_percent() as f64))
+            .collect();
+        if snapshot.swap_total > 0 {
+            datasets.push(
+                Dataset::default()
+                    .name("Swap %")
+                    .marker(symbols::Marker::Braille)
+                    .style(Style::default().fg(Color::LightRed))
+                    .data(&swap_data),
+            );
+        }
+
+        let chart = Chart::new(datasets)
             .block(
                 Block::default()
                     .title(" Memory History ")
@@ -163,15 +549,255 @@ impl MemoryWidget {
 
         chart.render(area, buf);
     }
+
+    /// Maximized memory gauge: RAM and swap side by side with absolute values
+    pub fn render_detailed(snapshot: &SystemSnapshot, area: Rect, buf: &mut Buffer) {
+        let block = Block::default()
+            .title(" Memory Details ")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Magenta));
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // RAM gauge
+                Constraint::Length(3), // Swap gauge
+                Constraint::Min(0),    // Figures
+            ])
+            .split(inner);
+
+        let used = snapshot.memory_used;
+        let total = snapshot.memory_total;
+        let usage_percent = snapshot.memory_usage_percent();
+
+        Gauge::default()
+            .block(Block::default().title(" RAM ").borders(Borders::ALL))
+            .gauge_style(
+                Style::default()
+                    .fg(usage_color(usage_percent, 75.0, 90.0))
+                    .add_modifier(Modifier::BOLD),
+            )
+            .percent(usage_percent.clamp(0.0, 100.0) as u16)
+            .label(format!("{:.1}% ({} / {})", usage_percent, format_bytes(used), format_bytes(total)))
+            .render(chunks[0], buf);
+
+        let swap_used = snapshot.swap_used;
+        let swap_total = snapshot.swap_total;
+        let swap_percent = if swap_total > 0 {
+            (swap_used as f32 / swap_total as f32) * 100.0
+        } else {
+            0.0
+        };
+
+        Gauge::default()
+            .block(Block::default().title(" Swap ").borders(Borders::ALL))
+            .gauge_style(
+                Style::default()
+                    .fg(usage_color(swap_percent, 25.0, 50.0))
+                    .add_modifier(Modifier::BOLD),
+            )
+            .percent(swap_percent.clamp(0.0, 100.0) as u16)
+            .label(if swap_total > 0 {
+                format!("{:.1}% ({} / {})", swap_percent, format_bytes(swap_used), format_bytes(swap_total))
+            } else {
+                "No swap configured".to_string()
+            })
+            .render(chunks[1], buf);
+
+        let figures = vec![
+            Line::from(vec![
+                Span::styled("Used:      ", Style::default().fg(Color::Magenta)),
+                Span::raw(format_bytes(used)),
+            ]),
+            Line::from(vec![
+                Span::styled("Available: ", Style::default().fg(Color::Green)),
+                Span::raw(format_bytes(snapshot.memory_available)),
+            ]),
+            Line::from(vec![
+                Span::styled("Free:      ", Style::default().fg(Color::Green)),
+                Span::raw(format_bytes(snapshot.memory_free)),
+            ]),
+            Line::from(vec![
+                Span::styled("Total:     ", Style::default().fg(Color::Gray)),
+                Span::raw(format_bytes(total)),
+            ]),
+        ];
+
+        let figure_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(28), Constraint::Min(0)])
+            .split(chunks[2]);
+        Paragraph::new(figures).render(figure_chunks[0], buf);
+
+        let details = snapshot.memory_history.back().and_then(|mem_data| mem_data.details);
+        Self::render_breakdown(details, figure_chunks[1], buf);
+    }
+
+    /// Kernel memory breakdown from `/proc/meminfo`, for capacity planning
+    fn render_breakdown(details: Option<MemInfo>, area: Rect, buf: &mut Buffer) {
+        let Some(details) = details else {
+            Paragraph::new(Line::from(Span::styled(
+                "Detailed breakdown needs /proc/meminfo (Linux)",
+                Style::default().fg(Color::Gray),
+            )))
+            .render(area, buf);
+            return;
+        };
+
+        let figure = |label: &str, bytes: u64, color: Color| {
+            Line::from(vec![
+                Span::styled(format!("{:<15}", label), Style::default().fg(color)),
+                Span::raw(format_bytes(bytes)),
+            ])
+        };
+        let hugepages = if details.hugepages_total > 0 {
+            format!(
+                "{} of {} free ({} each)",
+                details.hugepages_free,
+                details.hugepages_total,
+                format_bytes(details.hugepage_size)
+            )
+        } else {
+            "none reserved".to_string()
+        };
+
+        let lines = vec![
+            figure("Buffers:", details.buffers, Color::Yellow),
+            figure("Cached:", details.cached, Color::Yellow),
+            figure("Shared:", details.shared, Color::Cyan),
+            figure("Slab:", details.slab, Color::Cyan),
+            figure("  reclaimable:", details.slab_reclaimable, Color::Gray),
+            figure("Dirty:", details.dirty, Color::LightRed),
+            figure("Writeback:", details.writeback, Color::LightRed),
+            figure("Swap cached:", details.swap_cached, Color::Gray),
+            Line::from(vec![
+                Span::styled(format!("{:<15}", "Huge pages:"), Style::default().fg(Color::Blue)),
+                Span::raw(hugepages),
+            ]),
+        ];
+        Paragraph::new(lines).render(area, buf);
+    }
+
+    /// Maximized memory history: RAM use stacked as used, cache/buffers and
+    /// free (cache + free = available), with swap stacked above total RAM
+    pub fn render_detailed_chart(snapshot: &SystemSnapshot, area: Rect, buf: &mut Buffer) {
+        let history = &snapshot.memory_history;
+        let block = Block::default()
+            .title(" Memory History ")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Magenta));
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(2), Constraint::Min(0)])
+            .split(inner);
+
+        let stats = series_stats(history.iter().map(|mem_data| mem_data.usage_percent as f64));
+        let swap_stats = series_stats(history.iter().map(|mem_data| mem_data.swap_usage_percent() as f64));
+        Paragraph::new(vec![
+            stats_line("Memory", snapshot.memory_usage_percent() as f64, stats, Color::Magenta),
+            stats_line("Swap  ", snapshot.swap_usage_percent() as f64, swap_stats, Color::LightRed),
+        ])
+        .render(chunks[0], buf);
+
+        if history.is_empty() {
+            return;
+        }
+
+        // Each series is the running total of the bands below it, in GB
+        let gb = |bytes: u64| bytes as f64 / 1_073_741_824.0;
+        let stacked = |band: fn(&MemoryData) -> u64| -> Vec<(f64, f64)> {
+            history
+                .iter()
+                .enumerate()
+                .map(|(i, mem_data)| (i as f64, gb(band(mem_data))))
+                .collect()
+        };
+        let used = stacked(|m| m.used);
+        let cache = stacked(|m| m.used + m.cache());
+        let free = stacked(|m| m.used + m.available);
+        let swap = stacked(|m| m.used + m.available + m.swap_used);
+
+        let latest = history.back();
+        let legend = |name: &str, bytes: u64| format!("{} {}", name, format_bytes(bytes));
+        let mut datasets = vec![
+            Dataset::default()
+                .name(legend("Used", latest.map_or(0, |m| m.used)))
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(Color::Magenta))
+                .data(&used),
+            Dataset::default()
+                .name(legend("+ Cache/buffers", latest.map_or(0, |m| m.cache())))
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(Color::Yellow))
+                .data(&cache),
+            Dataset::default()
+                .name(legend("+ Free (available", latest.map_or(0, |m| m.available)) + ")")
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(Color::Green))
+                .data(&free),
+        ];
+        if snapshot.swap_total > 0 {
+            datasets.push(
+                Dataset::default()
+                    .name(legend("+ Swap used", latest.map_or(0, |m| m.swap_used)))
+                    .marker(symbols::Marker::Braille)
+                    .graph_type(GraphType::Line)
+                    .style(Style::default().fg(Color::LightRed))
+                    .data(&swap),
+            );
+        }
+
+        let top = gb(snapshot.memory_total + snapshot.swap_total).max(1.0);
+        let chart = Chart::new(datasets)
+            .x_axis(
+                Axis::default()
+                    .title("Time")
+                    .style(Style::default().fg(Color::Gray))
+                    .bounds([0.0, history.len().max(1) as f64]),
+            )
+            .y_axis(
+                Axis::default()
+                    .title("GB")
+                    .style(Style::default().fg(Color::Gray))
+                    .bounds([0.0, top])
+                    .labels(vec![
+                        Span::raw("0"),
+                        Span::raw(format!("{:.1}", top / 2.0)),
+                        Span::raw(format!("{:.1}", top)),
+                    ]),
+            )
+            .hidden_legend_constraints((Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)));
+
+        chart.render(chunks[1], buf);
+    }
 }
 
 pub struct SystemInfoWidget;
 
 impl SystemInfoWidget {
-    pub fn render(monitor: &SystemMonitor, area: Rect, buf: &mut Buffer) {
-        let uptime = monitor.uptime();
-        let process_count = monitor.process_count();
-        let load_avg = monitor.load_average();
+    /// Load average, raw or divided by the core count, with a matching label
+    fn load_text(snapshot: &SystemSnapshot, per_core: bool) -> (&'static str, String) {
+        if per_core {
+            let load = snapshot.load_average.per_core(snapshot.cpu_count());
+            ("Load/core: ", format!("{:.2} {:.2} {:.2}", load.one, load.five, load.fifteen))
+        } else {
+            let load = snapshot.load_average;
+            ("Load Avg: ", format!("{:.2} {:.2} {:.2}", load.one, load.five, load.fifteen))
+        }
+    }
+
+    pub fn render(snapshot: &SystemSnapshot, normalize_load: bool, area: Rect, buf: &mut Buffer) {
+        let uptime = snapshot.uptime;
+        let process_count = snapshot.process_count();
+        let (load_label, load_text) = Self::load_text(snapshot, normalize_load);
         
         // Convert uptime to human readable format
         let uptime_days = uptime / 86400;
@@ -196,9 +822,8 @@ impl SystemInfoWidget {
                 Span::raw(process_count.to_string()),
             ]),
             Line::from(vec![
-                Span::styled("Load Avg: ", Style::default().fg(Color::Yellow)),
-                Span::raw(format!("{:.2} {:.2} {:.2}", 
-                    load_avg.one, load_avg.five, load_avg.fifteen)),
+                Span::styled(load_label, Style::default().fg(Color::Yellow)),
+                Span::raw(load_text),
             ]),
         ];
 
@@ -213,13 +838,356 @@ impl SystemInfoWidget {
 
         paragraph.render(area, buf);
     }
+
+    /// Maximized system info: host identity alongside the usual counters,
+    /// with load average history underneath
+    pub fn render_detailed(snapshot: &SystemSnapshot, normalize_load: bool, area: Rect, buf: &mut Buffer) {
+        let block = Block::default()
+            .title(" System Info ")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::White));
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(55), Constraint::Min(0)])
+            .split(inner);
+
+        let (load_label, load_text) = Self::load_text(snapshot, normalize_load);
+        let unknown = || "unknown".to_string();
+        let boot_time = chrono::DateTime::from_timestamp(snapshot.boot_time as i64, 0)
+            .map(|time| time.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_else(unknown);
+
+        let field = |label: &'static str, value: String, color: Color| {
+            Line::from(vec![
+                Span::styled(format!("{:<15}", label), Style::default().fg(color)),
+                Span::raw(value),
+            ])
+        };
+
+        let mut info_text = vec![
+            field("Host:", snapshot.host.host_name.clone().unwrap_or_else(unknown), Color::Cyan),
+            field("OS:", snapshot.host.os_version.clone().unwrap_or_else(unknown), Color::Cyan),
+            field("Kernel:", snapshot.host.kernel_version.clone().unwrap_or_else(unknown), Color::Cyan),
+            field("Architecture:", snapshot.host.cpu_arch.clone().unwrap_or_else(unknown), Color::Cyan),
+            Line::from(""),
+            field("Uptime:", format_duration(snapshot.uptime), Color::Green),
+            field("Booted:", boot_time, Color::Green),
+            field("CPU cores:", snapshot.cpu_count().to_string(), Color::Green),
+            field("Processes:", snapshot.process_count().to_string(), Color::Green),
+            field(load_label.trim_end(), load_text, Color::Yellow),
+        ];
+
+        if let Some(container) = &snapshot.container {
+            let stat = container.cpu_stat;
+            info_text.extend([
+                Line::from(""),
+                field("Cgroup:", container.cgroup_path.clone(), Color::Magenta),
+                field(
+                    "CPU limit:",
+                    container.cpu_limit.map_or("none".to_string(), |cores| format!("{:.2} cores", cores)),
+                    Color::Magenta,
+                ),
+                field(
+                    "Memory limit:",
+                    container.memory_max.map_or("none".to_string(), format_bytes),
+                    Color::Magenta,
+                ),
+                field("Cgroup memory:", format_bytes(container.memory_current), Color::Magenta),
+                field(
+                    "Throttled:",
+                    format!(
+                        "{} of {} periods ({:.1}s total)",
+                        stat.nr_throttled,
+                        stat.nr_periods,
+                        stat.throttled_usec as f64 / 1_000_000.0
+                    ),
+                    Color::Magenta,
+                ),
+            ]);
+        }
+
+        if !snapshot.sensors.is_empty() {
+            info_text.push(Line::from(""));
+            info_text.push(Line::from(Span::styled(
+                "Temperatures:",
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            )));
+            for sensor in &snapshot.sensors {
+                let critical = sensor.critical.map(|c| format!(" (crit {:.0}°C)", c)).unwrap_or_default();
+                let hot = sensor.critical.is_some_and(|c| sensor.temperature >= c * 0.9);
+                info_text.push(Line::from(vec![
+                    Span::raw(format!("  {:<24}", sensor.label)),
+                    Span::styled(
+                        format!("{:.1}°C", sensor.temperature),
+                        Style::default().fg(if hot { Color::Red } else { Color::Green }),
+                    ),
+                    Span::styled(critical, Style::default().fg(Color::Gray)),
+                ]));
+            }
+        }
+
+        Paragraph::new(info_text)
+            .wrap(Wrap { trim: true })
+            .render(chunks[0], buf);
+
+        Self::render_load_chart(snapshot, normalize_load, chunks[1], buf);
+    }
+
+    /// 1, 5 and 15 minute load over time; per core when `normalize_load` is
+    /// set, where 1.0 means every core busy
+    pub fn render_load_chart(snapshot: &SystemSnapshot, normalize_load: bool, area: Rect, buf: &mut Buffer) {
+        let history = &snapshot.load_history;
+        if history.is_empty() {
+            return;
+        }
+
+        let cores = snapshot.cpu_count();
+        let scaled = |load: &LoadAverage| if normalize_load { load.per_core(cores) } else { *load };
+        let series = |pick: fn(&LoadAverage) -> f64| -> Vec<(f64, f64)> {
+            history
+                .iter()
+                .enumerate()
+                .map(|(i, load_data)| (i as f64, pick(&scaled(&load_data.load))))
+                .collect()
+        };
+        let one = series(|load| load.one);
+        let five = series(|load| load.five);
+        let fifteen = series(|load| load.fifteen);
+
+        // Keep the saturation line (one load unit per core) in view
+        let saturation = if normalize_load { 1.0 } else { cores.max(1) as f64 };
+        let top = one
+            .iter()
+            .chain(five.iter())
+            .chain(fifteen.iter())
+            .map(|(_, value)| *value)
+            .fold(saturation, f64::max)
+            * 1.1;
+
+        let datasets = vec![
+            Dataset::default()
+                .name("1 min")
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(Color::Yellow))
+                .data(&one),
+            Dataset::default()
+                .name("5 min")
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(Color::LightRed))
+                .data(&five),
+            Dataset::default()
+                .name("15 min")
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(Color::Red))
+                .data(&fifteen),
+        ];
+
+        let chart = Chart::new(datasets)
+            .x_axis(
+                Axis::default()
+                    .title("Time")
+                    .style(Style::default().fg(Color::Gray))
+                    .bounds([0.0, history.len().max(1) as f64]),
+            )
+            .y_axis(
+                Axis::default()
+                    .title(if normalize_load { "Load / core" } else { "Load" })
+                    .style(Style::default().fg(Color::Gray))
+                    .bounds([0.0, top])
+                    .labels(vec![
+                        Span::raw("0"),
+                        Span::raw(format!("{:.1}", top / 2.0)),
+                        Span::raw(format!("{:.1}", top)),
+                    ]),
+            )
+            .hidden_legend_constraints((Constraint::Ratio(1, 3), Constraint::Ratio(1, 2)));
+
+        chart.render(area, buf);
+    }
+}
+
+pub struct PressureWidget;
+
+impl PressureWidget {
+    const UNAVAILABLE: &'static str = "PSI not available (needs Linux 4.20+ with CONFIG_PSI, or boot with psi=1)";
+
+    /// Compact PSI table: some/full avg10/60/300 per resource
+    pub fn render(snapshot: &SystemSnapshot, thresholds: &AlertSettings, area: Rect, buf: &mut Buffer) {
+        let block = Block::default()
+            .title(" Pressure (PSI) ")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::LightBlue));
+
+        let Some(pressure) = snapshot.pressure() else {
+            Paragraph::new(Line::from(Span::styled(Self::UNAVAILABLE, Style::default().fg(Color::Gray))))
+                .block(block)
+                .wrap(Wrap { trim: true })
+                .render(area, buf);
+            return;
+        };
+
+        Self::table(pressure, thresholds).block(block).render(area, buf);
+    }
+
+    fn table(pressure: &PressureData, thresholds: &AlertSettings) -> Table<'static> {
+        let cell = |value: Option<f32>, threshold: f32| match value {
+            Some(value) => Span::styled(
+                format!("{:>6.2}", value),
+                Style::default().fg(if value >= threshold {
+                    Color::Red
+                } else if value >= threshold / 2.0 {
+                    Color::Yellow
+                } else {
+                    Color::Green
+                }),
+            ),
+            None => Span::styled(format!("{:>6}", "-"), Style::default().fg(Color::DarkGray)),
+        };
+
+        let mut rows = Vec::new();
+        for (name, resource) in pressure.resources() {
+            let some = resource.map(|resource| resource.some);
+            let full = resource.and_then(|resource| resource.full);
+            for (kind, averages, threshold) in [
+                ("some", some, thresholds.psi_some_avg10),
+                ("full", full, thresholds.psi_full_avg10),
+            ] {
+                rows.push(Row::new(vec![
+                    Line::from(if kind == "some" { name } else { "" }),
+                    Line::from(kind),
+                    Line::from(cell(averages.map(|a| a.avg10), threshold)),
+                    Line::from(cell(averages.map(|a| a.avg60), threshold)),
+                    Line::from(cell(averages.map(|a| a.avg300), threshold)),
+                ]));
+            }
+        }
+
+        Table::new(
+            rows,
+            [
+                Constraint::Length(7),
+                Constraint::Length(4),
+                Constraint::Length(6),
+                Constraint::Length(6),
+                Constraint::Length(6),
+            ],
+        )
+        .header(
+            Row::new(vec!["", "", " avg10", " avg60", "avg300"])
+                .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+        )
+    }
+
+    /// Maximized PSI: the table plus avg10 history for each resource
+    pub fn render_detailed(snapshot: &SystemSnapshot, thresholds: &AlertSettings, area: Rect, buf: &mut Buffer) {
+        let block = Block::default()
+            .title(" Pressure Stall Information ")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::LightBlue));
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let Some(pressure) = snapshot.pressure() else {
+            Paragraph::new(Line::from(Span::styled(Self::UNAVAILABLE, Style::default().fg(Color::Gray))))
+                .wrap(Wrap { trim: true })
+                .render(inner, buf);
+            return;
+        };
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(8), Constraint::Min(0)])
+            .split(inner);
+        Self::table(pressure, thresholds).render(chunks[0], buf);
+
+        // Percent of time stalled, so the chart scales to the worst value seen
+        let history = &snapshot.pressure_history;
+        let colors = [Color::Cyan, Color::Magenta, Color::Yellow];
+        type Series = (String, Color, Vec<(f64, f64)>);
+        let series: Vec<Series> = ["cpu", "memory", "io"]
+            .iter()
+            .zip(colors)
+            .flat_map(|(name, color)| {
+                let pick = move |full: bool| -> Vec<(f64, f64)> {
+                    history
+                        .iter()
+                        .enumerate()
+                        .filter_map(|(i, sample)| {
+                            let resource = sample.resources().into_iter().find(|(n, _)| n == name)?.1?;
+                            let averages = if full { resource.full? } else { resource.some };
+                            Some((i as f64, averages.avg10 as f64))
+                        })
+                        .collect()
+                };
+                [
+                    (format!("{} some", name), color, pick(false)),
+                    (format!("{} full", name), color, pick(true)),
+                ]
+            })
+            .filter(|(_, _, data)| !data.is_empty())
+            .collect();
+
+        let top = series
+            .iter()
+            .flat_map(|(_, _, data)| data.iter().map(|(_, value)| *value))
+            .fold(thresholds.psi_some_avg10 as f64, f64::max)
+            .max(1.0);
+
+        let datasets: Vec<Dataset> = series
+            .iter()
+            .map(|(name, color, data)| {
+                let style = if name.ends_with("full") {
+                    Style::default().fg(*color).add_modifier(Modifier::DIM)
+                } else {
+                    Style::default().fg(*color)
+                };
+                Dataset::default()
+                    .name(name.as_str())
+                    .marker(symbols::Marker::Braille)
+                    .graph_type(GraphType::Line)
+                    .style(style)
+                    .data(data)
+            })
+            .collect();
+
+        let chart = Chart::new(datasets)
+            .x_axis(
+                Axis::default()
+                    .title("Time")
+                    .style(Style::default().fg(Color::Gray))
+                    .bounds([0.0, history.len().max(1) as f64]),
+            )
+            .y_axis(
+                Axis::default()
+                    .title("avg10 %")
+                    .style(Style::default().fg(Color::Gray))
+                    .bounds([0.0, top])
+                    .labels(vec![Span::raw("0"), Span::raw(format!("{:.1}", top))]),
+            )
+            .hidden_legend_constraints((Constraint::Ratio(1, 3), Constraint::Ratio(1, 1)));
+
+        chart.render(chunks[1], buf);
+    }
 }
 
 pub struct DiskWidget;
 
 impl DiskWidget {
-    pub fn render(monitor: &SystemMonitor, area: Rect, buf: &mut Buffer) {
-        let disks = monitor.disk_info();
+    /// Render the disk table and return the mount points of the visible rows, top to bottom
+    pub fn render(
+        snapshot: &SystemSnapshot,
+        area: Rect,
+        buf: &mut Buffer,
+        scroll_offset: usize,
+        selected: Option<&str>,
+    ) -> Vec<String> {
+        let disks = &snapshot.disks;
         
         if disks.is_empty() {
             let empty_text = Paragraph::new("No disk information available")
@@ -230,22 +1198,34 @@ impl DiskWidget {
                         .border_style(Style::default().fg(Color::Yellow)),
                 );
             empty_text.render(area, buf);
-            return;
+            return Vec::new();
         }
 
-        let rows: Vec<Row> = disks
+        let visible: Vec<_> = disks
+            .iter()
+            .skip(scroll_offset.min(disks.len().saturating_sub(1)))
+            .take(area.height.saturating_sub(TABLE_FIRST_ROW_OFFSET + 1) as usize)
+            .collect();
+
+        let rows: Vec<Row> = visible
             .iter()
             .map(|disk| {
                 let used_gb = disk.used_space as f64 / 1_073_741_824.0;
                 let total_gb = disk.total_space as f64 / 1_073_741_824.0;
                 
-                Row::new(vec![
+                let row = Row::new(vec![
                     disk.mount_point.clone(),
                     disk.file_system.clone(),
                     format!("{:.1} GB", total_gb),
                     format!("{:.1} GB", used_gb),
                     format!("{:.1}%", disk.usage_percent),
-                ])
+                ]);
+
+                if selected == Some(disk.mount_point.as_str()) {
+                    row.style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD))
+                } else {
+                    row
+                }
             })
             .collect();
 
@@ -272,56 +1252,397 @@ impl DiskWidget {
         );
 
         table.render(area, buf);
+
+        visible.iter().map(|disk| disk.mount_point.clone()).collect()
+    }
+
+    /// Maximized disk view: device names, free space and a usage bar per disk
+    pub fn render_detailed(snapshot: &SystemSnapshot, area: Rect, buf: &mut Buffer) {
+        let disks = &snapshot.disks;
+
+        let rows: Vec<Row> = disks
+            .iter()
+            .map(|disk| {
+                let bar_width = 20;
+                let filled = ((disk.usage_percent / 100.0) * bar_width as f32).round() as usize;
+                let bar = format!(
+                    "{}{}",
+                    "█".repeat(filled.min(bar_width)),
+                    "░".repeat(bar_width - filled.min(bar_width))
+                );
+
+                Row::new(vec![
+                    Span::raw(disk.name.clone()),
+                    Span::raw(disk.mount_point.clone()),
+                    Span::raw(disk.file_system.clone()),
+                    Span::raw(format_bytes(disk.total_space)),
+                    Span::raw(format_bytes(disk.used_space)),
+                    Span::raw(format_bytes(disk.available_space)),
+                    Span::styled(
+                        format!("{} {:>5.1}%", bar, disk.usage_percent),
+                        Style::default().fg(usage_color(disk.usage_percent, 75.0, 90.0)),
+                    ),
+                ])
+            })
+            .collect();
+
+        let table = Table::new(
+            rows,
+            &[
+                Constraint::Min(15),    // Device
+                Constraint::Min(15),    // Mount point
+                Constraint::Length(10), // File system
+                Constraint::Length(10), // Total
+                Constraint::Length(10), // Used
+                Constraint::Length(10), // Available
+                Constraint::Length(28), // Usage bar
+            ],
+        )
+        .header(
+            Row::new(vec!["Device", "Mount", "FS", "Total", "Used", "Free", "Usage"])
+                .style(Style::default().add_modifier(Modifier::BOLD))
+                .bottom_margin(1),
+        )
+        .block(
+            Block::default()
+                .title(format!(" Disk Usage ({} disks) ", disks.len()))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Yellow)),
+        );
+
+        table.render(area, buf);
     }
 }
 
 pub struct ProcessWidget;
 
 impl ProcessWidget {
-    pub fn render(monitor: &SystemMonitor, area: Rect, buf: &mut Buffer, scroll_offset: usize) {
-        let mut processes: Vec<_> = monitor.system().processes().iter().collect();
-        
-        // Sort by CPU usage (descending)
-        processes.sort_by(|a, b| b.1.cpu_usage().partial_cmp(&a.1.cpu_usage()).unwrap_or(std::cmp::Ordering::Equal));
+    /// Render a process list under `title` and return the PID of every process in display order
+    pub fn render(
+        mut processes: Vec<&ProcessInfo>,
+        title: &str,
+        area: Rect,
+        buf: &mut Buffer,
+        scroll_offset: usize,
+        selected: Option<u32>,
+        sort: (ProcessSort, bool),
+    ) -> Vec<u32> {
+        let (sort_column, descending) = sort;
+        // The collector already delivers CPU-descending order
+        if sort != (ProcessSort::Cpu, true) {
+            sort_processes(&mut processes, sort_column, descending);
+        }
         
-        let items: Vec<ListItem> = processes
+        let visible: Vec<_> = processes
             .iter()
             .skip(scroll_offset)
             .take(area.height.saturating_sub(2) as usize) // Account for border
-            .map(|(pid, process)| {
-                let memory_mb = process.memory() as f64 / 1_048_576.0; // Convert to MB
+            .collect();
+
+        let items: Vec<ListItem> = visible
+            .iter()
+            .map(|process| {
+                let memory_mb = process.memory as f64 / 1_048_576.0; // Convert to MB
                 
-                ListItem::new(Line::from(vec![
-                    Span::styled(format!("{:>8}", pid), Style::default().fg(Color::Cyan)),
+                let item = ListItem::new(Line::from(vec![
+                    Span::styled(format!("{:>8}", process.pid), Style::default().fg(Color::Cyan)),
                     Span::raw("  "),
-                    Span::styled(format!("{:>6.1}%", process.cpu_usage()), 
+                    Span::styled(format!("{:>6.1}%", process.cpu_usage), 
                         Style::default().fg(Color::Green)),
                     Span::raw("  "),
                     Span::styled(format!("{:>8.1}M", memory_mb), 
                         Style::default().fg(Color::Yellow)),
                     Span::raw("  "),
-                    Span::raw(process.name()),
-                ]))
+                    Span::raw(process.name.clone()),
+                ]));
+
+                if selected == Some(process.pid) {
+                    item.style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD))
+                } else {
+                    item
+                }
             })
             .collect();
 
         let list = List::new(items)
             .block(
                 Block::default()
-                    .title(format!(" Processes ({}) ", processes.len()))
+                    .title(format!(
+                        " {} ({}) — by {} {} ",
+                        title,
+                        processes.len(),
+                        sort_column.name(),
+                        if descending { "▼" } else { "▲" }
+                    ))
                     .borders(Borders::ALL)
                     .border_style(Style::default().fg(Color::Green)),
             );
 
         list.render(area, buf);
+
+        processes.iter().map(|process| process.pid).collect()
+    }
+}
+
+pub struct CgroupWidget;
+
+impl CgroupWidget {
+    /// Render the cgroup table and return the paths of every group in display order
+    pub fn render(
+        snapshot: &SystemSnapshot,
+        area: Rect,
+        buf: &mut Buffer,
+        scroll_offset: usize,
+        selected: Option<&str>,
+        sort: (CgroupSort, bool),
+    ) -> Vec<String> {
+        if snapshot.cgroups.is_empty() {
+            let empty_text = Paragraph::new("cgroup v2 is not mounted; per-group usage is unavailable")
+                .block(
+                    Block::default()
+                        .title(" Cgroups ")
+                        .borders(Borders::ALL)
+                        .border_style(Style::default().fg(Color::Magenta)),
+                );
+            empty_text.render(area, buf);
+            return Vec::new();
+        }
+
+        let mut groups: Vec<_> = snapshot.cgroups.iter().collect();
+        let (sort_column, descending) = sort;
+        sort_cgroups(&mut groups, sort_column, descending);
+
+        let rows: Vec<Row> = groups
+            .iter()
+            .skip(scroll_offset)
+            .take(area.height.saturating_sub(TABLE_FIRST_ROW_OFFSET + 1) as usize)
+            .map(|group| {
+                let optional_bytes = |bytes: Option<u64>| bytes.map(format_bytes).unwrap_or_else(|| "-".to_string());
+                let row = Row::new(vec![
+                    Cell::from(group.path.clone()),
+                    Cell::from(format!("{:.1}%", group.cpu_usage)).style(Style::default().fg(Color::Green)),
+                    Cell::from(optional_bytes(group.memory_current)).style(Style::default().fg(Color::Yellow)),
+                    Cell::from(optional_bytes(group.memory_max)),
+                    Cell::from(format!("{}/s", format_bytes(group.io_read_rate as u64))),
+                    Cell::from(format!("{}/s", format_bytes(group.io_write_rate as u64))),
+                    Cell::from(group.pids_current.map_or_else(|| "-".to_string(), |pids| pids.to_string())),
+                ]);
+
+                if selected == Some(group.path.as_str()) {
+                    row.style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD))
+                } else {
+                    row
+                }
+            })
+            .collect();
+
+        let table = Table::new(
+            rows,
+            &[
+                Constraint::Min(20),    // Path
+                Constraint::Length(8),  // CPU
+                Constraint::Length(10), // Memory
+                Constraint::Length(10), // Limit
+                Constraint::Length(11), // Read
+                Constraint::Length(11), // Write
+                Constraint::Length(6),  // Pids
+            ],
+        )
+        .header(
+            Row::new(vec!["Path", "CPU", "Memory", "Limit", "Read", "Write", "Pids"])
+                .style(Style::default().add_modifier(Modifier::BOLD))
+                .bottom_margin(1),
+        )
+        .block(
+            Block::default()
+                .title(format!(
+                    " Cgroups ({}) — by {} {} ",
+                    groups.len(),
+                    sort_column.name(),
+                    if descending { "▼" } else { "▲" }
+                ))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Magenta)),
+        );
+
+        table.render(area, buf);
+
+        groups.iter().map(|group| group.path.clone()).collect()
+    }
+
+    /// One-line totals for a group, shown above its member processes
+    pub fn render_summary(group: &CgroupStats, area: Rect, buf: &mut Buffer) {
+        let memory = match (group.memory_current, group.memory_max) {
+            (Some(current), Some(max)) => format!("{} / {}", format_bytes(current), format_bytes(max)),
+            (Some(current), None) => format!("{} (no limit)", format_bytes(current)),
+            _ => "-".to_string(),
+        };
+        let line = Line::from(vec![
+            Span::styled("CPU ", Style::default().fg(Color::Gray)),
+            Span::styled(format!("{:.1}%", group.cpu_usage), Style::default().fg(Color::Green)),
+            Span::styled("  Memory ", Style::default().fg(Color::Gray)),
+            Span::styled(memory, Style::default().fg(Color::Yellow)),
+            Span::styled("  IO ", Style::default().fg(Color::Gray)),
+            Span::raw(format!(
+                "{}/s read, {}/s write",
+                format_bytes(group.io_read_rate as u64),
+                format_bytes(group.io_write_rate as u64)
+            )),
+            Span::styled("  Pids ", Style::default().fg(Color::Gray)),
+            Span::raw(group.pids_current.map_or_else(|| "-".to_string(), |pids| pids.to_string())),
+        ]);
+
+        Paragraph::new(line)
+            .block(
+                Block::default()
+                    .title(format!(" {} ", group.name()))
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::Magenta)),
+            )
+            .render(area, buf);
+    }
+}
+
+/// The last `width` values as block characters, scaled to `max`
+fn sparkline_text(values: &[f64], max: f64, width: usize) -> String {
+    const LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    values
+        .iter()
+        .skip(values.len().saturating_sub(width))
+        .map(|value| {
+            let level = (value / max.max(f64::EPSILON) * (LEVELS.len() - 1) as f64).round();
+            LEVELS[(level.max(0.0) as usize).min(LEVELS.len() - 1)]
+        })
+        .collect()
+}
+
+pub struct FleetWidget;
+
+impl FleetWidget {
+    /// Render one row per host from `scroll_offset` on; `active` is the host
+    /// the other tabs show
+    pub fn render(
+        hosts: &[HostStatus],
+        active: usize,
+        selected: usize,
+        scroll_offset: usize,
+        settings: (&AlertSettings, bool),
+        area: Rect,
+        buf: &mut Buffer,
+    ) {
+        let (thresholds, normalize_load) = settings;
+        const HISTORY_WIDTH: usize = 30;
+
+        let rows: Vec<Row> = hosts
+            .iter()
+            .enumerate()
+            .skip(scroll_offset)
+            .take(area.height.saturating_sub(TABLE_FIRST_ROW_OFFSET + 1) as usize)
+            .map(|(index, host)| {
+                let snapshot = &host.snapshot;
+                let marker = if index == active { "▶" } else { " " };
+                let (status, status_color) = if host.online {
+                    ("online", Color::Green)
+                } else if host.is_connecting() {
+                    ("connecting", Color::Yellow)
+                } else {
+                    ("offline", Color::Red)
+                };
+
+                let mut cells = vec![
+                    Cell::from(format!("{} {}", marker, host.name)),
+                    Cell::from(host.address.clone()),
+                    Cell::from(status).style(Style::default().fg(status_color)),
+                ];
+
+                if host.is_connecting() {
+                    cells.extend((0..6).map(|_| Cell::from("-")));
+                } else {
+                    let load = if normalize_load {
+                        snapshot.load_average.per_core(snapshot.cpu_count())
+                    } else {
+                        snapshot.load_average
+                    };
+                    let disk = snapshot.fullest_disk().map(|disk| disk.usage_percent);
+                    let alerts = evaluate_alerts(snapshot, thresholds);
+                    let (alert_text, alert_color) = match alerts.first().map(|alert| alert.level) {
+                        None => ("ok".to_string(), Color::Green),
+                        Some(AlertLevel::Warning) => (format!("⚠ {}", alerts.len()), Color::Yellow),
+                        Some(AlertLevel::Critical) => (format!("⚠ {}", alerts.len()), Color::Red),
+                    };
+                    let history: Vec<f64> = snapshot.cpu_history.iter().map(|data| data.usage as f64).collect();
+
+                    cells.extend([
+                        Cell::from(format!("{:.1}%", snapshot.cpu_usage))
+                            .style(Style::default().fg(usage_color(snapshot.cpu_usage, 50.0, 80.0))),
+                        Cell::from(format!("{:.1}%", snapshot.memory_usage_percent()))
+                            .style(Style::default().fg(usage_color(snapshot.memory_usage_percent(), 60.0, 85.0))),
+                        Cell::from(format!("{:.2}", load.one)),
+                        Cell::from(disk.map_or_else(|| "-".to_string(), |percent| format!("{:.0}%", percent))).style(
+                            Style::default().fg(usage_color(
+                                disk.unwrap_or(0.0),
+                                thresholds.disk_warning_percent,
+                                thresholds.disk_critical_percent,
+                            )),
+                        ),
+                        Cell::from(alert_text).style(Style::default().fg(alert_color)),
+                        Cell::from(sparkline_text(&history, 100.0, HISTORY_WIDTH))
+                            .style(Style::default().fg(Color::Cyan)),
+                    ]);
+                }
+
+                let mut row = Row::new(cells);
+                if !host.online {
+                    // Stale figures from before the link dropped
+                    row = row.style(Style::default().fg(Color::DarkGray));
+                }
+                if index == selected {
+                    row = row.style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD));
+                }
+                row
+            })
+            .collect();
+
+        let mut title = format!(" Fleet ({} hosts) ", hosts.len());
+        if hosts.len() <= 1 {
+            title = " Fleet — add agents under [[fleet.hosts]] in the config ".to_string();
+        }
+
+        let table = Table::new(
+            rows,
+            &[
+                Constraint::Min(14),    // Host
+                Constraint::Length(22), // Address
+                Constraint::Length(10), // Status
+                Constraint::Length(7),  // CPU
+                Constraint::Length(7),  // Memory
+                Constraint::Length(6),  // Load
+                Constraint::Length(5),  // Disk
+                Constraint::Length(6),  // Alerts
+                Constraint::Length(HISTORY_WIDTH as u16), // CPU history
+            ],
+        )
+        .header(
+            Row::new(vec!["Host", "Address", "Status", "CPU", "Memory", "Load", "Disk", "Alerts", "CPU history"])
+                .style(Style::default().add_modifier(Modifier::BOLD))
+                .bottom_margin(1),
+        )
+        .block(
+            Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Cyan)),
+        );
+
+        table.render(area, buf);
     }
 }
 
 pub struct NetworkWidget;
 
 impl NetworkWidget {
-    pub fn render(monitor: &SystemMonitor, area: Rect, buf: &mut Buffer) {
-        let networks = monitor.network_info();
+    pub fn render(snapshot: &SystemSnapshot, area: Rect, buf: &mut Buffer) {
+        let networks = &snapshot.networks;
         
         if networks.is_empty() {
             let empty_text = Paragraph::new("No network information available")
@@ -1,7 +1,5 @@
 // Utility helper functions
 
-use std::fmt;
-
 /// Convert bytes to human readable format
 pub fn format_bytes(bytes: u64) -> String {
     const UNITS: &[&str] = &["B", "KB", "MB", "GB", "TB"];